@@ -0,0 +1,46 @@
+//! Compares converting narrow text of a nullable column, whose values are appended as options,
+//! with converting the same text of a non nullable column, whose values are appended as is.
+
+use arrow_odbc::{
+    odbc_api::buffers::{AnySlice, ColumnBuffer, TextColumn},
+    MappingErrorPolicy, NarrowText, ReadStrategy, ValueErrors,
+};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+/// Number of rows in each batch converted
+const BATCH_SIZE: usize = 10_000;
+
+/// Maximum length of each value in bytes
+const MAX_STR_LEN: usize = 32;
+
+/// A buffer holding `BATCH_SIZE` values, which cycle through `values`.
+fn column_of(values: &[&str]) -> TextColumn<u8> {
+    let mut column = TextColumn::new(BATCH_SIZE, MAX_STR_LEN);
+    for index in 0..BATCH_SIZE {
+        column.set_value(index, Some(values[index % values.len()].as_bytes()));
+    }
+    column
+}
+
+fn narrow_text(c: &mut Criterion) {
+    let column = column_of(&["DE-BER-0001", "US-NYC-0002", "Grüße aus Berlin"]);
+    let policy = MappingErrorPolicy::Fail;
+    let errors = ValueErrors::new(&policy, 0);
+
+    let mut group = c.benchmark_group("narrow_text");
+    group.throughput(Throughput::Elements(BATCH_SIZE as u64));
+    for (name, nullable) in [("nullable", true), ("non_nullable", false)] {
+        let strategy = NarrowText::<i32>::new(MAX_STR_LEN, nullable, None, false);
+        group.bench_with_input(BenchmarkId::from_parameter(name), &column, |b, column| {
+            b.iter(|| {
+                strategy
+                    .fill_arrow_array(AnySlice::Text(column.view(BATCH_SIZE)), &errors)
+                    .unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, narrow_text);
+criterion_main!(benches);
@@ -0,0 +1,76 @@
+//! Compares converting the values of nullable columns with converting those of `NOT NULL`
+//! columns, which are fetched into buffers without indicators where possible, and appended
+//! without computing a validity bitmap.
+
+use arrow::datatypes::Int32Type;
+use arrow_odbc::{
+    odbc_api::buffers::{AnyBuffer, AnySlice, BufferDesc, ColumnBuffer, TextColumn},
+    MapOdbcToArrow, MappingErrorPolicy, NarrowText, ReadStrategy, ValueErrors,
+};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+/// Number of rows in each batch converted
+const BATCH_SIZE: usize = 10_000;
+
+/// Maximum length of each text value in bytes
+const MAX_STR_LEN: usize = 32;
+
+/// An integer buffer holding `BATCH_SIZE` values, with indicators if `nullable`.
+fn int_column(nullable: bool) -> AnyBuffer {
+    let values = (0..BATCH_SIZE as i32).map(|value| value * 7);
+    let mut buffer = AnyBuffer::from_desc(BATCH_SIZE, BufferDesc::I32 { nullable });
+    match &mut buffer {
+        AnyBuffer::I32(column) => column.iter_mut().zip(values).for_each(|(v, i)| *v = i),
+        AnyBuffer::NullableI32(column) => column.writer_n(BATCH_SIZE).write(values.map(Some)),
+        _ => unreachable!(),
+    }
+    buffer
+}
+
+/// A text buffer holding `BATCH_SIZE` values.
+fn varchar_column() -> TextColumn<u8> {
+    let values = ["DE-BER-0001", "US-NYC-0002", "JP-TYO-0003"];
+    let mut column = TextColumn::new(BATCH_SIZE, MAX_STR_LEN);
+    for index in 0..BATCH_SIZE {
+        column.set_value(index, Some(values[index % values.len()].as_bytes()));
+    }
+    column
+}
+
+fn not_null(c: &mut Criterion) {
+    let policy = MappingErrorPolicy::Fail;
+    let errors = ValueErrors::new(&policy, 0);
+
+    let mut group = c.benchmark_group("int");
+    group.throughput(Throughput::Elements(BATCH_SIZE as u64));
+    for (name, nullable) in [("nullable", true), ("not_null", false)] {
+        let strategy = Int32Type::identical(nullable);
+        let column = int_column(nullable);
+        group.bench_with_input(BenchmarkId::from_parameter(name), &column, |b, column| {
+            b.iter(|| {
+                strategy
+                    .fill_arrow_array(column.view(BATCH_SIZE), &errors)
+                    .unwrap()
+            })
+        });
+    }
+    group.finish();
+
+    let column = varchar_column();
+    let mut group = c.benchmark_group("varchar");
+    group.throughput(Throughput::Elements(BATCH_SIZE as u64));
+    for (name, nullable) in [("nullable", true), ("not_null", false)] {
+        let strategy = NarrowText::<i32>::new(MAX_STR_LEN, nullable, None, false);
+        group.bench_with_input(BenchmarkId::from_parameter(name), &column, |b, column| {
+            b.iter(|| {
+                strategy
+                    .fill_arrow_array(AnySlice::Text(column.view(BATCH_SIZE)), &errors)
+                    .unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, not_null);
+criterion_main!(benches);
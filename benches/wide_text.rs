@@ -0,0 +1,60 @@
+//! Compares converting wide text consisting only of ASCII, which takes the fast path, with
+//! converting multilingual text, which needs to be decoded as UTF-16.
+
+use arrow_odbc::{
+    odbc_api::buffers::{AnySlice, ColumnBuffer, TextColumn},
+    MappingErrorPolicy, ReadStrategy, ValueErrors, WideText,
+};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+/// Number of rows in each batch converted
+const BATCH_SIZE: usize = 10_000;
+
+/// Maximum length of each value in UTF-16 code units
+const MAX_STR_LEN: usize = 32;
+
+/// A buffer holding `BATCH_SIZE` values, which cycle through `values`.
+fn column_of(values: &[&str]) -> TextColumn<u16> {
+    let mut column = TextColumn::new(BATCH_SIZE, MAX_STR_LEN);
+    for index in 0..BATCH_SIZE {
+        let utf16: Vec<u16> = values[index % values.len()].encode_utf16().collect();
+        column.set_value(index, Some(&utf16));
+    }
+    column
+}
+
+fn wide_text(c: &mut Criterion) {
+    let workloads = [
+        (
+            "ascii",
+            column_of(&["DE-BER-0001", "US-NYC-0002", "JP-TYO-0003"]),
+        ),
+        (
+            "multilingual",
+            column_of(&["Grüße aus Berlin", "東京からこんにちは", "Привет"]),
+        ),
+        (
+            "mostly_ascii",
+            column_of(&["DE-BER-0001", "US-NYC-0002", "JP-TYO-0003", "Grüße"]),
+        ),
+    ];
+    let strategy = WideText::<i32>::new(MAX_STR_LEN, true, false, false);
+    let policy = MappingErrorPolicy::Fail;
+    let errors = ValueErrors::new(&policy, 0);
+
+    let mut group = c.benchmark_group("wide_text");
+    group.throughput(Throughput::Elements(BATCH_SIZE as u64));
+    for (name, column) in &workloads {
+        group.bench_with_input(BenchmarkId::from_parameter(name), column, |b, column| {
+            b.iter(|| {
+                strategy
+                    .fill_arrow_array(AnySlice::WText(column.view(BATCH_SIZE)), &errors)
+                    .unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, wide_text);
+criterion_main!(benches);
@@ -0,0 +1,249 @@
+use std::convert::TryInto;
+
+use arrow::datatypes::{DataType, Field, Schema};
+use odbc_api::{Connection, DataType as OdbcDataType, ResultSetMetadata};
+
+use crate::{ColumnFailure, Error};
+
+/// Adjusts how columns are mapped for the quirks of a specific database management system, which
+/// can not be deduced from the metadata of a single column. Use
+/// [`crate::OdbcReaderBuilder::with_backend_profile`] to apply it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BackendProfile {
+    /// Map columns solely based on the metadata reported by the driver.
+    #[default]
+    Generic,
+    /// Firebird and its predecessor Interbase store `NUMERIC` and `DECIMAL` columns as `SMALLINT`,
+    /// `INTEGER` or `BIGINT` with a scale, and the driver reports the backing integer type. These
+    /// columns are inferred as `Decimal128` with the reported scale, rather than as integers.
+    /// Values of fixed size `CHAR` columns are padded to their length with spaces, which are
+    /// trimmed.
+    Firebird,
+    /// MySQL and MariaDB declare boolean columns as `TINYINT(1)`, which are inferred as `Boolean`
+    /// (see [`crate::OdbcReaderBuilder::with_tiny_int_1_as_boolean`]).
+    MySql,
+}
+
+impl BackendProfile {
+    /// Chooses the profile by the database management system name reported by the driver. E.g.
+    /// `Firebird` or `InterBase` choose [`BackendProfile::Firebird`], `MySQL` or `MariaDB` choose
+    /// [`BackendProfile::MySql`]. Any other name chooses [`BackendProfile::Generic`].
+    pub fn from_dbms_name(dbms_name: &str) -> Self {
+        let dbms_name = dbms_name.to_ascii_lowercase();
+        if dbms_name.contains("firebird") || dbms_name.contains("interbase") {
+            BackendProfile::Firebird
+        } else if dbms_name.contains("mysql") || dbms_name.contains("mariadb") {
+            BackendProfile::MySql
+        } else {
+            BackendProfile::Generic
+        }
+    }
+
+    /// Chooses the profile for the database management system `connection` is connected to. See
+    /// [`Self::from_dbms_name`].
+    pub fn detect(connection: &Connection<'_>) -> Result<Self, odbc_api::Error> {
+        let dbms_name = connection.database_management_system_name()?;
+        Ok(Self::from_dbms_name(&dbms_name))
+    }
+
+    /// `true` if trailing spaces are removed from the values of fixed size text columns.
+    pub(crate) fn trims_fixed_size_text(self) -> bool {
+        self == BackendProfile::Firebird
+    }
+
+    /// `true` if tiny integer columns with a display width of one are inferred as `Boolean`.
+    pub(crate) fn infers_tiny_int_1_as_boolean(self) -> bool {
+        self == BackendProfile::MySql
+    }
+
+    /// Adjusts the schema inferred from the metadata of the result set.
+    pub(crate) fn adjust_schema(
+        self,
+        schema: Schema,
+        metadata: &mut impl ResultSetMetadata,
+    ) -> Result<Schema, Error> {
+        match self {
+            BackendProfile::Generic | BackendProfile::MySql => Ok(schema),
+            BackendProfile::Firebird => scaled_integers_as_decimal(schema, metadata),
+        }
+    }
+}
+
+/// Replaces the fields of integer columns with a scale by `Decimal128` fields. The text the driver
+/// converts these values to contains the decimal point, so they are fetched like any other decimal.
+fn scaled_integers_as_decimal(
+    schema: Schema,
+    metadata: &mut impl ResultSetMetadata,
+) -> Result<Schema, Error> {
+    let mut fields = Vec::new();
+    for (index, field) in schema.fields().iter().enumerate() {
+        let col_index = (index + 1).try_into().unwrap();
+        let describe_error = |cause| {
+            ColumnFailure::FailedToDescribeColumn(cause)
+                .into_crate_error(field.name().clone(), index)
+        };
+        let sql_type = metadata.col_data_type(col_index).map_err(describe_error)?;
+        // Only integer columns are asked for their scale and precision.
+        let data_type = if max_precision_of_integer(sql_type).is_some() {
+            let scale = metadata.col_scale(col_index).map_err(describe_error)?;
+            let precision = metadata.col_precision(col_index).map_err(describe_error)?;
+            scaled_integer_as_decimal(sql_type, scale, precision)
+        } else {
+            None
+        };
+        let field = match data_type {
+            Some(data_type) => Field::new(field.name(), data_type, field.is_nullable())
+                .with_metadata(field.metadata().clone()),
+            None => field.as_ref().clone(),
+        };
+        fields.push(field);
+    }
+    Ok(Schema::new_with_metadata(fields, schema.metadata().clone()))
+}
+
+/// Replaces the fields of tiny integer columns with a display width of one by `Boolean` fields.
+pub(crate) fn tiny_int_1_as_boolean(
+    schema: Schema,
+    metadata: &mut impl ResultSetMetadata,
+) -> Result<Schema, Error> {
+    let mut fields = Vec::new();
+    for (index, field) in schema.fields().iter().enumerate() {
+        let col_index = (index + 1).try_into().unwrap();
+        let describe_error = |cause| {
+            ColumnFailure::FailedToDescribeColumn(cause)
+                .into_crate_error(field.name().clone(), index)
+        };
+        // Only columns inferred as 8 Bit integers are asked for their SQL type and display size.
+        let is_tiny_int_1 = matches!(field.data_type(), DataType::Int8 | DataType::UInt8) && {
+            let sql_type = metadata.col_data_type(col_index).map_err(describe_error)?;
+            let display_size = metadata
+                .col_display_size(col_index)
+                .map_err(describe_error)?;
+            is_tiny_int_1(sql_type, display_size)
+        };
+        let field = if is_tiny_int_1 {
+            Field::new(field.name(), DataType::Boolean, field.is_nullable())
+                .with_metadata(field.metadata().clone())
+        } else {
+            field.as_ref().clone()
+        };
+        fields.push(field);
+    }
+    Ok(Schema::new_with_metadata(fields, schema.metadata().clone()))
+}
+
+/// `true` for a column of `sql_type` with `display_size`, which is declared as `TINYINT(1)`.
+fn is_tiny_int_1(sql_type: OdbcDataType, display_size: isize) -> bool {
+    sql_type == OdbcDataType::TinyInt && display_size == 1
+}
+
+/// Maximum precision of a numeric backed by `sql_type`, if it is an integer type.
+fn max_precision_of_integer(sql_type: OdbcDataType) -> Option<isize> {
+    match sql_type {
+        OdbcDataType::SmallInt => Some(4),
+        OdbcDataType::Integer => Some(9),
+        OdbcDataType::BigInt => Some(18),
+        _ => None,
+    }
+}
+
+/// `Decimal128` type for a column of `sql_type` with the `scale` and `precision` reported by the
+/// driver, if it is an integer backing a numeric. `None` if the column is to be inferred as usual.
+fn scaled_integer_as_decimal(
+    sql_type: OdbcDataType,
+    scale: isize,
+    precision: isize,
+) -> Option<DataType> {
+    let max_precision = max_precision_of_integer(sql_type)?;
+    if scale <= 0 || scale > max_precision {
+        return None;
+    }
+    // Fall back to the maximum precision of the backing integer, if the driver reports no usable
+    // precision for the numeric.
+    let precision = if precision >= scale && precision <= max_precision {
+        precision
+    } else {
+        max_precision
+    };
+    Some(DataType::Decimal128(precision as u8, scale as i8))
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::datatypes::DataType;
+    use odbc_api::DataType as OdbcDataType;
+
+    use super::{is_tiny_int_1, scaled_integer_as_decimal, BackendProfile};
+
+    #[test]
+    fn profile_from_dbms_name() {
+        assert_eq!(
+            BackendProfile::Firebird,
+            BackendProfile::from_dbms_name("Firebird")
+        );
+        assert_eq!(
+            BackendProfile::Firebird,
+            BackendProfile::from_dbms_name("InterBase")
+        );
+        assert_eq!(
+            BackendProfile::MySql,
+            BackendProfile::from_dbms_name("MySQL")
+        );
+        assert_eq!(
+            BackendProfile::MySql,
+            BackendProfile::from_dbms_name("MariaDB")
+        );
+        assert_eq!(
+            BackendProfile::Generic,
+            BackendProfile::from_dbms_name("Microsoft SQL Server")
+        );
+    }
+
+    #[test]
+    fn tiny_int_with_display_width_one_is_boolean() {
+        assert!(is_tiny_int_1(OdbcDataType::TinyInt, 1));
+    }
+
+    #[test]
+    fn wider_tiny_int_is_no_boolean() {
+        assert!(!is_tiny_int_1(OdbcDataType::TinyInt, 4));
+        assert!(!is_tiny_int_1(OdbcDataType::SmallInt, 1));
+    }
+
+    #[test]
+    fn numeric_backed_by_integer() {
+        // NUMERIC(9,2) of Firebird, which the driver reports as INTEGER with scale 2
+        let data_type = scaled_integer_as_decimal(OdbcDataType::Integer, 2, 9);
+
+        assert_eq!(Some(DataType::Decimal128(9, 2)), data_type);
+    }
+
+    #[test]
+    fn precision_falls_back_to_maximum_of_backing_integer() {
+        // Precisions smaller than the scale, or too large for the backing integer, are unusable
+        let too_small = scaled_integer_as_decimal(OdbcDataType::SmallInt, 2, 0);
+        let too_large = scaled_integer_as_decimal(OdbcDataType::BigInt, 4, 19);
+
+        assert_eq!(Some(DataType::Decimal128(4, 2)), too_small);
+        assert_eq!(Some(DataType::Decimal128(18, 4)), too_large);
+    }
+
+    #[test]
+    fn integer_without_scale() {
+        let data_type = scaled_integer_as_decimal(OdbcDataType::Integer, 0, 10);
+
+        assert_eq!(None, data_type);
+    }
+
+    #[test]
+    fn numeric_which_is_no_integer() {
+        let sql_type = OdbcDataType::Numeric {
+            precision: 9,
+            scale: 2,
+        };
+
+        let data_type = scaled_integer_as_decimal(sql_type, 2, 9);
+
+        assert_eq!(None, data_type);
+    }
+}
@@ -0,0 +1,152 @@
+use std::sync::Arc;
+
+use arrow::{
+    compute::concat_batches,
+    datatypes::{DataType, Field, Schema, SchemaRef},
+    record_batch::RecordBatch,
+};
+use odbc_api::{handles::StatementImpl, Connection, CursorImpl};
+
+use crate::{Error, OdbcReaderBuilder};
+
+/// Names of the columns in the result set of `SQLTables`, in order.
+pub const TABLES_COLUMNS: [&str; 5] = [
+    "TABLE_CAT",
+    "TABLE_SCHEM",
+    "TABLE_NAME",
+    "TABLE_TYPE",
+    "REMARKS",
+];
+
+/// Names of the columns in the result set of `SQLColumns`, in order.
+pub const COLUMNS_COLUMNS: [&str; 18] = [
+    "TABLE_CAT",
+    "TABLE_SCHEM",
+    "TABLE_NAME",
+    "COLUMN_NAME",
+    "DATA_TYPE",
+    "TYPE_NAME",
+    "COLUMN_SIZE",
+    "BUFFER_LENGTH",
+    "DECIMAL_DIGITS",
+    "NUM_PREC_RADIX",
+    "NULLABLE",
+    "REMARKS",
+    "COLUMN_DEF",
+    "SQL_DATA_TYPE",
+    "SQL_DATETIME_SUB",
+    "CHAR_OCTET_LENGTH",
+    "ORDINAL_POSITION",
+    "IS_NULLABLE",
+];
+
+/// What to do if a catalog function like [`fetch_tables`] finds no rows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmptyCatalogResult {
+    /// Return a record batch with zero rows and the standard schema of the catalog function.
+    EmptyBatch,
+    /// Fail with [`Error::EmptyCatalogResult`].
+    Error,
+}
+
+/// Arrow schema of the result of [`fetch_tables`]. Column names are [`TABLES_COLUMNS`], all
+/// columns are nullable `Utf8`.
+pub fn tables_schema() -> Schema {
+    Schema::new(
+        TABLES_COLUMNS
+            .iter()
+            .map(|name| Field::new(*name, DataType::Utf8, true))
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// Arrow schema of the result of [`fetch_columns`]. Column names are [`COLUMNS_COLUMNS`]. Columns
+/// defined as `Smallint` or `Integer` by the ODBC standard are `Int16` and `Int32`, all others
+/// `Utf8`. All columns are nullable, even those the standard defines as not nullable, since not
+/// every driver adheres to it.
+pub fn columns_schema() -> Schema {
+    Schema::new(
+        COLUMNS_COLUMNS
+            .iter()
+            .map(|&name| {
+                let data_type = match name {
+                    "DATA_TYPE" | "DECIMAL_DIGITS" | "NUM_PREC_RADIX" | "NULLABLE"
+                    | "SQL_DATA_TYPE" | "SQL_DATETIME_SUB" => DataType::Int16,
+                    "COLUMN_SIZE" | "BUFFER_LENGTH" | "CHAR_OCTET_LENGTH" | "ORDINAL_POSITION" => {
+                        DataType::Int32
+                    }
+                    _ => DataType::Utf8,
+                };
+                Field::new(name, data_type, true)
+            })
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// Fetches the tables matching the search patterns using `SQLTables` into a single record batch
+/// with the schema [`tables_schema`]. An empty string matches everything.
+///
+/// # Example
+///
+/// ```no_run
+/// use arrow_odbc::{fetch_tables, odbc_api::Connection, EmptyCatalogResult, Error};
+///
+/// fn list_tables(connection: &Connection<'_>) -> Result<(), Error> {
+///     let on_empty = EmptyCatalogResult::EmptyBatch;
+///     let tables = fetch_tables(connection, "", "dbo", "", "TABLE", on_empty)?;
+///     println!("Found {} tables", tables.num_rows());
+///     Ok(())
+/// }
+/// ```
+pub fn fetch_tables(
+    connection: &Connection<'_>,
+    catalog_name: &str,
+    schema_name: &str,
+    table_name: &str,
+    table_type: &str,
+    on_empty: EmptyCatalogResult,
+) -> Result<RecordBatch, Error> {
+    let function = "SQLTables";
+    let cursor = connection
+        .tables(catalog_name, schema_name, table_name, table_type)
+        .map_err(|source| Error::CatalogFunction { function, source })?;
+    fetch_catalog(function, cursor, tables_schema(), on_empty)
+}
+
+/// Fetches the columns matching the search patterns using `SQLColumns` into a single record batch
+/// with the schema [`columns_schema`]. An empty string matches everything.
+pub fn fetch_columns(
+    connection: &Connection<'_>,
+    catalog_name: &str,
+    schema_name: &str,
+    table_name: &str,
+    column_name: &str,
+    on_empty: EmptyCatalogResult,
+) -> Result<RecordBatch, Error> {
+    let function = "SQLColumns";
+    let cursor = connection
+        .columns(catalog_name, schema_name, table_name, column_name)
+        .map_err(|source| Error::CatalogFunction { function, source })?;
+    fetch_catalog(function, cursor, columns_schema(), on_empty)
+}
+
+fn fetch_catalog(
+    function: &'static str,
+    cursor: CursorImpl<StatementImpl<'_>>,
+    schema: Schema,
+    on_empty: EmptyCatalogResult,
+) -> Result<RecordBatch, Error> {
+    let schema: SchemaRef = Arc::new(schema);
+    let reader = OdbcReaderBuilder::new()
+        .with_schema(schema.clone())
+        .build(cursor)?;
+    let batches = reader
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|source| Error::FetchingCatalog { function, source })?;
+    let batch = concat_batches(&schema, &batches)
+        .map_err(|source| Error::FetchingCatalog { function, source })?;
+    if batch.num_rows() == 0 && on_empty == EmptyCatalogResult::Error {
+        return Err(Error::EmptyCatalogResult { function });
+    }
+    Ok(batch)
+}
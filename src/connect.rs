@@ -0,0 +1,33 @@
+use odbc_api::{handles::State, Connection, ConnectionOptions, Environment};
+
+use crate::Error;
+
+/// SQLSTATE reported by drivers then the login timeout expired.
+const LOGIN_TIMEOUT_EXPIRED: State = State(*b"HYT00");
+
+/// Connects to a data source using a connection string, waiting at most `login_timeout_sec`
+/// seconds for the login to complete. This sets `SQL_ATTR_LOGIN_TIMEOUT` before connecting. `None`
+/// keeps the default of the driver, `Some(0)` waits indefinitely.
+///
+/// An expired timeout is reported as [`Error::LoginTimeout`], other failures as
+/// [`Error::Connecting`]. Be aware that some drivers ignore the login timeout, in which case
+/// connecting may still hang until the driver gives up on its own.
+///
+/// Setting `SQL_ATTR_CONNECTION_TIMEOUT`, or reading back either timeout, is not supported. The
+/// handle owned by [`Connection`] is neither exposed by `odbc-api` for borrowing, nor can a
+/// [`Connection`] be constructed from a raw handle, which had these attributes set beforehand.
+pub fn connect_with_login_timeout<'env>(
+    env: &'env Environment,
+    connection_string: &str,
+    login_timeout_sec: Option<u32>,
+) -> Result<Connection<'env>, Error> {
+    env.connect_with_connection_string(connection_string, ConnectionOptions { login_timeout_sec })
+        .map_err(|source| match (&source, login_timeout_sec) {
+            (odbc_api::Error::Diagnostics { record, .. }, Some(seconds))
+                if record.state == LOGIN_TIMEOUT_EXPIRED =>
+            {
+                Error::LoginTimeout { seconds }
+            }
+            _ => Error::Connecting { source },
+        })
+}
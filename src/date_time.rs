@@ -4,9 +4,10 @@ use arrow::{
     array::{Array, PrimitiveArray},
     datatypes::{
         ArrowPrimitiveType, Time32MillisecondType, Time64MicrosecondType, Time64NanosecondType,
+        TimeUnit,
     },
 };
-use chrono::{Datelike, NaiveDate, NaiveDateTime, Timelike};
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, Timelike};
 use odbc_api::{
     buffers::{AnySliceMut, BufferDesc, TextColumnSliceMut},
     sys::{Date, Time, Timestamp},
@@ -23,38 +24,79 @@ pub fn days_since_epoch(date: &Date) -> i32 {
     duration.num_days().try_into().unwrap()
 }
 
-pub fn seconds_since_epoch(from: &Timestamp) -> i64 {
-    let ndt = NaiveDate::from_ymd_opt(from.year as i32, from.month as u32, from.day as u32)
+/// How to handle fractional seconds which can not be represented by the arrow time unit a
+/// timestamp is fetched into, e.g. nanoseconds reported by the data source for a
+/// `Timestamp(Microsecond)` field.
+///
+/// Use [`crate::OdbcReaderBuilder::with_timestamp_rounding`] to choose it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TimestampRounding {
+    /// Drop the excess digits. `23:59:59.999999999` becomes `23:59:59.999999` in microseconds.
+    #[default]
+    Truncate,
+    /// Round to the nearest representable value, halfway values are rounded towards the later
+    /// point in time. `23:59:59.999999999` becomes `00:00:00.000000` of the next day in
+    /// microseconds.
+    Nearest,
+}
+
+pub fn seconds_since_epoch(from: &Timestamp, rounding: TimestampRounding) -> i64 {
+    units_since_epoch(from, 1, rounding)
+}
+
+pub fn ms_since_epoch(from: &Timestamp, rounding: TimestampRounding) -> i64 {
+    units_since_epoch(from, 1_000, rounding)
+}
+
+pub fn us_since_epoch(from: &Timestamp, rounding: TimestampRounding) -> i64 {
+    units_since_epoch(from, 1_000_000, rounding)
+}
+
+/// Converts the timestamp into units since epoch. The fraction of the timestamp is in nanoseconds
+/// and is rounded to `units_per_second`.
+fn units_since_epoch(from: &Timestamp, units_per_second: i64, rounding: TimestampRounding) -> i64 {
+    let seconds = NaiveDate::from_ymd_opt(from.year as i32, from.month as u32, from.day as u32)
         .unwrap()
         .and_hms_opt(from.hour as u32, from.minute as u32, from.second as u32)
-        .unwrap();
-    ndt.timestamp()
+        .unwrap()
+        .timestamp();
+    let ns_per_unit = 1_000_000_000 / units_per_second;
+    let fraction = from.fraction as i64;
+    let fraction = match rounding {
+        TimestampRounding::Truncate => fraction / ns_per_unit,
+        TimestampRounding::Nearest => (fraction + ns_per_unit / 2) / ns_per_unit,
+    };
+    seconds * units_per_second + fraction
 }
 
-pub fn ms_since_epoch(from: &Timestamp) -> i64 {
-    let ndt = NaiveDate::from_ymd_opt(from.year as i32, from.month as u32, from.day as u32)
-        .unwrap()
-        .and_hms_nano_opt(
-            from.hour as u32,
-            from.minute as u32,
-            from.second as u32,
-            from.fraction,
-        )
-        .unwrap();
-    ndt.timestamp_millis()
+/// Days since epoch of the day of a timestamp, discarding its time of day.
+pub fn days_since_epoch_of_day(from: &Timestamp) -> i32 {
+    days_since_epoch(&Date {
+        year: from.year,
+        month: from.month,
+        day: from.day,
+    })
 }
 
-pub fn us_since_epoch(from: &Timestamp) -> i64 {
-    let ndt = NaiveDate::from_ymd_opt(from.year as i32, from.month as u32, from.day as u32)
-        .unwrap()
-        .and_hms_nano_opt(
-            from.hour as u32,
-            from.minute as u32,
-            from.second as u32,
-            from.fraction,
-        )
-        .unwrap();
-    ndt.timestamp_nanos() / 1_000
+/// Days since epoch of a timestamp, which must be at midnight.
+pub fn days_since_epoch_of_midnight(from: &Timestamp) -> Result<i32, MappingError> {
+    if (from.hour, from.minute, from.second, from.fraction) != (0, 0, 0, 0) {
+        let value = NaiveDate::from_ymd_opt(from.year as i32, from.month as u32, from.day as u32)
+            .unwrap()
+            .and_hms_nano_opt(
+                from.hour as u32,
+                from.minute as u32,
+                from.second as u32,
+                from.fraction,
+            )
+            .unwrap();
+        return Err(MappingError::NotMidnight { value });
+    }
+    Ok(days_since_epoch(&Date {
+        year: from.year,
+        month: from.month,
+        day: from.day,
+    }))
 }
 
 pub fn ns_since_epoch(from: &Timestamp) -> Result<i64, MappingError> {
@@ -75,6 +117,62 @@ pub fn ns_since_epoch(from: &Timestamp) -> Result<i64, MappingError> {
     Ok(ndt.timestamp_nanos())
 }
 
+/// Parses the text representation of a timestamp with a time zone offset (e.g.
+/// `2023-01-01 12:00:00.1234567 +05:30`) into units since unix epoch in UTC. Fractional seconds
+/// finer than `unit` are truncated. Only nanoseconds are range checked, since the coarser units
+/// hold all timestamps the data source can represent.
+pub fn since_epoch_of_offset_text(text: &[u8], unit: &TimeUnit) -> Result<i64, MappingError> {
+    let invalid = || MappingError::InvalidTimestampOffset {
+        value: String::from_utf8_lossy(text).into_owned(),
+    };
+    let text = std::str::from_utf8(text).map_err(|_| invalid())?;
+    let utc = DateTime::parse_from_str(text.trim(), "%Y-%m-%d %H:%M:%S%.f %:z")
+        .map_err(|_| invalid())?
+        .naive_utc();
+    let since_epoch = match unit {
+        TimeUnit::Second => utc.timestamp(),
+        TimeUnit::Millisecond => utc.timestamp_millis(),
+        TimeUnit::Microsecond => utc.timestamp_micros(),
+        TimeUnit::Nanosecond => {
+            if min_datetime_ns() > utc || utc > max_datetime_ns() {
+                return Err(MappingError::OutOfRangeTimestampNs { value: utc });
+            }
+            utc.timestamp_nanos()
+        }
+    };
+    Ok(since_epoch)
+}
+
+/// Parses the text representation of a time of day (`hh:mm:ss` with an optional fraction of up to
+/// nine digits) into nanoseconds since midnight. `None` if the text is not a valid time.
+pub fn ns_since_midnight(text: &[u8]) -> Option<i64> {
+    let (hms, fraction) = match text.iter().position(|&c| c == b'.') {
+        Some(dot) => (&text[..dot], &text[dot + 1..]),
+        None => (text, &text[text.len()..]),
+    };
+    let mut parts = hms.split(|&c| c == b':');
+    let mut next_part = |max: i64| {
+        let part = parts.next()?;
+        if part.is_empty() || part.len() > 2 || !part.iter().all(u8::is_ascii_digit) {
+            return None;
+        }
+        let value = part.iter().fold(0, |acc, &c| acc * 10 + (c - b'0') as i64);
+        (value <= max).then_some(value)
+    };
+    let hour = next_part(23)?;
+    let minute = next_part(59)?;
+    let second = next_part(59)?;
+    if parts.next().is_some() || fraction.len() > 9 || !fraction.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    let nanos = fraction
+        .iter()
+        .chain(std::iter::repeat(&b'0'))
+        .take(9)
+        .fold(0, |acc, &c| acc * 10 + (c - b'0') as i64);
+    Some(((hour * 60 + minute) * 60 + second) * 1_000_000_000 + nanos)
+}
+
 /// 2262-04-11 23:47:16.854775807 is the latest timestamp representable with nanoseconds precision
 /// due to arrow using a signed 64 Bit integer.
 fn max_datetime_ns() -> NaiveDateTime {
@@ -91,24 +189,60 @@ fn min_datetime_ns() -> NaiveDateTime {
     NaiveDateTime::from_timestamp_opt(min_without_fraction / 1_000_000_000, 0).unwrap()
 }
 
-pub fn epoch_to_timestamp<const UNIT_FACTOR: i64>(from: i64) -> Timestamp {
-    let ndt = NaiveDateTime::from_timestamp_opt(
-        from / UNIT_FACTOR,
-        ((from % UNIT_FACTOR) * (1_000_000_000 / UNIT_FACTOR))
-            .try_into()
-            .unwrap(),
-    )
-    .unwrap();
+/// Converts a point in time, relative to unix epoch, into an ODBC timestamp. `UNIT_FACTOR` is the
+/// number of units in a second, e.g. `1_000` for milliseconds. Values before unix epoch are
+/// negative and are rounded towards negative infinity, so the fractional part always stays
+/// positive.
+pub fn epoch_to_timestamp<const UNIT_FACTOR: i64>(from: i64) -> Result<Timestamp, WriterError> {
+    let ndt = epoch_to_date_time(from, UNIT_FACTOR)?;
     let date = ndt.date();
     let time = ndt.time();
-    Timestamp {
-        year: date.year().try_into().unwrap(),
+    Ok(Timestamp {
+        year: date
+            .year()
+            .try_into()
+            .map_err(|_| timestamp_out_of_range(from, UNIT_FACTOR))?,
         month: date.month().try_into().unwrap(),
         day: date.day().try_into().unwrap(),
         hour: time.hour().try_into().unwrap(),
         minute: time.minute().try_into().unwrap(),
         second: time.second().try_into().unwrap(),
         fraction: time.nanosecond(),
+    })
+}
+
+/// Converts nanoseconds since unix epoch into an ODBC timestamp. The last two digits are dropped,
+/// since nanosecond timestamps are bound with a precision of seven fractional digits.
+pub fn epoch_ns_to_timestamp(from: i64) -> Result<Timestamp, WriterError> {
+    let mut timestamp = epoch_to_timestamp::<1_000_000_000>(from)?;
+    timestamp.fraction = timestamp.fraction / 100 * 100;
+    Ok(timestamp)
+}
+
+/// Point in time `from` in units since unix epoch as date and time in UTC. `unit_factor` is the
+/// number of units in a second. See [`epoch_to_timestamp`].
+pub fn epoch_to_date_time(from: i64, unit_factor: i64) -> Result<NaiveDateTime, WriterError> {
+    let seconds = from.div_euclid(unit_factor);
+    let nanoseconds = from.rem_euclid(unit_factor) * (1_000_000_000 / unit_factor);
+    NaiveDateTime::from_timestamp_opt(seconds, nanoseconds.try_into().unwrap())
+        .ok_or_else(|| timestamp_out_of_range(from, unit_factor))
+}
+
+/// Error for a timestamp `value` in units since unix epoch, which can not be inserted.
+/// `unit_factor` is the number of units in a second.
+pub fn timestamp_out_of_range(value: i64, unit_factor: i64) -> WriterError {
+    WriterError::TimestampOutOfRange {
+        value,
+        time_unit: time_unit_from_factor(unit_factor),
+    }
+}
+
+fn time_unit_from_factor(unit_factor: i64) -> TimeUnit {
+    match unit_factor {
+        1 => TimeUnit::Second,
+        1_000 => TimeUnit::Millisecond,
+        1_000_000 => TimeUnit::Microsecond,
+        _ => TimeUnit::Nanosecond,
     }
 }
 
@@ -6,6 +6,11 @@ use odbc_api::buffers::{AnySliceMut, BufferDesc};
 
 use crate::{odbc_writer::WriteStrategy, WriterError};
 
+/// Sends decimals to the database in their text representation. The buffers offered by `odbc-api`
+/// do not include `SQL_C_NUMERIC`, but the text representation is exact for every precision and
+/// scale, so no digits are lost on the way. Binding `SQL_C_NUMERIC` would also require setting
+/// precision and scale on the application parameter descriptor, since drivers ignore the ones in
+/// the struct otherwise, and `odbc-api` offers no access to descriptors.
 pub struct NullableDecimal128AsText {
     precision: u8,
     scale: i8,
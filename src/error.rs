@@ -1,3 +1,5 @@
+use arrow::error::ArrowError;
+use odbc_api::handles::Record;
 use thiserror::Error;
 
 use crate::read_strategy::ColumnFailure;
@@ -8,18 +10,322 @@ pub enum Error {
     /// Failure to retrieve the number of columns from the result set.
     #[error("Unable to retrieve number of columns in result set.\n{0}")]
     UnableToRetrieveNumCols(odbc_api::Error),
-    /// Indicates that the error is related to a specify column.
-    #[error(
-        "There is a problem with the SQL type of the column with name: {} and index {}:\n{source}",
-        name,
-        index
-    )]
+    /// Indicates that the error is related to a specify column. E.g. no read strategy could be
+    /// chosen for it.
+    #[error("Failed to create read strategy for column {index} '{name}':\n{source}")]
     ColumnFailure {
-        // Name of the erroneous column
+        /// Name of the erroneous column
         name: String,
-        // Index of the erroneous column
+        /// Zero based index of the erroneous column
         index: usize,
-        // Cause of the error
+        /// Cause of the error
         source: ColumnFailure,
     },
+    /// The result set has more columns than allowed by
+    /// [`crate::OdbcReaderBuilder::with_max_columns`].
+    #[error(
+        "The result set has {num_columns} columns, which exceeds the maximum of {max_columns} \
+        columns."
+    )]
+    TooManyColumns {
+        num_columns: usize,
+        max_columns: usize,
+    },
+    /// A single row of the buffers exceeds the budget passed to
+    /// [`crate::OdbcReaderBuilder::with_max_buffer_bytes`]. Names the column with the largest
+    /// buffer.
+    #[error(
+        "A single row requires {bytes_per_row} bytes of buffer, which exceeds the maximum of \
+        {max_buffer_bytes} bytes. The largest buffer with {column_bytes} bytes per row is the one \
+        of column {index} '{name}'."
+    )]
+    BufferBudgetExceeded {
+        max_buffer_bytes: usize,
+        bytes_per_row: usize,
+        /// Zero based index of the column with the largest buffer
+        index: usize,
+        name: String,
+        column_bytes: usize,
+    },
+    /// Failure to execute the query given to [`crate::OdbcReaderBuilder::build_with_query`].
+    #[error("An error occurred executing SQL statement. SQL:\n{sql}\n{source}")]
+    ExecutingQuery {
+        #[source]
+        source: odbc_api::Error,
+        sql: String,
+    },
+    /// The query given to [`crate::OdbcReaderBuilder::build_with_query`] did not produce a result
+    /// set.
+    #[error("The SQL statement did not produce a result set. SQL:\n{sql}")]
+    NoResultSet { sql: String },
+    /// The function passed to [`crate::OdbcReaderBuilder::with_batch_transformer`] failed to
+    /// transform an empty batch, which is used to determine the schema of the reader.
+    #[error("The batch transformer failed to transform an empty batch:\n{0}")]
+    TransformingBatch(#[source] arrow::error::ArrowError),
+    /// Failure to advance the cursor of a reader to the next result set in
+    /// [`crate::OdbcReader::into_next_result_set`].
+    #[error("Unable to advance to the next result set.\n{0}")]
+    MoreResults(odbc_api::Error),
+    /// Failure to unbind the buffers from the cursor in order to fetch ahead in
+    /// [`crate::OdbcReaderBuilder::build_concurrent`], or to rebind them in
+    /// [`crate::OdbcReader::rebind`].
+    #[error("Unable to unbind buffers from the cursor.\n{0}")]
+    UnbindingBuffers(odbc_api::Error),
+    /// The result set passed to [`crate::OdbcReader::rebind`] has another number of columns than
+    /// the one the reader has been built for.
+    #[error(
+        "The buffers of the reader are bound to {expected} columns, but the result set has \
+        {actual} columns."
+    )]
+    ColumnCountMismatch { expected: usize, actual: usize },
+    /// A column of the result set passed to [`crate::OdbcReader::rebind`] has another SQL type
+    /// than the one in the result set the reader has been built for.
+    #[error(
+        "The buffer of the reader for column {index} has been chosen for the SQL type \
+        {expected:?}, but the column of the result set is of type {actual:?}."
+    )]
+    ColumnTypeMismatch {
+        /// Zero based index of the column
+        index: usize,
+        expected: odbc_api::DataType,
+        actual: odbc_api::DataType,
+    },
+    /// Failure to prepare the query given to [`crate::validate`].
+    #[error("An error occurred preparing SQL statement. SQL:\n{sql}\n{source}")]
+    PreparingQuery {
+        #[source]
+        source: odbc_api::Error,
+        sql: String,
+    },
+    /// The pattern passed to [`crate::OdbcReaderBuilder::with_type_rule`] is not a valid regular
+    /// expression.
+    #[error("Invalid regular expression '{pattern}' for matching column names:\n{source}")]
+    InvalidColumnPattern {
+        pattern: String,
+        #[source]
+        source: regex::Error,
+    },
+    /// A column referenced by name in the options of [`crate::OdbcReaderBuilder`] is not part of
+    /// the result set.
+    #[error("The result set has no column named '{name}'.")]
+    UnknownColumn { name: String },
+    /// A column referenced by index in the options of [`crate::OdbcReaderBuilder`] is not part of
+    /// the result set.
+    #[error("The result set has no column with index {index}. It has {num_columns} columns.")]
+    UnknownColumnIndex { index: usize, num_columns: usize },
+    /// A column is passed more than once to [`crate::OdbcReaderBuilder::with_projection`] or
+    /// [`crate::OdbcReaderBuilder::with_projection_indices`].
+    #[error("The column {index} '{name}' is projected more than once.")]
+    DuplicateProjection { index: usize, name: String },
+    /// The flag column passed to [`crate::OdbcReaderBuilder::with_null_flag`] is neither boolean
+    /// nor an integer.
+    #[error(
+        "The column '{name}' can not be used to flag NULL values, since its type {data_type} is \
+        neither boolean nor an integer."
+    )]
+    UnsupportedNullFlagType {
+        name: String,
+        data_type: arrow::datatypes::DataType,
+    },
+    /// The sort key column passed to [`crate::OdbcReaderBuilder::with_sort_key`] is not binary.
+    #[error(
+        "The column '{name}' can not be used as a sort key, since its type {data_type} is not \
+        binary."
+    )]
+    UnsupportedSortKeyType {
+        name: String,
+        data_type: arrow::datatypes::DataType,
+    },
+    /// More than one of the readers passed to [`crate::SplitOdbcReader::new`] fetches a column
+    /// with this name, other than the key column.
+    #[error("The column '{name}' is fetched by more than one reader of the split reader.")]
+    DuplicateColumn { name: String },
+    /// Connecting, executing the query, building the reader or fetching a batch failed for a
+    /// partition of [`crate::OdbcReaderBuilder::build_partitioned`].
+    #[error("Failed to read partition {index}:\n{source}")]
+    Partition {
+        /// Zero based index of the partition
+        index: usize,
+        #[source]
+        source: Box<Error>,
+    },
+    /// The thread reading a partition of [`crate::OdbcReaderBuilder::build_partitioned`] panicked.
+    /// Wrapped in an [`Error::Partition`].
+    #[error("The thread reading the partition panicked.")]
+    PartitionPanicked,
+    /// The reader for a partition of [`crate::OdbcReaderBuilder::build_partitioned`] has another
+    /// schema than the reader for the first partition.
+    #[error(
+        "The reader for partition {index} has another schema than the reader for the first \
+        partition."
+    )]
+    PartitionSchemaMismatch { index: usize },
+    /// The data type passed to [`crate::OdbcReaderBuilder::with_text_coercion`] is not numeric.
+    #[error(
+        "Text of the column '{name}' can not be parsed into {data_type}, which is not numeric."
+    )]
+    UnsupportedCoercion {
+        name: String,
+        data_type: arrow::datatypes::DataType,
+    },
+    /// Failure to call an ODBC catalog function, e.g. `SQLTables` for [`crate::fetch_tables`].
+    #[error("An error occurred calling the ODBC catalog function {function}:\n{source}")]
+    CatalogFunction {
+        function: &'static str,
+        #[source]
+        source: odbc_api::Error,
+    },
+    /// Failure to fetch the result set of an ODBC catalog function.
+    #[error(
+        "An error occurred fetching the result of the ODBC catalog function {function}:\n{source}"
+    )]
+    FetchingCatalog {
+        function: &'static str,
+        #[source]
+        source: arrow::error::ArrowError,
+    },
+    /// An ODBC catalog function found no rows, and [`crate::EmptyCatalogResult::Error`] has been
+    /// requested for this case.
+    #[error("The ODBC catalog function {function} did not find any rows.")]
+    EmptyCatalogResult { function: &'static str },
+    /// Failure to connect to the data source in [`crate::connect_with_login_timeout`].
+    #[error("An error occurred connecting to the data source:\n{source}")]
+    Connecting {
+        #[source]
+        source: odbc_api::Error,
+    },
+    /// The driver did not complete the login within the timeout passed to
+    /// [`crate::connect_with_login_timeout`].
+    #[error("Connecting to the data source timed out after {seconds} seconds.")]
+    LoginTimeout { seconds: u32 },
+    /// The schema passed to [`crate::OdbcReaderBuilder::with_schema`] does not line up with the
+    /// columns of the result set. Fields are matched with columns by position, so they could not
+    /// be bound, or would receive the values of another column.
+    #[error("{}", display_schema_mismatch(.num_fields, .num_columns, .missing, .extra, .reordered))]
+    SchemaMismatch {
+        num_fields: usize,
+        num_columns: usize,
+        /// Names of the columns without a field of the same name.
+        missing: Vec<String>,
+        /// Names of the fields without a column of the same name.
+        extra: Vec<String>,
+        /// Names of the fields at another position than the column of the same name.
+        reordered: Vec<String>,
+    },
+    /// The task constructing and iterating the reader of a `BatchStream` has been cancelled by
+    /// tokio, e.g. because the runtime shuts down. Only emitted with the `async` feature.
+    #[error("Reading the batches of the stream has been cancelled.")]
+    BatchStreamCancelled,
+    /// Fetching or transforming a batch failed in [`crate::OdbcReader::read_all`].
+    #[error("Failed to read a batch of the result set:\n{0}")]
+    ReadingBatch(#[source] arrow::error::ArrowError),
+    /// The values of a column in [`crate::OdbcReader::read_all`] exceed the range of its 32 Bit
+    /// offsets, so the batches can not be concatenated into one array.
+    #[error(
+        "The column {index} '{name}' holds {num_values} values in total, which exceeds the \
+        maximum number of values for the type {data_type}. Consider fetching it as large type, \
+        e.g. LargeUtf8 or LargeBinary."
+    )]
+    OffsetOverflow {
+        /// Zero based index of the column
+        index: usize,
+        name: String,
+        data_type: arrow::datatypes::DataType,
+        /// Sum of bytes (`Utf8`, `Binary`) or elements (`List`) of the column in all batches.
+        num_values: usize,
+    },
+    /// Concatenating the batches failed in [`crate::OdbcReader::read_all`].
+    #[error("Failed to concatenate the batches of the result set:\n{0}")]
+    ConcatenatingBatches(#[source] arrow::error::ArrowError),
+    /// Emitted by [`crate::validate`], which checks all columns rather than stopping at the first
+    /// one which can not be fetched. Each element is an [`Error::ColumnFailure`].
+    #[error("{}", display_all(.0))]
+    ColumnFailures(Vec<Error>),
+}
+
+impl Error {
+    /// The error reported by the ODBC driver or driver manager, which caused this error. `None` if
+    /// it has not been caused by a failing ODBC function call.
+    pub fn odbc_error(&self) -> Option<&odbc_api::Error> {
+        match self {
+            Error::UnableToRetrieveNumCols(source)
+            | Error::ExecutingQuery { source, .. }
+            | Error::MoreResults(source)
+            | Error::UnbindingBuffers(source)
+            | Error::PreparingQuery { source, .. }
+            | Error::CatalogFunction { source, .. }
+            | Error::Connecting { source } => Some(source),
+            Error::ColumnFailure { source, .. } => source.odbc_error(),
+            Error::FetchingCatalog { source, .. } | Error::ReadingBatch(source) => {
+                odbc_error_of(source)
+            }
+            Error::ColumnFailures(errors) => errors.iter().find_map(Error::odbc_error),
+            Error::Partition { source, .. } => source.odbc_error(),
+            _ => None,
+        }
+    }
+
+    /// Diagnostic record of the ODBC error causing this error, with SQLSTATE, native error code
+    /// and message. See [`Self::odbc_error`].
+    pub fn diagnostic_record(&self) -> Option<&Record> {
+        self.odbc_error().and_then(diagnostic_record)
+    }
+
+    /// Five character SQLSTATE of the ODBC error causing this error, e.g. `40001` for a
+    /// serialization failure, like a deadlock, after which the operation can be retried.
+    pub fn sql_state(&self) -> Option<&str> {
+        self.diagnostic_record().map(|record| record.state.as_str())
+    }
+}
+
+/// The ODBC error within an error yielded by [`crate::OdbcReader`], e.g. if fetching a batch
+/// failed. Readers yield [`ArrowError`]s, so the ODBC error is boxed as an external error.
+pub fn odbc_error_of(error: &ArrowError) -> Option<&odbc_api::Error> {
+    match error {
+        ArrowError::ExternalError(source) => source.downcast_ref(),
+        _ => None,
+    }
+}
+
+/// Diagnostic record attached to `error`, if any.
+pub(crate) fn diagnostic_record(error: &odbc_api::Error) -> Option<&Record> {
+    match error {
+        odbc_api::Error::Diagnostics { record, .. }
+        | odbc_api::Error::UnsupportedOdbcApiVersion(record)
+        | odbc_api::Error::InvalidRowArraySize { record, .. }
+        | odbc_api::Error::UnableToRepresentNull(record)
+        | odbc_api::Error::OracleOdbcDriverDoesNotSupport64Bit(record) => Some(record),
+        _ => None,
+    }
+}
+
+fn display_schema_mismatch(
+    num_fields: &usize,
+    num_columns: &usize,
+    missing: &[String],
+    extra: &[String],
+    reordered: &[String],
+) -> String {
+    let mut message = format!(
+        "The schema of the reader has {num_fields} fields, but does not match the {num_columns} \
+        columns of the result set."
+    );
+    for (names, description) in [
+        (missing, "Columns without field"),
+        (extra, "Fields without column"),
+        (reordered, "Fields at another position than their column"),
+    ] {
+        if !names.is_empty() {
+            message.push_str(&format!("\n{description}: {}", names.join(", ")));
+        }
+    }
+    message
+}
+
+fn display_all(errors: &[Error]) -> String {
+    errors
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("\n")
 }
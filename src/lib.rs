@@ -47,12 +47,16 @@
 //!
 //!
 //! ```
+mod backend_profile;
+mod catalog;
+mod connect;
 mod date_time;
 mod decimal;
 mod error;
 mod odbc_reader;
 
 mod odbc_writer;
+mod parquet_hints;
 mod read_strategy;
 mod schema;
 
@@ -62,9 +66,31 @@ pub use arrow;
 pub use odbc_api;
 
 pub use self::{
-    error::Error,
-    odbc_reader::OdbcReader,
-    odbc_writer::{insert_into_table, insert_statement_from_schema, OdbcWriter, WriterError},
-    read_strategy::{BufferAllocationOptions, ColumnFailure},
-    schema::arrow_schema_from,
+    backend_profile::BackendProfile,
+    catalog::{
+        columns_schema, fetch_columns, fetch_tables, tables_schema, EmptyCatalogResult,
+        COLUMNS_COLUMNS, TABLES_COLUMNS,
+    },
+    connect::connect_with_login_timeout,
+    date_time::TimestampRounding,
+    error::{odbc_error_of, Error},
+    odbc_reader::{
+        is_cancelled, validate, BatchTiming, CancellationToken, Cancelled, ColumnDiagnostic,
+        ConcurrentOdbcReader, FetchProgress, FetchWarning, OdbcReader, OdbcReaderBuilder,
+        PartitionedOdbcReader, ReadSummary, RebatchReader, RowWiseOdbcReader, SplitOdbcReader,
+    },
+    odbc_writer::{
+        create_table_statement_from_schema, insert_into_table, insert_statement_from_schema,
+        OdbcWriter, OdbcWriterBuilder, SqlDialect, WriterError,
+    },
+    parquet_hints::ParquetHints,
+    read_strategy::{
+        BufferAllocationOptions, ColumnFailure, ColumnLookup, GuidByteOrder, GuidRepresentation,
+        MapOdbcToArrow, MappingError, MappingErrorPolicy, NarrowText, ReadStrategy, TextEncoding,
+        UnmappedValue, ValueErrors, WideText, ZeroSizedColumnPolicy,
+    },
+    schema::{arrow_schema_from, FieldIds},
 };
+
+#[cfg(feature = "async")]
+pub use self::odbc_reader::BatchStream;
@@ -1,23 +1,76 @@
-use std::{convert::TryInto, sync::Arc};
- use rayon::prelude::*;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use regex::Regex;
+use std::{
+    collections::{HashMap, HashSet},
+    convert::TryInto,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use arrow::{
     array::ArrayRef,
-    datatypes::{Schema, SchemaRef},
+    compute::can_cast_types,
+    datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit},
     error::ArrowError,
     record_batch::{RecordBatch, RecordBatchReader},
 };
 use odbc_api::{
-    buffers::{AnyBuffer, ColumnarAnyBuffer, ColumnarBuffer},
-    BlockCursor, Cursor,
+    buffers::{AnyBuffer, ColumnarBuffer},
+    handles::{slice_to_cow_utf8, AsStatementRef, Record, Statement, StatementImpl},
+    sys::SqlDataType,
+    BlockCursor, Connection, Cursor, CursorImpl, DataType as OdbcDataType, ParameterCollectionRef,
+    Preallocated, ResultSetMetadata,
 };
 
 use crate::{
     arrow_schema_from,
-    read_strategy::{choose_column_strategy, MappingError, ReadStrategy},
-    BufferAllocationOptions, ColumnFailure, Error,
+    backend_profile::tiny_int_1_as_boolean,
+    read_strategy::{
+        choose_column_strategy, is_guid, is_nullable_buffer, time_precision, ByteSwap, Cast,
+        CoerceText, ColumnLookup, Custom, DecodingFallback, GuidByteOrder, GuidRepresentation,
+        HexText, Lookup, MappingError, MappingErrorPolicy, NonNullable, ReadStrategy,
+        StrategyOptions, TextEncoding, TrimTrailingSpaces, ValueErrors,
+    },
+    schema::{is_rowversion, is_unsigned, type_name},
+    BackendProfile, BufferAllocationOptions, ColumnFailure, Error, FieldIds, ParquetHints,
+    TimestampRounding, ZeroSizedColumnPolicy,
+};
+
+#[cfg(feature = "async")]
+mod batch_stream;
+mod batch_transformer;
+mod cancellation;
+mod concurrent_odbc_reader;
+mod null_flag;
+mod partitioned_odbc_reader;
+mod read_all;
+mod read_summary;
+mod rebatch_reader;
+mod row_wise_odbc_reader;
+mod sort_key;
+mod split_odbc_reader;
+
+pub use self::{
+    cancellation::{is_cancelled, CancellationToken, Cancelled},
+    concurrent_odbc_reader::ConcurrentOdbcReader,
+    partitioned_odbc_reader::PartitionedOdbcReader,
+    read_summary::{FetchWarning, ReadSummary},
+    rebatch_reader::RebatchReader,
+    row_wise_odbc_reader::RowWiseOdbcReader,
+    split_odbc_reader::SplitOdbcReader,
 };
 
+#[cfg(feature = "async")]
+pub use self::batch_stream::BatchStream;
+
+use self::batch_transformer::BatchTransformer;
+use self::cancellation::Registration;
+use self::null_flag::{apply_null_flags, NullFlag};
+use self::read_all::concat_all;
+use self::read_summary::{count_truncated_batches, FetchStatement};
+use self::sort_key::{check_sort_key, mark_sort_key};
+
 /// Arrow ODBC reader. Implements the [`arrow::record_batch::RecordBatchReader`] trait so it can be
 /// used to fill Arrow arrays from an ODBC data source.
 ///
@@ -74,10 +127,44 @@ pub struct OdbcReader<C: Cursor> {
     /// specific decisions which go into filling an Arrow array from an ODBC data source.
     column_strategies: Vec<Box<dyn ReadStrategy>>,
     /// Arrow schema describing the arrays we want to fill from the Odbc data source.
+    batch_schema: SchemaRef,
+    /// Schema of the record batches yielded by the reader. Differs from `batch_schema` only if the
+    /// batches are transformed.
     schema: SchemaRef,
+    /// Registration of the statement with the cancellation token of the builder, if any. Declared
+    /// before `cursor`, so it is dropped before the statement is freed.
+    cancellation: Option<Registration>,
     /// Odbc cursor with a bound buffer we repeatedly fill with the batches send to us by the data
     /// source. One column buffer must be bound for each element in column_strategies.
     cursor: BlockCursor<C, ColumnarBuffer<AnyBuffer>>,
+    /// Applied to the arrays created by the column strategies.
+    null_flags: Vec<NullFlag>,
+    /// Total number of rows in the batches yielded so far.
+    num_rows_fetched: usize,
+    /// Number of batches yielded so far.
+    num_batches_fetched: usize,
+    /// Statement of `cursor`, queried for the warnings of each fetch.
+    statement: FetchStatement,
+    /// Number of fetched batches with truncated values, for each column.
+    truncated_batches: Vec<usize>,
+    /// Warnings reported by the driver for the fetches so far.
+    warnings: Vec<FetchWarning>,
+    /// Invoked after each batch, with the progress of the reader.
+    on_progress: Option<ProgressCallback>,
+    /// Invoked after each batch, with the time it took to fetch and convert it.
+    on_timing: Option<TimingCallback>,
+    /// Applied to each batch, before it is yielded.
+    batch_transformer: Option<BatchTransformer>,
+    /// What to do with values which can not be converted into their arrow type.
+    mapping_error_policy: MappingErrorPolicy,
+    /// The reader stops once it yielded this many rows. `None` means no limit.
+    max_total_rows: Option<usize>,
+    /// SQL types of the columns the buffers have been chosen for. Result sets the buffers are
+    /// rebound to must match them.
+    column_types: Vec<OdbcDataType>,
+    /// One based indices of the columns of the result set the buffers are bound to, in the order
+    /// of `column_strategies`.
+    column_indices: Vec<u16>,
 }
 
 impl<C: Cursor> OdbcReader<C> {
@@ -110,7 +197,7 @@ impl<C: Cursor> OdbcReader<C> {
     ///   supported though.
     /// * `max_batch_size`: Maximum batch size requested from the datasource.
     /// * `schema`: Arrow schema. Describes the type of the Arrow Arrays in the record batches, but
-    ///    is also used to determine CData type requested from the data source.
+    ///   is also used to determine CData type requested from the data source.
     pub fn with_arrow_schema(
         cursor: C,
         max_batch_size: usize,
@@ -137,64 +224,2471 @@ impl<C: Cursor> OdbcReader<C> {
     ///   supported though.
     /// * `max_batch_size`: Maximum batch size requested from the datasource.
     /// * `schema`: Arrow schema. Describes the type of the Arrow Arrays in the record batches, but
-    ///    is also used to determine CData type requested from the data source. Set to `None` to
-    ///    infer schema from the data source.
+    ///   is also used to determine CData type requested from the data source. Set to `None` to
+    ///   infer schema from the data source.
     /// * `buffer_allocation_options`: Allows you to specify upper limits for binary and / or text
-    ///    buffer types. This is useful support fetching data from e.g. VARCHAR(max) or
-    ///    VARBINARY(max) columns, which otherwise might lead to errors, due to the ODBC driver
-    ///    having a hard time specifying a good upper bound for the largest possible expected value.
+    ///   buffer types. This is useful support fetching data from e.g. VARCHAR(max) or
+    ///   VARBINARY(max) columns, which otherwise might lead to errors, due to the ODBC driver
+    ///   having a hard time specifying a good upper bound for the largest possible expected value.
     pub fn with(
-        mut cursor: C,
+        cursor: C,
         max_batch_size: usize,
         schema: Option<SchemaRef>,
         buffer_allocation_options: BufferAllocationOptions,
     ) -> Result<Self, Error> {
-        // Infer schema if not given by the user
-        let schema = if let Some(schema) = schema {
-            schema
+        let mut builder = OdbcReaderBuilder::new();
+        builder
+            .with_max_num_rows_per_batch(max_batch_size)
+            .with_buffer_allocation_options(buffer_allocation_options);
+        if let Some(schema) = schema {
+            builder.with_schema(schema);
+        }
+        builder.build(cursor)
+    }
+
+    /// Total number of rows in the batches yielded by the reader so far. The batch last yielded
+    /// starts at the global row offset `num_rows_fetched() - batch.num_rows()`. Record it together
+    /// with the processed batch, in order to resume processing at the offset later, e.g. using
+    /// keyset or `OFFSET` pagination. Rows are counted before the batches are passed to a batch
+    /// transformer (see [`OdbcReaderBuilder::with_batch_transformer`]), which may filter them.
+    pub fn num_rows_fetched(&self) -> usize {
+        self.num_rows_fetched
+    }
+
+    /// Rows, truncated values and warnings of the fetches so far. Call it once the iteration is
+    /// complete, to learn about problems the driver tolerated, e.g. values it truncated.
+    pub fn summary(&self) -> ReadSummary {
+        ReadSummary {
+            num_rows: self.num_rows_fetched,
+            truncated_batches: self.truncated_batches.clone(),
+            warnings: self.warnings.clone(),
+        }
+    }
+
+    /// Consumes the reader and concatenates all its batches into a single one with the schema of
+    /// the reader. All batches are buffered in memory before being concatenated, so the memory
+    /// required adds up to about twice the size of the result set. Reserve this for result sets
+    /// which fit comfortably into memory, and iterate over the batches otherwise.
+    ///
+    /// Fails with [`Error::ReadingBatch`] if fetching any batch fails, and with
+    /// [`Error::OffsetOverflow`] if the values of a `Utf8`, `Binary` or `List` column exceed the
+    /// range of its 32 Bit offsets. Consider the `LargeUtf8`, `LargeBinary` or `LargeList`
+    /// types for such columns.
+    pub fn read_all(self) -> Result<RecordBatch, Error> {
+        let schema = self.schema.clone();
+        let batches = self
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(Error::ReadingBatch)?;
+        concat_all(&schema, &batches)
+    }
+
+    /// Destroy the ODBC arrow reader and yield the underlyinng cursor object.
+    ///
+    /// One application of this is to process more than one result set in case you executed a stored
+    /// procedure.
+    pub fn into_cursor(self) -> Result<C, odbc_api::Error> {
+        let (cursor, _buffer) = self.cursor.unbind()?;
+        Ok(cursor)
+    }
+
+    /// Destroy the reader and bind its buffers to `cursor`, e.g. the result set of executing the
+    /// same query again in order to poll for changes. Saves allocating the buffers and choosing the
+    /// strategies for each execution. The current cursor is dropped, and rows of it not yet fetched
+    /// are discarded. The new reader starts counting fetched rows at zero, but keeps all other
+    /// options.
+    ///
+    /// Fails with [`Error::ColumnCountMismatch`] or [`Error::ColumnTypeMismatch`] unless the
+    /// columns of `cursor` have the same SQL types as the ones of the result set the reader has
+    /// been built for.
+    pub fn rebind<C2: Cursor>(self, mut cursor: C2) -> Result<OdbcReader<C2>, Error> {
+        let column_types = column_types(&mut cursor)?;
+        if column_types.len() != self.column_types.len() {
+            return Err(Error::ColumnCountMismatch {
+                expected: self.column_types.len(),
+                actual: column_types.len(),
+            });
+        }
+        let mismatch = self
+            .column_types
+            .iter()
+            .zip(&column_types)
+            .position(|(expected, actual)| expected != actual);
+        if let Some(index) = mismatch {
+            return Err(Error::ColumnTypeMismatch {
+                index,
+                expected: self.column_types[index],
+                actual: column_types[index],
+            });
+        }
+        let (_cursor, buffer) = self.cursor.unbind().map_err(Error::UnbindingBuffers)?;
+        if let Some(cancellation) = &self.cancellation {
+            // Safety: The registration moves into the new reader, along with `cursor`.
+            unsafe { cancellation.update(cursor.as_stmt_ref().as_sys()) }
+        }
+        // Safety: The statement moves into the new reader, along with `cursor`.
+        let statement = unsafe { FetchStatement::new(cursor.as_stmt_ref().as_sys()) };
+        let num_columns = self.truncated_batches.len();
+        Ok(OdbcReader {
+            column_strategies: self.column_strategies,
+            batch_schema: self.batch_schema,
+            schema: self.schema,
+            cancellation: self.cancellation,
+            cursor: cursor.bind_buffer(buffer).unwrap(),
+            null_flags: self.null_flags,
+            num_rows_fetched: 0,
+            num_batches_fetched: 0,
+            statement,
+            truncated_batches: vec![0; num_columns],
+            warnings: Vec::new(),
+            on_progress: self.on_progress,
+            on_timing: self.on_timing,
+            batch_transformer: self.batch_transformer,
+            mapping_error_policy: self.mapping_error_policy,
+            max_total_rows: self.max_total_rows,
+            column_types,
+            column_indices: self.column_indices,
+        })
+    }
+
+    /// Destroy the reader and build a new one for the next result set of its cursor, e.g. one of
+    /// the result sets returned by a stored procedure. Rows of the current result set not yet
+    /// fetched are discarded. `None` if there are no more result sets.
+    ///
+    /// Each result set gets its own schema. If the result sets differ in the width of their integer
+    /// columns, use [`OdbcReaderBuilder::with_widened_integers`] so the batches share a schema and
+    /// can be concatenated.
+    pub fn into_next_result_set(
+        self,
+        builder: &OdbcReaderBuilder,
+    ) -> Result<Option<OdbcReader<C>>, Error> {
+        let cursor = self.into_cursor().map_err(Error::MoreResults)?;
+        match cursor.more_results().map_err(Error::MoreResults)? {
+            Some(cursor) => builder.build(cursor).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+impl<S: AsStatementRef> OdbcReader<CursorImpl<S>> {
+    /// Destroy the ODBC arrow reader, close its cursor and yield the underlying statement. Rows not
+    /// yet fetched are discarded.
+    ///
+    /// E.g. a reader constructed with [`OdbcReaderBuilder::build_with_query`] yields a
+    /// [`StatementImpl`] borrowing the connection. Dropping it releases the connection for
+    /// subsequent queries. A [`odbc_api::StatementConnection`] owns its connection, but `odbc-api`
+    /// offers no way to take the connection back out of it.
+    pub fn into_statement(self) -> Result<S, odbc_api::Error> {
+        let mut statement = self.into_cursor()?.into_stmt();
+        {
+            let mut stmt = statement.as_stmt_ref();
+            stmt.close_cursor().into_result(&stmt)?;
+        }
+        Ok(statement)
+    }
+}
+
+/// Allows setting options for an [`OdbcReader`] before constructing it.
+///
+/// # Example
+///
+/// ```no_run
+/// use arrow_odbc::{odbc_api::Cursor, OdbcReader, OdbcReaderBuilder};
+///
+/// fn reader<C: Cursor>(cursor: C) -> Result<OdbcReader<C>, anyhow::Error> {
+///     let reader = OdbcReaderBuilder::new()
+///         .with_max_num_rows_per_batch(10_000)
+///         .build(cursor)?;
+///     Ok(reader)
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct OdbcReaderBuilder {
+    max_num_rows_per_batch: usize,
+    /// `None` means all rows of the result set are read.
+    max_total_rows: Option<usize>,
+    /// `None` means the number of rows per batch is not limited by the size of the buffers.
+    max_buffer_bytes: Option<usize>,
+    schema: Option<SchemaRef>,
+    /// Options consulted then choosing the strategy for each column.
+    strategy_options: StrategyOptions,
+    /// Lookup tables by column name.
+    column_lookups: HashMap<String, ColumnLookup>,
+    /// Sizes of the big endian elements packed into binary columns, by column name.
+    big_endian_elements: HashMap<String, usize>,
+    /// Numeric types and decimal separators for text columns by column name.
+    text_coercions: HashMap<String, (DataType, char)>,
+    /// Maximum text sizes overriding the one of the buffer allocation options, by column name.
+    max_text_sizes: HashMap<String, usize>,
+    /// Names of the text columns inferred as dictionary encoded.
+    dictionary_columns: HashSet<String>,
+    /// Names of the timestamp columns inferred as text.
+    timestamps_as_text: HashSet<String>,
+    /// Names of the binary columns emitted as hex text.
+    hex_columns: HashSet<String>,
+    /// Names of the text columns retrieved piecewise by readers built with
+    /// [`Self::build_row_wise`].
+    streamed_text: HashSet<String>,
+    /// If `true` all binary columns are emitted as hex text.
+    all_binary_as_hex: bool,
+    /// Nullability of the fields in the inferred schema overriding the one reported by the
+    /// driver, by column name.
+    nullability_overrides: HashMap<String, bool>,
+    /// Strategies provided by the user, by zero based column index. Used instead of the strategy
+    /// the reader would choose.
+    custom_strategies: HashMap<usize, CustomStrategy>,
+    /// Arrow time unit for time of day columns in the inferred schema. `None` means their type is
+    /// inferred from their precision.
+    time_unit: Option<TimeUnit>,
+    /// Unit of all timestamp fields in the inferred schema. `None` means the unit is chosen by the
+    /// precision of each column.
+    timestamp_unit: Option<TimeUnit>,
+    /// Data types for the fields of columns with matching names in the inferred schema. The first
+    /// matching rule wins.
+    type_rules: Vec<(Regex, DataType)>,
+    /// If `true` tiny integers with a display size of one are inferred as boolean.
+    tiny_int_1_as_boolean: bool,
+    /// `true` if the decoding fallback has been installed by [`Self::with_lossy_utf8`], rather than
+    /// [`Self::with_decoding_fallback`].
+    lossy_utf8: bool,
+    /// If `true` integer columns the driver reports as unsigned are inferred as unsigned arrow
+    /// integers.
+    unsigned_integers: bool,
+    /// If `true` `rowversion` columns are inferred as `UInt64`, rather than `FixedSizeBinary(8)`.
+    rowversion_as_uint64: bool,
+    /// Quirks of the database management system accounted for.
+    backend_profile: BackendProfile,
+    /// If `true` trailing spaces are removed from the values of `CHAR` and `NCHAR` columns.
+    trim_fixed_size_text: bool,
+    /// If `true` all inferred signed integer fields are `Int64`.
+    widen_integers: bool,
+    /// If `true` inferred fields carry the SQL type of their column as metadata.
+    sql_type_metadata: bool,
+    /// If `true` inferring text for a column without a text SQL type is an error.
+    forbid_text_fallback: bool,
+    /// Pairs of value and flag column names. Values are `NULL` wherever the flag is set.
+    null_flags: Vec<(String, String)>,
+    /// Pairs of text and sort key column names.
+    sort_keys: Vec<(String, String)>,
+    /// Fields the columns of the same name are cast into.
+    target_schema: Option<SchemaRef>,
+    /// If `true` the strategy chosen for each column is logged then building the reader.
+    log_strategies: bool,
+    /// What to do with values which can not be converted into their arrow type.
+    mapping_error_policy: MappingErrorPolicy,
+    /// `None` means no limit.
+    max_columns: Option<usize>,
+    /// Columns of the result set the reader fetches, in the order of the emitted fields. `None`
+    /// means all of them.
+    projection: Option<Projection>,
+    /// Applied to queries before they are prepared or executed by the builder.
+    sql_rewriter: Option<SqlRewriter>,
+    /// Invoked for each diagnostic record reported while choosing the strategy of a column.
+    on_diagnostic: Option<DiagnosticCallback>,
+    /// Invoked by readers after each batch, with their progress.
+    on_progress: Option<ProgressCallback>,
+    /// Invoked by readers after each batch, with the time it took to fetch and convert it.
+    on_timing: Option<TimingCallback>,
+    /// Cancels the reads of the readers built.
+    cancellation_token: Option<CancellationToken>,
+    /// Applied to each batch before it is yielded by the reader.
+    batch_transformer: Option<BatchTransformer>,
+    /// `None` means the fields of the emitted schema carry no parquet field ids.
+    field_ids: Option<FieldIds>,
+    /// `None` means the fields of the emitted schema carry no parquet encoding or compression
+    /// hints.
+    parquet_hints: Option<ParquetHints>,
+}
+
+/// Columns passed to [`OdbcReaderBuilder::with_projection`] or
+/// [`OdbcReaderBuilder::with_projection_indices`].
+#[derive(Debug, Clone)]
+enum Projection {
+    Names(Vec<String>),
+    Indices(Vec<usize>),
+}
+
+/// Wraps the rewriting function, so the builder can implement `Debug` and `Clone`.
+#[derive(Clone)]
+struct SqlRewriter(Arc<dyn Fn(&str) -> String + Send + Sync>);
+
+impl std::fmt::Debug for SqlRewriter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SqlRewriter")
+    }
+}
+
+/// A read strategy provided by the user, together with the data type of the arrays it emits.
+/// Shared, so the builder can implement `Clone` and build more than one reader.
+#[derive(Clone)]
+struct CustomStrategy {
+    data_type: DataType,
+    strategy: Arc<dyn ReadStrategy>,
+}
+
+impl std::fmt::Debug for CustomStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CustomStrategy")
+            .field("data_type", &self.data_type)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Wraps the diagnostic callback, so the builder can implement `Debug` and `Clone`.
+#[derive(Clone)]
+struct DiagnosticCallback(Arc<dyn Fn(&ColumnDiagnostic) + Send + Sync>);
+
+impl std::fmt::Debug for DiagnosticCallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("DiagnosticCallback")
+    }
+}
+
+/// Wraps the progress callback, so the builder can implement `Debug` and `Clone`.
+#[derive(Clone)]
+struct ProgressCallback(Arc<dyn Fn(&FetchProgress) + Send + Sync>);
+
+impl std::fmt::Debug for ProgressCallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ProgressCallback")
+    }
+}
+
+impl ProgressCallback {
+    fn report(&self, num_batches: usize, num_rows: usize) {
+        (self.0)(&FetchProgress {
+            num_batches,
+            num_rows,
+        })
+    }
+}
+
+/// Wraps the timing callback, so the builder can implement `Debug` and `Clone`.
+#[derive(Clone)]
+struct TimingCallback(Arc<dyn Fn(&BatchTiming) + Send + Sync>);
+
+impl std::fmt::Debug for TimingCallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("TimingCallback")
+    }
+}
+
+/// Where the time went for a batch, passed to the callback of
+/// [`OdbcReaderBuilder::with_timing_callback`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchTiming {
+    /// Duration of the ODBC fetch, filling the buffers bound to the cursor.
+    pub fetch: Duration,
+    /// Duration of filling the arrow array of each column from its buffer, one element for each
+    /// field of the batch, before it is transformed.
+    pub columns: Vec<Duration>,
+}
+
+/// Progress of a reader, passed to the callback of [`OdbcReaderBuilder::with_progress_callback`]
+/// after each batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FetchProgress {
+    /// Number of batches yielded so far, including the current one.
+    pub num_batches: usize,
+    /// Total number of rows in the batches yielded so far. Same as
+    /// [`OdbcReader::num_rows_fetched`].
+    pub num_rows: usize,
+}
+
+/// A diagnostic record (e.g. a warning) the driver reported while the reader has been querying the
+/// metadata of a column in order to choose how to bind it. See
+/// [`OdbcReaderBuilder::with_diagnostic_callback`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnDiagnostic {
+    /// Zero based index of the column in the result set.
+    pub column_index: usize,
+    /// Name of the column.
+    pub column_name: String,
+    /// Five character SQLSTATE code, e.g. `01S02` for an option value which has been changed by
+    /// the driver.
+    pub state: String,
+    /// Driver specific error code.
+    pub native_error: i32,
+    /// Message text of the diagnostic record.
+    pub message: String,
+}
+
+impl Default for OdbcReaderBuilder {
+    fn default() -> Self {
+        Self {
+            max_num_rows_per_batch: 10_000,
+            max_total_rows: None,
+            max_buffer_bytes: None,
+            schema: None,
+            strategy_options: StrategyOptions::default(),
+            column_lookups: HashMap::new(),
+            text_coercions: HashMap::new(),
+            dictionary_columns: HashSet::new(),
+            timestamps_as_text: HashSet::new(),
+            hex_columns: HashSet::new(),
+            streamed_text: HashSet::new(),
+            all_binary_as_hex: false,
+            nullability_overrides: HashMap::new(),
+            custom_strategies: HashMap::new(),
+            max_text_sizes: HashMap::new(),
+            big_endian_elements: HashMap::new(),
+            time_unit: None,
+            timestamp_unit: None,
+            type_rules: Vec::new(),
+            tiny_int_1_as_boolean: false,
+            lossy_utf8: false,
+            unsigned_integers: false,
+            rowversion_as_uint64: false,
+            backend_profile: BackendProfile::Generic,
+            trim_fixed_size_text: false,
+            widen_integers: false,
+            sql_type_metadata: false,
+            forbid_text_fallback: false,
+            null_flags: Vec::new(),
+            sort_keys: Vec::new(),
+            target_schema: None,
+            log_strategies: false,
+            mapping_error_policy: MappingErrorPolicy::Fail,
+            max_columns: None,
+            projection: None,
+            sql_rewriter: None,
+            on_diagnostic: None,
+            on_progress: None,
+            on_timing: None,
+            cancellation_token: None,
+            batch_transformer: None,
+            field_ids: None,
+            parquet_hints: None,
+        }
+    }
+}
+
+impl OdbcReaderBuilder {
+    /// Creates a builder with default options. By default batches hold at most `10_000` rows and
+    /// the schema is inferred from the metadata of the cursor.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Maximum batch size requested from the datasource.
+    pub fn with_max_num_rows_per_batch(&mut self, max_num_rows_per_batch: usize) -> &mut Self {
+        self.max_num_rows_per_batch = max_num_rows_per_batch;
+        self
+    }
+
+    /// Limits the number of rows per batch, so the buffers bound to the cursor occupy at most
+    /// `max_buffer_bytes` bytes. The batches hold as many rows as fit, but not more than
+    /// [`Self::with_max_num_rows_per_batch`]. E.g. to cap the memory of result sets with wide
+    /// text columns. Building fails with [`Error::BufferBudgetExceeded`] if not even a single row
+    /// fits. A [`ConcurrentOdbcReader`] allocates this amount for each buffer set of its ring. No
+    /// limit by default.
+    pub fn with_max_buffer_bytes(&mut self, max_buffer_bytes: usize) -> &mut Self {
+        self.max_buffer_bytes = Some(max_buffer_bytes);
+        self
+    }
+
+    /// Read at most `max_total_rows` rows in total, e.g. to sample a table without spelling out a
+    /// `LIMIT` clause in the SQL dialect of the data source. The last batch is truncated, and the
+    /// reader stops once the limit is reached. Batches are not larger than the limit, so a limit
+    /// smaller than [`Self::with_max_num_rows_per_batch`] does not fetch rows only to discard them.
+    /// A limit of `0` yields no batches, but a reader with the schema of the result set. No limit
+    /// by default.
+    pub fn with_max_total_rows(&mut self, max_total_rows: usize) -> &mut Self {
+        self.max_total_rows = Some(max_total_rows);
+        self
+    }
+
+    /// Arrow schema. Describes the type of the Arrow Arrays in the record batches, but is also
+    /// used to determine CData type requested from the data source. If not set, the schema is
+    /// inferred from the data source. Fields are matched with the columns of the result set by
+    /// position. Building fails with [`Error::SchemaMismatch`] if the schema has more fields than
+    /// the result set has columns. Trailing columns without a field are not fetched. In strict mode
+    /// (see [`Self::with_strict`]) the names of the fields must match the columns exactly.
+    pub fn with_schema(&mut self, schema: SchemaRef) -> &mut Self {
+        self.schema = Some(schema);
+        self
+    }
+
+    /// Cast the columns of the result set into the types of the fields with the same name in
+    /// `schema`, using the cast kernel of arrow. E.g. to fetch an `INTEGER` column as `Int64` or
+    /// text as `Date32`, without post processing each batch. Other columns keep the type of the
+    /// inferred (or explicitly specified) schema. Unlike [`Self::with_schema`] the target type does
+    /// not need to be supported by a read strategy, as long as arrow can cast the fetched type
+    /// into it. Building the reader fails if it can not, or if `schema` contains a field without a
+    /// column of the same name. Values which can not be cast (e.g. due to overflow) fail the batch
+    /// with an error. The nullability of the columns is not changed.
+    pub fn with_target_schema(&mut self, schema: SchemaRef) -> &mut Self {
+        self.target_schema = Some(schema);
+        self
+    }
+
+    /// Allows you to specify upper limits for binary and / or text buffer types. See
+    /// [`BufferAllocationOptions`].
+    pub fn with_buffer_allocation_options(
+        &mut self,
+        buffer_allocation_options: BufferAllocationOptions,
+    ) -> &mut Self {
+        self.strategy_options.buffer_allocation_options = buffer_allocation_options;
+        self
+    }
+
+    /// Limits the buffer of the text column named `column_name` to `max_text_size` elements,
+    /// overriding [`BufferAllocationOptions::max_text_size`] for this column. Use this to allow
+    /// large values in a few columns, while keeping the buffers for all other columns small.
+    pub fn with_max_text_size_for(
+        &mut self,
+        column_name: impl Into<String>,
+        max_text_size: usize,
+    ) -> &mut Self {
+        self.max_text_sizes
+            .insert(column_name.into(), max_text_size);
+        self
+    }
+
+    /// Replace the values of the column named `column_name` with the values of a lookup table.
+    /// The column is fetched as described by the schema, but emitted as a `Utf8` array. The field
+    /// in the schema of the reader is changed accordingly. Has no effect, if no column of that
+    /// name exists.
+    pub fn with_column_lookup(
+        &mut self,
+        column_name: impl Into<String>,
+        lookup: ColumnLookup,
+    ) -> &mut Self {
+        self.column_lookups.insert(column_name.into(), lookup);
+        self
+    }
+
+    /// Parse the values of the column named `column_name` into `data_type`, if it is fetched as
+    /// text. Use this to recover numeric columns which fall back to text, because the driver
+    /// reports unusable metadata for them. `decimal_separator` is replaced with `.` before parsing,
+    /// so e.g. `','` parses `3,5` as `3.5`. Leading and trailing whitespace is ignored. The field
+    /// in the schema of the reader is changed to `data_type`. Has no effect, if no column of that
+    /// name exists, or if it is not fetched as text.
+    ///
+    /// Building the reader fails with [`Error::UnsupportedCoercion`], if `data_type` is not
+    /// numeric. Values which can not be parsed are reported as errors then fetching the batch.
+    pub fn with_text_coercion(
+        &mut self,
+        column_name: impl Into<String>,
+        data_type: DataType,
+        decimal_separator: char,
+    ) -> &mut Self {
+        self.text_coercions
+            .insert(column_name.into(), (data_type, decimal_separator));
+        self
+    }
+
+    /// Values of the binary column named `column_name` hold big endian elements of `element_size`
+    /// bytes each, e.g. packed 32 Bit integers for an `element_size` of `4`. The bytes of each
+    /// element are reversed into native byte order while fetching, so the values can be
+    /// reinterpreted as native integers downstream. Has no effect, if no column of that name
+    /// exists, or if it is not fetched as `Binary` or `FixedSizeBinary`. Values with a length which
+    /// is not a multiple of `element_size` are reported as errors then fetching the batch.
+    ///
+    /// # Panics
+    ///
+    /// If `element_size` is `0`.
+    pub fn with_big_endian_elements(
+        &mut self,
+        column_name: impl Into<String>,
+        element_size: usize,
+    ) -> &mut Self {
+        assert!(element_size > 0, "Element size must be at least one byte.");
+        self.big_endian_elements
+            .insert(column_name.into(), element_size);
+        self
+    }
+
+    /// Time of day columns (e.g. `TIME`) in the inferred schema are represented as `Time32` or
+    /// `Time64` arrays of `time_unit`, regardless of the precision reported by the data source.
+    /// This keeps the schema stable across sources reporting different precisions. Without this
+    /// option the unit is chosen by the precision of each column (see
+    /// [`crate::arrow_schema_from`]). Has no effect on an explicitly specified schema.
+    ///
+    /// Building the reader fails, if `time_unit` can not represent the fractional seconds reported
+    /// for the column, unless truncation is allowed using [`Self::with_time_truncation`].
+    pub fn with_time_unit(&mut self, time_unit: TimeUnit) -> &mut Self {
+        self.time_unit = Some(time_unit);
+        self
+    }
+
+    /// Set to `true` in order to allow fetching time of day columns into an arrow time unit which
+    /// can not represent all fractional seconds reported for the column. Excess digits are
+    /// truncated. `false` by default.
+    pub fn with_time_truncation(&mut self, allow_time_truncation: bool) -> &mut Self {
+        self.strategy_options.allow_time_truncation = allow_time_truncation;
+        self
+    }
+
+    /// Byte order of GUIDs (e.g. `UNIQUEIDENTIFIER` columns) fetched into `FixedSizeBinary(16)`
+    /// arrays. GUIDs are fetched in their text representation, so the same logical GUID results
+    /// in the same bytes regardless of the data source. [`GuidByteOrder::BigEndian`] by default.
+    pub fn with_guid_byte_order(&mut self, guid_byte_order: GuidByteOrder) -> &mut Self {
+        self.strategy_options.guid_byte_order = guid_byte_order;
+        self
+    }
+
+    /// Representation of GUID columns (e.g. `UNIQUEIDENTIFIER`) in the inferred schema. By default
+    /// ([`GuidRepresentation::Text`]) they are fetched as text formatted by the driver, which may
+    /// differ in casing and braces between drivers. [`GuidRepresentation::CanonicalText`] emits
+    /// lower case hyphenated text, and [`GuidRepresentation::Binary`] infers `FixedSizeBinary(16)`
+    /// with the byte order of [`Self::with_guid_byte_order`]. Canonical text also applies to `Utf8`
+    /// fields of GUID columns in an explicitly specified schema.
+    pub fn with_guid_representation(
+        &mut self,
+        guid_representation: GuidRepresentation,
+    ) -> &mut Self {
+        self.strategy_options.guid_representation = guid_representation;
+        self
+    }
+
+    /// Set to `true` in order to infer `UInt64` for `rowversion` columns of Microsoft SQL Server
+    /// (also known as `timestamp`, yet unrelated to points in time), interpreting their bytes as
+    /// big endian integer. This is the order in which the counter increments. By default they are
+    /// inferred as `FixedSizeBinary(8)`, preserving the raw bytes. `UInt64` fields of any
+    /// `BINARY(8)` column in an explicitly specified schema are fetched the same way.
+    pub fn with_rowversion_as_uint64(&mut self, rowversion_as_uint64: bool) -> &mut Self {
+        self.rowversion_as_uint64 = rowversion_as_uint64;
+        self
+    }
+
+    /// Attach parquet field ids to the fields of the emitted schema, using the metadata key
+    /// `PARQUET:field_id`. Parquet writers like the one in the `parquet` crate pick them up, which
+    /// allows table formats like Apache Iceberg to track columns across renames. No field ids are
+    /// assigned by default.
+    pub fn with_field_ids(&mut self, field_ids: FieldIds) -> &mut Self {
+        self.field_ids = Some(field_ids);
+        self
+    }
+
+    /// Annotate the fields of the emitted schema with parquet encoding and compression hints, for
+    /// a downstream parquet writer to pick up. See [`ParquetHints`]. No hints are emitted by
+    /// default.
+    pub fn with_parquet_hints(&mut self, parquet_hints: ParquetHints) -> &mut Self {
+        self.parquet_hints = Some(parquet_hints);
+        self
+    }
+
+    /// Set to `true` in order to share one dictionary across all batches for columns fetched into
+    /// `Dictionary` arrays of text. Values seen first in a later batch are appended to the
+    /// dictionary, so the keys of all batches refer to the same values and batches can be
+    /// concatenated without unifying their dictionaries. The dictionary keeps growing with each
+    /// distinct value though, so choose a key type wide enough for all distinct values of the
+    /// entire result set. Exceeding it fails the batch. `false` by default, then each batch has its
+    /// own dictionary.
+    pub fn with_shared_dictionaries(&mut self, shared_dictionaries: bool) -> &mut Self {
+        self.strategy_options.shared_dictionaries = shared_dictionaries;
+        self
+    }
+
+    /// Limits the number of distinct values in the dictionary of columns fetched into `Dictionary`
+    /// arrays of text. A batch exceeding it is emitted without deduplicating its values, each value
+    /// referenced by its own key, and a warning is logged. This keeps dictionary encoding cheap to
+    /// enable speculatively, for columns which turn out not to have a low cardinality. The schema
+    /// stays the same. A shared dictionary (see [`Self::with_shared_dictionaries`]) exceeding the
+    /// limit stops being shared, and subsequent batches get their own dictionaries. No limit by
+    /// default.
+    pub fn with_max_dictionary_size(&mut self, max_dictionary_size: usize) -> &mut Self {
+        self.strategy_options.max_dictionary_size = Some(max_dictionary_size);
+        self
+    }
+
+    /// Fetch columns whose names match the regular expression `pattern` into arrow arrays of
+    /// `data_type`, instead of the type inferred from the metadata of the data source. E.g. to
+    /// fetch all columns ending in `_id` as `Int64`. Rules are tried in the order they are added,
+    /// the first matching rule wins. Columns without a matching rule keep their inferred type. Has
+    /// no effect on an explicitly specified schema.
+    ///
+    /// Fails with [`Error::InvalidColumnPattern`] if `pattern` is not a valid regular expression.
+    pub fn with_type_rule(
+        &mut self,
+        pattern: &str,
+        data_type: DataType,
+    ) -> Result<&mut Self, Error> {
+        let regex = Regex::new(pattern).map_err(|source| Error::InvalidColumnPattern {
+            pattern: pattern.to_owned(),
+            source,
+        })?;
+        self.type_rules.push((regex, data_type));
+        Ok(self)
+    }
+
+    /// Set to `true` in order to fail with [`ColumnFailure::UnexpectedTextFallback`], if a column
+    /// of the inferred schema would be fetched as text, even though its SQL type is not a text
+    /// type. This happens for SQL types without a dedicated mapping to an arrow type, or if the
+    /// driver reports unexpected metadata. Useful for scheduled jobs, which should not silently
+    /// degrade typed columns to text. Checked after applying [`Self::with_time_unit`] and
+    /// [`Self::with_type_rule`]. Has no effect on an explicitly specified schema. `false` by
+    /// default.
+    pub fn with_forbid_text_fallback(&mut self, forbid_text_fallback: bool) -> &mut Self {
+        self.forbid_text_fallback = forbid_text_fallback;
+        self
+    }
+
+    /// Decides how fractional seconds reported by the data source are mapped to timestamps with
+    /// a coarser arrow time unit, e.g. nanoseconds to `Timestamp(Microsecond)`.
+    /// [`TimestampRounding::Truncate`] by default.
+    pub fn with_timestamp_rounding(&mut self, rounding: TimestampRounding) -> &mut Self {
+        self.strategy_options.timestamp_rounding = rounding;
+        self
+    }
+
+    /// Timestamp columns in the inferred schema are represented as `Timestamp` arrays of
+    /// `time_unit`, regardless of the precision reported by the data source. By default the unit is
+    /// chosen by the precision of each column, with nanoseconds for precisions beyond six digits.
+    /// Nanoseconds limit the range to the years 1677 to 2262, so choose a coarser unit for
+    /// historical or far future dates, which do not require sub microsecond resolution. Fractional
+    /// seconds the unit can not represent are rounded as specified by
+    /// [`Self::with_timestamp_rounding`]. Has no effect on timestamps with time zone and on an
+    /// explicitly specified schema.
+    pub fn with_timestamp_unit(&mut self, time_unit: TimeUnit) -> &mut Self {
+        self.timestamp_unit = Some(time_unit);
+        self
+    }
+
+    /// Derive the `NULL` values of the column `value_column` from the column `flag_column`, rather
+    /// than from the indicators reported by the data source. For schemas which store a separate
+    /// "is null" flag instead of SQL `NULL`. A value becomes `NULL` wherever its flag is `true`, or
+    /// a non zero integer. Values with a `NULL` flag are kept. The field of `value_column` in the
+    /// emitted schema is nullable. `flag_column` itself is emitted unchanged.
+    ///
+    /// Building the reader fails with [`Error::UnknownColumn`] if the result set has no column with
+    /// either name, or with [`Error::UnsupportedNullFlagType`] if `flag_column` is neither boolean
+    /// nor an integer.
+    pub fn with_null_flag(
+        &mut self,
+        value_column: impl Into<String>,
+        flag_column: impl Into<String>,
+    ) -> &mut Self {
+        self.null_flags
+            .push((value_column.into(), flag_column.into()));
+        self
+    }
+
+    /// Declare the binary column `sort_key_column` to hold the collation sort keys of the text
+    /// column `text_column`. Comparing text fetched into arrow byte by byte, ignores the collation
+    /// of the data source, so e.g. sorting or range partitioning the batches may disagree with the
+    /// order of the database. The sort key column is fetched like any other binary column, but its
+    /// field in the emitted schema carries the metadata entry `arrow_odbc.sort_key_for` with the
+    /// name of `text_column` as value. Comparing the sort keys as bytes, reproduces the order of
+    /// the collation.
+    ///
+    /// ODBC offers no way to obtain sort keys, so the query must select them, using a function of
+    /// the data source. E.g. `NLSSORT` for Oracle or `COLLATION_KEY_BIT` for DB2. Whether the keys
+    /// preserve the collation order is up to the data source. Microsoft SQL Server offers no such
+    /// function.
+    ///
+    /// Building the reader fails with [`Error::UnknownColumn`] if the result set has no column with
+    /// either name, or with [`Error::UnsupportedSortKeyType`] if `sort_key_column` is not binary.
+    pub fn with_sort_key(
+        &mut self,
+        text_column: impl Into<String>,
+        sort_key_column: impl Into<String>,
+    ) -> &mut Self {
+        self.sort_keys
+            .push((text_column.into(), sort_key_column.into()));
+        self
+    }
+
+    /// Set to `true` in order to log the name, SQL type, arrow type and buffer description of each
+    /// column then building the reader, using the `log` crate with level `info`. Helps
+    /// understanding how columns are mapped in production, without changing any code. `false` by
+    /// default.
+    pub fn with_log_strategies(&mut self, log_strategies: bool) -> &mut Self {
+        self.log_strategies = log_strategies;
+        self
+    }
+
+    /// Fetch the timestamp column named `column_name` into a `Date32` array. Use this for date
+    /// columns, which the driver reports as timestamps. Each value is validated to be at midnight,
+    /// and any other time of day is reported as an error then fetching the batch, rather than being
+    /// truncated. If the schema is inferred, the field of the column is changed to `Date32`.
+    /// Otherwise the validation applies to an explicitly specified `Date32` field of that name.
+    pub fn with_timestamp_as_date(&mut self, column_name: impl Into<String>) -> &mut Self {
+        self.strategy_options
+            .timestamps_as_dates
+            .insert(column_name.into());
+        self
+    }
+
+    /// Fetch the timestamp column named `column_name` into a `Date32` array, discarding the time of
+    /// day of its values. Like [`Self::with_timestamp_as_date`], but for columns, which are
+    /// expected to hold dates, yet are not worth failing the read over the odd value with another
+    /// time of day. In strict mode (see [`Self::with_strict`]) discarding the time of day is not
+    /// allowed, and values which are not at midnight are reported as errors. If the schema is
+    /// inferred, the field of the column is changed to `Date32`.
+    pub fn with_timestamp_truncated_to_date(
+        &mut self,
+        column_name: impl Into<String>,
+    ) -> &mut Self {
+        self.strategy_options
+            .timestamps_truncated_to_dates
+            .insert(column_name.into());
+        self
+    }
+
+    /// Fetch the timestamp column named `column_name` as `Utf8` text, in the format the driver
+    /// converts it to, e.g. `2300-01-01 00:00:00.1234567`. Use this for timestamps outside the
+    /// range of nanosecond timestamps between 1677 and 2262, which otherwise fail the batch with
+    /// [`MappingError::OutOfRangeTimestampNs`]. Applies to timestamps with and without time zone.
+    /// Takes precedence over [`Self::with_timestamp_as_date`],
+    /// [`Self::with_timestamp_truncated_to_date`] and [`Self::with_timestamp_unit`], and the
+    /// columns are exempt from [`Self::with_forbid_text_fallback`]. Has no effect on an explicitly
+    /// specified schema, which may specify `Utf8` fields directly.
+    pub fn with_timestamp_as_text(&mut self, column_name: impl Into<String>) -> &mut Self {
+        self.timestamps_as_text.insert(column_name.into());
+        self
+    }
+
+    /// Fetch the text column named `column_name` into a `Dictionary(Int32, Utf8)` array, which
+    /// stores each distinct value only once. Use this for columns with few distinct values, like
+    /// status codes or country codes. The cardinality of a column is not known before fetching it,
+    /// so columns are not dictionary encoded unless requested. `NULL`s are emitted as `NULL` keys.
+    /// Has no effect on an explicitly specified schema, which may specify `Dictionary` fields with
+    /// any integer key type directly. See also [`Self::with_shared_dictionaries`].
+    pub fn with_dictionary_encoding(&mut self, column_name: impl Into<String>) -> &mut Self {
+        self.dictionary_columns.insert(column_name.into());
+        self
+    }
+
+    /// Emit the values of the binary column named `column_name` as `Utf8` text of lowercase hex
+    /// digits without prefix, e.g. `00ff`, for consumers which can not handle binary arrays. Empty
+    /// values become empty strings, while `NULL` stays `NULL`. Applies to columns fetched as
+    /// `Binary`, `LargeBinary` or `FixedSizeBinary`, whether inferred or explicitly specified. See
+    /// also [`Self::with_all_binary_as_hex`].
+    pub fn with_binary_as_hex(&mut self, column_name: impl Into<String>) -> &mut Self {
+        self.hex_columns.insert(column_name.into());
+        self
+    }
+
+    /// Retrieve the values of the text column named `column_name` piecewise with `SQLGetData`,
+    /// rather than into a buffer bound to the cursor. Values are emitted completely regardless of
+    /// their length, so neither a buffer fitting the largest possible value needs to be allocated,
+    /// nor are values truncated. Use this for columns like `VARCHAR(max)` holding large documents.
+    /// Only applies to readers built with [`Self::build_row_wise`], which fetch the result set row
+    /// by row.
+    pub fn with_streamed_text(&mut self, column_name: impl Into<String>) -> &mut Self {
+        self.streamed_text.insert(column_name.into());
+        self
+    }
+
+    /// Infer the field of the column named `column_name` as `nullable`, instead of relying on the
+    /// nullability reported by the driver. Use this if the application knows better, e.g. for
+    /// columns of views or computed columns, which drivers often report as nullable. Non nullable
+    /// columns are fetched without a validity bitmap, and into buffers without indicators where
+    /// possible.
+    ///
+    /// If a `NULL` arrives in a column overridden to be non nullable, fetching the batch fails with
+    /// [`MappingError::UnexpectedNull`], or with an error of the driver, if the buffer of the
+    /// column has no indicators. Has no effect on an explicitly specified schema, which specifies
+    /// the nullability of each field directly.
+    pub fn with_nullability(
+        &mut self,
+        column_name: impl Into<String>,
+        nullable: bool,
+    ) -> &mut Self {
+        self.nullability_overrides
+            .insert(column_name.into(), nullable);
+        self
+    }
+
+    /// Fetch the column at the zero based `column_index` using `strategy`, instead of the strategy
+    /// the reader would choose. Use this for conversions the reader does not anticipate, e.g.
+    /// parsing a proprietary binary format. The field of the column is changed to `data_type`,
+    /// whether the schema is inferred or explicitly specified. Other options of the builder still
+    /// apply to the arrays emitted by `strategy`.
+    ///
+    /// Building the reader fails with [`Error::UnknownColumnIndex`] if the result set has no column
+    /// at `column_index`, and with [`ColumnFailure::NonNullableBuffer`] if `strategy` binds a
+    /// buffer without indicators for a nullable column. Fetching a batch fails, if values do not
+    /// fit into the buffer, or `strategy` emits an array not of type `data_type`, or with a length
+    /// other than the number of rows in the batch.
+    pub fn with_column_strategy(
+        &mut self,
+        column_index: usize,
+        data_type: DataType,
+        strategy: Box<dyn ReadStrategy>,
+    ) -> &mut Self {
+        let custom = CustomStrategy {
+            data_type,
+            strategy: strategy.into(),
+        };
+        self.custom_strategies.insert(column_index, custom);
+        self
+    }
+
+    /// Set to `true` in order to emit the values of all binary columns as hex text, like
+    /// [`Self::with_binary_as_hex`] does for individual columns. `false` by default.
+    pub fn with_all_binary_as_hex(&mut self, all_binary_as_hex: bool) -> &mut Self {
+        self.all_binary_as_hex = all_binary_as_hex;
+        self
+    }
+
+    /// Buffer length used for columns fetched as text, if the driver reports no length for them.
+    /// E.g. for computed columns or types without a dedicated mapping. Only applies if neither
+    /// the SQL type nor the display size of the column yield a length, and is still subject to
+    /// [`BufferAllocationOptions::max_text_size`]. Without it, these columns cause an error then
+    /// building the reader. Values exceeding the length are reported as errors, unless truncation
+    /// is allowed with [`Self::with_text_truncation`].
+    pub fn with_default_text_length(&mut self, default_text_length: usize) -> &mut Self {
+        self.strategy_options.default_text_length = Some(default_text_length);
+        self
+    }
+
+    /// Buffer length used for columns fetched as text, if the driver reports a length of more than
+    /// `2^20` for them and [`BufferAllocationOptions::max_text_size`] is not set. Drivers report
+    /// such lengths, e.g. `2^31 - 1`, for columns without an upper bound, like `NVARCHAR(MAX)`, and
+    /// allocating them for each row would exhaust the memory. `8192` by default. Values exceeding
+    /// the length are reported as errors, unless truncation is allowed with
+    /// [`Self::with_text_truncation`]. Pass `usize::MAX` in order to allocate the reported length.
+    pub fn with_huge_text_length(&mut self, huge_text_length: usize) -> &mut Self {
+        self.strategy_options.huge_text_length = Some(huge_text_length);
+        self
+    }
+
+    /// Decides what happens to columns fetched as text, if the driver reports a size of zero for
+    /// them and [`BufferAllocationOptions::max_text_size`] is not set. E.g. for a single computed
+    /// column, which should not fail the entire query. [`ZeroSizedColumnPolicy::Fail`] by default.
+    pub fn with_zero_sized_column_policy(&mut self, policy: ZeroSizedColumnPolicy) -> &mut Self {
+        self.strategy_options.zero_sized_column_policy = policy;
+        self
+    }
+
+    /// Set to `true` in order to truncate text values exceeding the buffer allocated for their
+    /// column, rather than failing the batch with an error. Buffers may be too small, if the
+    /// maximum text size is limited (see [`BufferAllocationOptions::max_text_size`]), or the driver
+    /// under reports the size of the column. `false` by default, so no data is silently lost.
+    pub fn with_text_truncation(&mut self, allow_text_truncation: bool) -> &mut Self {
+        self.strategy_options.allow_text_truncation = allow_text_truncation;
+        self
+    }
+
+    /// Set to `true` in order to report values too large for half precision, which are fetched
+    /// into `Float16` fields, as errors. Otherwise they become infinite, like they would if cast.
+    /// `false` by default. Values too close to zero to be represented by a `Float16` become zero
+    /// either way. `Float16` is never inferred, but may be specified explicitly or by a type
+    /// rule.
+    pub fn with_strict_float16(&mut self, strict_float16: bool) -> &mut Self {
+        self.strategy_options.strict_float16 = strict_float16;
+        self
+    }
+
+    /// Set to `true` in order to turn the implicit assumptions the reader makes into errors, e.g.
+    /// for auditable pipelines. In strict mode
+    ///
+    /// * text of columns without a text SQL type, which are fetched as text, is verified to be
+    ///   ASCII, rather than assumed to be. Other values fail with [`MappingError::NonAsciiText`].
+    /// * text values exceeding their buffer and time of day columns with more fractional seconds
+    ///   than their arrow time unit are errors, even if [`Self::with_text_truncation`] or
+    ///   [`Self::with_time_truncation`] allow truncating them.
+    /// * values too large for `Float16` fields are errors, like with
+    ///   [`Self::with_strict_float16`], rather than becoming infinite.
+    /// * the fields of a schema passed to [`Self::with_schema`] must have the names of the columns
+    ///   of the result set, in the same order. Otherwise building fails with
+    ///   [`Error::SchemaMismatch`].
+    ///
+    /// `false` by default.
+    pub fn with_strict(&mut self, strict: bool) -> &mut Self {
+        self.strategy_options.strict = strict;
+        self
+    }
+
+    /// Set to `true` in order to emit empty text values as `NULL`, e.g. to match the behavior of
+    /// Oracle, which treats empty strings as `NULL`, across data sources. Applies only to nullable
+    /// columns fetched as `Utf8` or `LargeUtf8`. `false` by default, preserving exactly what the
+    /// driver reports.
+    pub fn with_empty_text_as_null(&mut self, empty_text_as_null: bool) -> &mut Self {
+        self.strategy_options.empty_text_as_null = empty_text_as_null;
+        self
+    }
+
+    /// What to do with values fetched from the database, which can not be converted into their
+    /// arrow type, e.g. a timestamp out of range. [`MappingErrorPolicy::Fail`] by default, failing
+    /// the entire batch. Any other policy replaces these values with `NULL` and keeps reading, so
+    /// all fields of the schema become nullable.
+    pub fn with_mapping_error_policy(&mut self, policy: MappingErrorPolicy) -> &mut Self {
+        self.mapping_error_policy = policy;
+        self
+    }
+
+    /// Set to `true` in order to describe the SQL type of each column in the metadata of its
+    /// inferred field, so downstream code, e.g. creating a destination table, can reproduce the
+    /// source types. Fields carry the entries `arrow_odbc.sql_type` with the data source specific
+    /// name of the type (e.g. `nvarchar`), if the driver reports it, as well as
+    /// `arrow_odbc.column_size` and `arrow_odbc.decimal_digits`. The nullability of the column is
+    /// reflected by the nullability of its field. `false` by default. Has no effect on an
+    /// explicitly specified schema.
+    pub fn with_sql_type_metadata(&mut self, sql_type_metadata: bool) -> &mut Self {
+        self.sql_type_metadata = sql_type_metadata;
+        self
+    }
+
+    /// Set to `true` in order to infer `Int64` for all signed integer columns, as well as unsigned
+    /// columns narrower than 64 Bits (see [`Self::with_unsigned_integers`]), rather than the
+    /// narrowest arrow type holding the values of their SQL type. E.g. a stored procedure may
+    /// return similarly shaped result sets, with an `INTEGER` column in one and a `BIGINT` column
+    /// in the other. Widening the integers gives the batches of both result sets the same schema,
+    /// so they can be concatenated. Result sets are described one at a time, so rather than
+    /// consulting the schemas of all result sets the integers are widened to the widest type.
+    /// `false` by default, keeping the type of each result set. Has no effect on an explicitly
+    /// specified schema. See also [`OdbcReader::into_next_result_set`].
+    pub fn with_widened_integers(&mut self, widen_integers: bool) -> &mut Self {
+        self.widen_integers = widen_integers;
+        self
+    }
+
+    /// Infer `data_type` for all integer columns, signed or unsigned, regardless of their width,
+    /// e.g. `Int64` for `TINYINT`, `SMALLINT`, `INTEGER` and `BIGINT` columns. Unlike
+    /// [`Self::with_widened_integers`] this also applies to unsigned 64 Bit columns, and allows for
+    /// narrower types. Columns are bound to buffers of their own width and their values converted,
+    /// so values which do not fit into `data_type` cause
+    /// [`crate::MappingError::IntegerOutOfRange`]. Takes precedence over
+    /// [`Self::with_widened_integers`]. Has no effect on an explicitly specified schema.
+    ///
+    /// # Panics
+    ///
+    /// If `data_type` is not an integer type.
+    pub fn with_unified_integers(&mut self, data_type: DataType) -> &mut Self {
+        assert!(
+            data_type.is_integer(),
+            "Integers can only be unified to an integer type."
+        );
+        self.strategy_options.unified_integers = Some(data_type);
+        self
+    }
+
+    /// Account for the quirks of a specific database management system. Use
+    /// [`BackendProfile::detect`] to choose the profile from the connection.
+    /// [`BackendProfile::Generic`] by default. Adjustments to the inferred data types have no
+    /// effect on an explicitly specified schema.
+    pub fn with_backend_profile(&mut self, backend_profile: BackendProfile) -> &mut Self {
+        self.backend_profile = backend_profile;
+        self
+    }
+
+    /// Set to `true` in order to remove the trailing spaces, which the values of fixed size text
+    /// columns (`CHAR` and `NCHAR`) are padded with, matching how ANSI SQL compares them. Variadic
+    /// text columns (e.g. `VARCHAR`) keep their trailing spaces. Applies to columns fetched into
+    /// `Utf8` arrays. `false` by default, keeping the values as returned by the driver, unless the
+    /// backend profile trims them (see [`Self::with_backend_profile`]).
+    pub fn with_trimmed_fixed_size_text(&mut self, trim_fixed_size_text: bool) -> &mut Self {
+        self.trim_fixed_size_text = trim_fixed_size_text;
+        self
+    }
+
+    /// Set to `true` in order to infer unsigned arrow integers (`UInt8`, `UInt16`, `UInt32` or
+    /// `UInt64`) of the same width for integer columns the driver reports as unsigned
+    /// (`SQL_DESC_UNSIGNED`). E.g. `TINYINT` of Microsoft SQL Server, which ranges from 0 to 255,
+    /// is inferred as `UInt8` rather than `Int8`, so values above 127 are preserved. `false` by
+    /// default, inferring signed integers for all integer columns. Has no effect on an explicitly
+    /// specified schema, which may use unsigned integers for any integer column.
+    pub fn with_unsigned_integers(&mut self, unsigned_integers: bool) -> &mut Self {
+        self.unsigned_integers = unsigned_integers;
+        self
+    }
+
+    /// Infer `Boolean` rather than `Int8` or `UInt8` for tiny integer columns with a display width
+    /// of one, i.e. `TINYINT(1)`. This follows the convention of MySQL, which declares boolean
+    /// columns this way. Wider tiny integers remain integers. Keep this disabled (default), if you
+    /// store values other than `0` and `1` in `TINYINT(1)` columns. Has no effect on an explicitly
+    /// specified schema. Always enabled by [`BackendProfile::MySql`].
+    pub fn with_tiny_int_1_as_boolean(&mut self, tiny_int_1_as_boolean: bool) -> &mut Self {
+        self.tiny_int_1_as_boolean = tiny_int_1_as_boolean;
+        self
+    }
+
+    /// Encoding in which text columns are requested from the driver. By default
+    /// ([`TextEncoding::Auto`]) text is fetched as UTF-16 on windows and as UTF-8 on other
+    /// platforms. Use [`TextEncoding::lookup`] to choose it from a table covering the platforms and
+    /// data sources of your deployments.
+    pub fn with_text_encoding(&mut self, text_encoding: TextEncoding) -> &mut Self {
+        self.strategy_options.text_encoding = text_encoding;
+        self
+    }
+
+    /// Decode narrow text values, which are not valid UTF-8, using `fallback`. E.g. to handle
+    /// columns holding a mix of UTF-8 and windows-1252 encoded text. A warning with the number of
+    /// affected values is logged for each batch in which the fallback had been applied. Without a
+    /// fallback, invalid UTF-8 is reported as [`MappingError::InvalidUtf8`] and handled according
+    /// to the [`MappingErrorPolicy`]. Only affects text fetched narrow, see
+    /// [`Self::with_text_encoding`].
+    pub fn with_decoding_fallback(
+        &mut self,
+        fallback: impl Fn(&[u8]) -> String + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.strategy_options.decoding_fallback = Some(DecodingFallback(Arc::new(fallback)));
+        self.lossy_utf8 = false;
+        self
+    }
+
+    /// Decode all narrow text values using `decoder`, rather than assuming them to be UTF-8. E.g.
+    /// for drivers returning text in the encoding of the client locale, like windows-1252 or
+    /// Shift-JIS. Any decoding library can be plugged in, e.g. with `encoding_rs`:
+    /// `|bytes| WINDOWS_1252.decode(bytes).0.into_owned()`. Takes precedence over
+    /// [`Self::with_decoding_fallback`]. Only affects text fetched narrow, see
+    /// [`Self::with_text_encoding`].
+    pub fn with_narrow_text_decoder(
+        &mut self,
+        decoder: impl Fn(&[u8]) -> String + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.strategy_options.narrow_text_decoder = Some(DecodingFallback(Arc::new(decoder)));
+        self
+    }
+
+    /// Set to `true` in order to replace invalid UTF-8 sequences in narrow text values with the
+    /// replacement character `U+FFFD`. E.g. for columns the driver reports as narrow, which
+    /// actually hold Latin-1 bytes. By default (`false`) invalid UTF-8 is reported as
+    /// [`MappingError::InvalidUtf8`]. Shorthand for [`Self::with_decoding_fallback`] with
+    /// [`String::from_utf8_lossy`], replacing any fallback set before. `false` only removes the
+    /// fallback installed by this method, a fallback set with [`Self::with_decoding_fallback`]
+    /// is kept.
+    pub fn with_lossy_utf8(&mut self, lossy: bool) -> &mut Self {
+        if lossy {
+            self.strategy_options.decoding_fallback = Some(DecodingFallback(Arc::new(|bytes| {
+                String::from_utf8_lossy(bytes).into_owned()
+            })));
+        } else if self.lossy_utf8 {
+            self.strategy_options.decoding_fallback = None;
+        }
+        self.lossy_utf8 = lossy;
+        self
+    }
+
+    /// Set to `true` in order to replace unpaired surrogates in wide text values with the
+    /// replacement character `U+FFFD`. By default (`false`) they are reported as an error then
+    /// fetching the batch, with the offending code unit and the row within the batch. Some drivers
+    /// return lone surrogates for corrupt `NCHAR` data. Only affects text fetched wide, see
+    /// [`Self::with_text_encoding`].
+    pub fn with_lossy_utf16(&mut self, lossy: bool) -> &mut Self {
+        self.strategy_options.lossy_utf16 = lossy;
+        self
+    }
+
+    /// Fail with [`Error::TooManyColumns`] before binding any buffers, if the result set has more
+    /// than `max_columns` columns. A safety valve for interactive tools executing arbitrary
+    /// queries, e.g. a pivot producing thousands of columns, which would otherwise exhaust memory
+    /// then allocating the buffers. Unlimited by default.
+    pub fn with_max_columns(&mut self, max_columns: usize) -> &mut Self {
+        self.max_columns = Some(max_columns);
+        self
+    }
+
+    /// Only fetch the columns named `column_names`, in this order. Only these columns are bound
+    /// to buffers and converted, so the reader emits one field for each of them. Use this to read
+    /// a few columns of a wide result set, without rewriting the query. Options referring to
+    /// columns by index, like [`Self::with_column_strategy`], still refer to the columns of the
+    /// result set. A schema passed to [`Self::with_schema`] must describe all columns of the
+    /// result set.
+    ///
+    /// Building the reader fails with [`Error::UnknownColumn`] if the result set has no column of
+    /// one of the names, and with [`Error::DuplicateProjection`] if a column is named more than
+    /// once. Replaces any projection passed before.
+    pub fn with_projection(
+        &mut self,
+        column_names: impl IntoIterator<Item = impl Into<String>>,
+    ) -> &mut Self {
+        let names = column_names.into_iter().map(Into::into).collect();
+        self.projection = Some(Projection::Names(names));
+        self
+    }
+
+    /// Like [`Self::with_projection`], but refers to the columns by their zero based index in the
+    /// result set. Building the reader fails with [`Error::UnknownColumnIndex`] if the result set
+    /// has no column with one of the indices.
+    pub fn with_projection_indices(
+        &mut self,
+        column_indices: impl IntoIterator<Item = usize>,
+    ) -> &mut Self {
+        let indices = column_indices.into_iter().collect();
+        self.projection = Some(Projection::Indices(indices));
+        self
+    }
+
+    /// Rewrite queries passed to [`Self::build_with_query`] and [`Self::validate`] before they are
+    /// send to the database. E.g. to centrally add hints like `OPTION (RECOMPILE)` for Microsoft
+    /// SQL Server, `LIMIT` clauses or schema qualifiers. Errors report the rewritten query.
+    pub fn with_sql_rewriter(
+        &mut self,
+        sql_rewriter: impl Fn(&str) -> String + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.sql_rewriter = Some(SqlRewriter(Arc::new(sql_rewriter)));
+        self
+    }
+
+    /// Invoke `callback` for each diagnostic record the driver reports while building the reader
+    /// queries the metadata of a column, to choose the buffer it is bound to. E.g. to capture
+    /// warnings about substituted types programmatically. Diagnostics of the call binding the
+    /// buffers to the cursor are not observable, they are only logged by `odbc-api`.
+    pub fn with_diagnostic_callback(
+        &mut self,
+        callback: impl Fn(&ColumnDiagnostic) + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.on_diagnostic = Some(DiagnosticCallback(Arc::new(callback)));
+        self
+    }
+
+    /// Invoke `callback` after each batch fetched by a reader, with the number of batches and rows
+    /// fetched so far. E.g. to drive a progress bar or to log the throughput of long running
+    /// reads. The callback runs on the thread iterating the reader, before the batch is yielded,
+    /// so it must not block on anything waiting for the reader. Batches failing to be fetched or
+    /// converted are not reported. All readers built by this builder share the same callback.
+    pub fn with_progress_callback(
+        &mut self,
+        callback: impl Fn(&FetchProgress) + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.on_progress = Some(ProgressCallback(Arc::new(callback)));
+        self
+    }
+
+    /// Invoke `callback` after each batch fetched by a reader, with the time it took to fetch the
+    /// batch from the data source and to fill the arrow array of each column. Use it to find out
+    /// where the time of a read goes, then tuning batch sizes and buffer limits. Nothing is
+    /// measured without a callback. While measuring, the columns of a batch are converted one after
+    /// another, even with the `rayon` feature, so their durations do not skew each other. The
+    /// batches are not altered. Batches failing to be fetched or converted are not reported.
+    /// [`ConcurrentOdbcReader`]s do not invoke the callback. All readers built by this builder
+    /// share the same callback.
+    pub fn with_timing_callback(
+        &mut self,
+        callback: impl Fn(&BatchTiming) + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.on_timing = Some(TimingCallback(Arc::new(callback)));
+        self
+    }
+
+    /// Allow cancelling the reads of readers built by this builder from another thread, using a
+    /// clone of `token`. Readers check the token before fetching each batch, and a fetch blocking
+    /// in the driver is interrupted with `SQLCancel`. A cancelled reader yields [`Cancelled`] as an
+    /// external arrow error once, and ends afterwards. Tell it apart from other errors using
+    /// [`is_cancelled`]. Readers built after the token has been cancelled yield [`Cancelled`]
+    /// right away.
+    pub fn with_cancellation_token(&mut self, token: CancellationToken) -> &mut Self {
+        self.cancellation_token = Some(token);
+        self
+    }
+
+    /// Apply `transformer` to each record batch, before it is yielded by the reader. A single place
+    /// for arrow native post processing, like renaming columns, deriving new ones or filtering
+    /// rows.
+    ///
+    /// The schema of the reader is determined by transforming an empty batch while building it.
+    /// Building fails with [`Error::TransformingBatch`] if that fails. A transformed batch with a
+    /// schema different from the reader is reported as an error. All readers built by this
+    /// builder share the same transformer. The transformer runs on the thread iterating the
+    /// reader, also for a [`ConcurrentOdbcReader`].
+    pub fn with_batch_transformer(
+        &mut self,
+        transformer: impl FnMut(RecordBatch) -> Result<RecordBatch, ArrowError> + Send + 'static,
+    ) -> &mut Self {
+        self.batch_transformer = Some(BatchTransformer::new(transformer));
+        self
+    }
+
+    /// Executes `query` and constructs a reader for its result set. The query is rewritten first,
+    /// if a rewriter has been specified using [`Self::with_sql_rewriter`].
+    pub fn build_with_query<'c>(
+        &self,
+        connection: &'c Connection<'_>,
+        query: &str,
+        params: impl ParameterCollectionRef,
+    ) -> Result<OdbcReader<CursorImpl<StatementImpl<'c>>>, Error> {
+        let sql = self.rewrite(query);
+        let cursor = match connection.execute(&sql, params) {
+            Ok(Some(cursor)) => cursor,
+            Ok(None) => return Err(Error::NoResultSet { sql }),
+            Err(source) => return Err(Error::ExecutingQuery { source, sql }),
+        };
+        self.build(cursor)
+    }
+
+    /// Executes `query` on a statement preallocated by the caller, and constructs a reader for its
+    /// result set. The reader borrows the statement, rather than owning it, so the statement can be
+    /// executed again once the reader is dropped, or destroyed using
+    /// [`OdbcReader::into_statement`], which closes the cursor. Neither the reader nor the
+    /// statement own the connection, which suits connections borrowed from a pool, which must be
+    /// returned after the read. The query is rewritten first, if a rewriter has been specified
+    /// using [`Self::with_sql_rewriter`].
+    pub fn build_with_preallocated<'s, 'c>(
+        &self,
+        statement: &'s mut Preallocated<'c>,
+        query: &str,
+        params: impl ParameterCollectionRef,
+    ) -> Result<OdbcReader<CursorImpl<&'s mut StatementImpl<'c>>>, Error> {
+        let sql = self.rewrite(query);
+        let cursor = match statement.execute(&sql, params) {
+            Ok(Some(cursor)) => cursor,
+            Ok(None) => return Err(Error::NoResultSet { sql }),
+            Err(source) => return Err(Error::ExecutingQuery { source, sql }),
+        };
+        self.build(cursor)
+    }
+
+    /// Builds a reader which fetches the next batch while the current one is converted into arrow
+    /// arrays on a separate thread, using two sets of buffers. This overlaps the round trips to
+    /// the data source with the conversion. Equivalent to calling
+    /// [`OdbcReader::into_concurrent`] with a ring depth of `2` on the reader returned by
+    /// [`Self::build`]. Use the latter directly to fetch further ahead.
+    pub fn build_concurrent<C: Cursor>(&self, cursor: C) -> Result<ConcurrentOdbcReader<C>, Error> {
+        self.build(cursor)?
+            .into_concurrent(2)
+            .map_err(Error::UnbindingBuffers)
+    }
+
+    /// Executes each of `queries` and zips the readers for their result sets into a
+    /// [`SplitOdbcReader`]. Use this to read result sets with more columns than the driver can bind
+    /// at once, by splitting the columns across several queries. Each query must select the `key`
+    /// column and order by it. See [`SplitOdbcReader`] for the requirements and limitations.
+    pub fn build_split<'c>(
+        &self,
+        connection: &'c Connection<'_>,
+        queries: &[&str],
+        key: &str,
+    ) -> Result<SplitOdbcReader<CursorImpl<StatementImpl<'c>>>, Error> {
+        let readers = queries
+            .iter()
+            .map(|query| self.build_with_query(connection, query, ()))
+            .collect::<Result<_, _>>()?;
+        SplitOdbcReader::new(readers, key)
+    }
+
+    /// Construct a new [`OdbcReader`] instance.
+    ///
+    /// # Parameters
+    ///
+    /// * `cursor`: ODBC cursor used to fetch batches from the data source. The constructor will
+    ///   bind buffers to this cursor in order to perform bulk fetches from the source. This is
+    ///   usually faster than fetching results row by row as it saves roundtrips to the database.
+    ///   The type of these buffers will be inferred from the arrow schema. Not every arrow type is
+    ///   supported though.
+    pub fn build<C: Cursor>(&self, mut cursor: C) -> Result<OdbcReader<C>, Error> {
+        let (source_schema, columns) = self.projected_schema(&mut cursor)?;
+        let column_strategies = self.column_strategies(&source_schema, &columns, &mut cursor)?;
+        let column_types = column_types(&mut cursor)?;
+        if self.log_strategies {
+            log_strategies(&source_schema, &columns, &column_strategies, &mut cursor);
+        }
+        let (schema, null_flags) = self.batch_schema(&source_schema)?;
+
+        let max_num_rows_per_batch =
+            self.buffer_rows(&column_strategies, &source_schema, &columns)?;
+        let column_indices: Vec<u16> = columns
+            .iter()
+            .map(|&index| (index + 1).try_into().unwrap())
+            .collect();
+        let fallible = self
+            .strategy_options
+            .buffer_allocation_options
+            .fallibale_allocations;
+        let buffers = column_strategies
+            .iter()
+            .zip(&column_indices)
+            .enumerate()
+            .map(|(buffer_index, (strategy, &col_index))| {
+                let desc = strategy.buffer_desc();
+                let buffer = if fallible {
+                    AnyBuffer::try_from_desc(max_num_rows_per_batch, desc).map_err(|err| {
+                        let err = err.add_context(buffer_index.try_into().unwrap());
+                        map_allocation_error(err, &source_schema, &columns)
+                    })?
+                } else {
+                    AnyBuffer::from_desc(max_num_rows_per_batch, desc)
+                };
+                Ok((col_index, buffer))
+            })
+            .collect::<Result<_, Error>>()?;
+        // Safety: Projected columns are unique, and all buffers have been allocated with the same
+        // capacity.
+        let row_set_buffer =
+            unsafe { ColumnarBuffer::new_unchecked(max_num_rows_per_batch, buffers) };
+        let output_schema = self.transformed_schema(&schema)?;
+        let cancellation = self.cancellation_token.as_ref().map(|token| {
+            // Safety: The registration is part of the reader owning the cursor, and is dropped
+            // before it.
+            unsafe { token.register(cursor.as_stmt_ref().as_sys()) }
+        });
+        // Safety: The statement is part of the reader owning the cursor.
+        let statement = unsafe { FetchStatement::new(cursor.as_stmt_ref().as_sys()) };
+        let cursor = cursor.bind_buffer(row_set_buffer).unwrap();
+        let num_columns = column_strategies.len();
+
+        Ok(OdbcReader {
+            column_strategies,
+            batch_schema: schema,
+            schema: output_schema,
+            cancellation,
+            cursor,
+            null_flags,
+            num_rows_fetched: 0,
+            num_batches_fetched: 0,
+            statement,
+            truncated_batches: vec![0; num_columns],
+            warnings: Vec::new(),
+            on_progress: self.on_progress.clone(),
+            on_timing: self.on_timing.clone(),
+            batch_transformer: self.batch_transformer.clone(),
+            mapping_error_policy: self.mapping_error_policy.clone(),
+            max_total_rows: self.max_total_rows,
+            column_types,
+            column_indices,
+        })
+    }
+
+    /// Schema of the record batches a reader built for the result set described by `metadata`
+    /// would emit, without binding any buffers or fetching any rows. E.g. to create a destination
+    /// table before streaming the batches into it. `metadata` is usually a cursor or a prepared
+    /// statement. The schema is derived with the same strategy selection as in [`Self::build`], so
+    /// it has the same types and nullability as the batches of the reader. Fails, if
+    /// [`Self::build`] would fail for the result set.
+    pub fn schema(&self, metadata: &mut impl ResultSetMetadata) -> Result<SchemaRef, Error> {
+        let (source_schema, columns) = self.projected_schema(metadata)?;
+        self.column_strategies(&source_schema, &columns, metadata)?;
+        let (schema, _null_flags) = self.batch_schema(&source_schema)?;
+        self.transformed_schema(&schema)
+    }
+
+    /// Number of bytes the buffers of a reader built for the result set described by `metadata`
+    /// would allocate, without allocating them. `metadata` is usually a cursor or a prepared
+    /// statement. Use it to choose [`Self::with_max_num_rows_per_batch`] for a memory budget,
+    /// e.g. for result sets with wide text columns, together with [`Self::bytes_per_row`]. A
+    /// [`ConcurrentOdbcReader`] allocates this once for each buffer set of its ring. The arrays
+    /// of the emitted batches are not included. Fails, if [`Self::build`] would fail for the
+    /// result set.
+    pub fn buffer_size(&self, metadata: &mut impl ResultSetMetadata) -> Result<usize, Error> {
+        let (source_schema, columns) = self.projected_schema(metadata)?;
+        let column_strategies = self.column_strategies(&source_schema, &columns, metadata)?;
+        let num_rows = self.buffer_rows(&column_strategies, &source_schema, &columns)?;
+        Ok(bytes_per_row(&column_strategies).saturating_mul(num_rows))
+    }
+
+    /// Number of bytes the buffers of a reader built for the result set described by `metadata`
+    /// would allocate for each row of a batch, including the indicators of nullable columns. See
+    /// [`Self::buffer_size`].
+    pub fn bytes_per_row(&self, metadata: &mut impl ResultSetMetadata) -> Result<usize, Error> {
+        let (source_schema, columns) = self.projected_schema(metadata)?;
+        let column_strategies = self.column_strategies(&source_schema, &columns, metadata)?;
+        Ok(bytes_per_row(&column_strategies))
+    }
+
+    /// Number of rows the buffers of the reader hold.
+    fn buffer_rows(
+        &self,
+        column_strategies: &[Box<dyn ReadStrategy>],
+        source_schema: &Schema,
+        columns: &[usize],
+    ) -> Result<usize, Error> {
+        let mut num_rows = self.max_num_rows_per_batch;
+        if let Some(max_buffer_bytes) = self.max_buffer_bytes {
+            let bytes_per_row = bytes_per_row(column_strategies);
+            if bytes_per_row > max_buffer_bytes {
+                let (buffer_index, column_bytes) = column_strategies
+                    .iter()
+                    .map(|strategy| strategy.buffer_desc().bytes_per_row())
+                    .enumerate()
+                    .max_by_key(|&(_, column_bytes)| column_bytes)
+                    .unwrap();
+                return Err(Error::BufferBudgetExceeded {
+                    max_buffer_bytes,
+                    bytes_per_row,
+                    index: columns[buffer_index],
+                    name: source_schema.field(buffer_index).name().clone(),
+                    column_bytes,
+                });
+            }
+            // A result set without columns takes no buffer at all.
+            num_rows = num_rows.min(max_buffer_bytes / bytes_per_row.max(1));
+        }
+        // Buffers must hold at least one row, even if no rows are to be read.
+        if let Some(max_total_rows) = self.max_total_rows {
+            num_rows = num_rows.min(max_total_rows.max(1));
+        }
+        Ok(num_rows)
+    }
+
+    /// Prepares `query` and checks whether a reader could be built for its result set with the
+    /// options of this builder, without executing it or fetching any rows. Returns the schema of
+    /// the record batches the reader would emit. In contrast to [`Self::build`] all columns are
+    /// checked, and every column which can not be fetched is reported in
+    /// [`Error::ColumnFailures`].
+    ///
+    /// Useful to catch unsupported column types early, e.g. in tests of a data pipeline.
+    pub fn validate(&self, connection: &Connection<'_>, query: &str) -> Result<SchemaRef, Error> {
+        let sql = self.rewrite(query);
+        let mut prepared = connection
+            .prepare(&sql)
+            .map_err(|source| Error::PreparingQuery { source, sql })?;
+        let (source_schema, columns) = match self.projected_schema(&mut prepared) {
+            Ok(projected) => projected,
+            Err(error @ Error::ColumnFailure { .. }) => {
+                return Err(Error::ColumnFailures(vec![error]))
+            }
+            Err(error) => return Err(error),
+        };
+        let failures: Vec<_> = source_schema
+            .fields()
+            .iter()
+            .zip(&columns)
+            .filter_map(|(field, &index)| self.column_strategy(field, &mut prepared, index).err())
+            .collect();
+        if !failures.is_empty() {
+            return Err(Error::ColumnFailures(failures));
+        }
+        let (schema, _null_flags) = self.batch_schema(&source_schema)?;
+        self.transformed_schema(&schema)
+    }
+
+    /// Chooses the read strategy of each column described by `source_schema`. `columns` holds the
+    /// index of the column in the result set for each field.
+    fn column_strategies(
+        &self,
+        source_schema: &Schema,
+        columns: &[usize],
+        metadata: &mut impl ResultSetMetadata,
+    ) -> Result<Vec<Box<dyn ReadStrategy>>, Error> {
+        source_schema
+            .fields()
+            .iter()
+            .zip(columns)
+            .map(|(field, &index)| {
+                let strategy = self.column_strategy(field, metadata, index);
+                if let Some(DiagnosticCallback(callback)) = &self.on_diagnostic {
+                    report_diagnostics(metadata, index, field, callback.as_ref());
+                }
+                strategy
+            })
+            .collect()
+    }
+
+    /// Schema of the converted batches, before they are transformed, and the null flags applied
+    /// to them.
+    fn batch_schema(&self, source_schema: &SchemaRef) -> Result<(SchemaRef, Vec<NullFlag>), Error> {
+        let schema = self.output_schema(source_schema);
+        self.check_sort_keys(&schema)?;
+        self.check_target_schema(source_schema)?;
+        let null_flags = self
+            .null_flags
+            .iter()
+            .map(|(value, flag)| NullFlag::resolve(&schema, value, flag))
+            .collect::<Result<_, Error>>()?;
+        Ok((schema, null_flags))
+    }
+
+    /// Schema of the batches emitted by the reader, after applying the batch transformer.
+    fn transformed_schema(&self, schema: &SchemaRef) -> Result<SchemaRef, Error> {
+        match &self.batch_transformer {
+            Some(transformer) => transformer
+                .output_schema(schema)
+                .map_err(Error::TransformingBatch),
+            None => Ok(schema.clone()),
+        }
+    }
+
+    fn rewrite(&self, query: &str) -> String {
+        match &self.sql_rewriter {
+            Some(SqlRewriter(rewrite)) => rewrite(query),
+            None => query.to_owned(),
+        }
+    }
+
+    /// Fields of the source schema for the projected columns, along with the index of each
+    /// column in the result set.
+    fn projected_schema(
+        &self,
+        metadata: &mut impl ResultSetMetadata,
+    ) -> Result<(SchemaRef, Vec<usize>), Error> {
+        let schema = self.source_schema(metadata)?;
+        let num_columns = schema.fields().len();
+        let columns: Vec<usize> = match &self.projection {
+            None => return Ok((schema, (0..num_columns).collect())),
+            Some(Projection::Names(names)) => names
+                .iter()
+                .map(|name| {
+                    schema
+                        .index_of(name)
+                        .map_err(|_| Error::UnknownColumn { name: name.clone() })
+                })
+                .collect::<Result<_, _>>()?,
+            Some(Projection::Indices(indices)) => {
+                if let Some(&index) = indices.iter().find(|&&index| index >= num_columns) {
+                    return Err(Error::UnknownColumnIndex { index, num_columns });
+                }
+                indices.clone()
+            }
+        };
+        let mut projected = HashSet::new();
+        if let Some(&index) = columns.iter().find(|&&index| !projected.insert(index)) {
+            let name = schema.field(index).name().clone();
+            return Err(Error::DuplicateProjection { index, name });
+        }
+        let schema = schema.project(&columns).unwrap();
+        Ok((Arc::new(schema), columns))
+    }
+
+    /// Schema describing the values fetched from the data source. Inferred, if not given by the
+    /// user.
+    fn source_schema(&self, metadata: &mut impl ResultSetMetadata) -> Result<SchemaRef, Error> {
+        let schema = if let Some(schema) = &self.schema {
+            check_user_schema(schema, metadata, self.strategy_options.strict)?;
+            schema.clone()
+        } else {
+            let schema = arrow_schema_from(metadata)?;
+            let schema = if let Some(time_unit) = &self.time_unit {
+                with_time_unit_for_time_columns(schema, metadata, time_unit.clone())?
+            } else {
+                schema
+            };
+            let schema = self.backend_profile.adjust_schema(schema, metadata)?;
+            let schema = self.timestamps_as_text(schema);
+            let schema = self.with_timestamp_unit_for_timestamp_columns(schema);
+            let schema = self.timestamps_as_dates(schema);
+            let schema = self.dictionary_encode(schema);
+            let schema = if self.strategy_options.guid_representation == GuidRepresentation::Binary
+            {
+                guids_as_binary(schema, metadata)?
+            } else {
+                schema
+            };
+            let schema = if self.unsigned_integers {
+                unsigned_integers(schema, metadata)
+            } else {
+                schema
+            };
+            let schema = if self.tiny_int_1_as_boolean
+                || self.backend_profile.infers_tiny_int_1_as_boolean()
+            {
+                tiny_int_1_as_boolean(schema, metadata)?
+            } else {
+                schema
+            };
+            let schema = if self.widen_integers {
+                widen_integers(schema)
+            } else {
+                schema
+            };
+            let schema = match &self.strategy_options.unified_integers {
+                Some(data_type) => unify_integers(schema, data_type),
+                None => schema,
+            };
+            let schema = if self.rowversion_as_uint64 {
+                rowversions_as_uint64(schema, metadata)?
+            } else {
+                schema
+            };
+            let schema = self.apply_type_rules(schema);
+            let schema = self.override_nullability(schema);
+            let schema = if self.sql_type_metadata {
+                with_sql_type_metadata(schema, metadata)?
+            } else {
+                schema
+            };
+            if self.forbid_text_fallback {
+                check_text_fallback(&schema, metadata, &self.timestamps_as_text)?;
+            }
+            Arc::new(schema)
+        };
+        let schema = self.nullable_for_mapping_errors(schema);
+        let schema = self.with_custom_strategy_types(schema)?;
+        if let Some(max_columns) = self.max_columns {
+            let num_columns = schema.fields().len();
+            if num_columns > max_columns {
+                return Err(Error::TooManyColumns {
+                    num_columns,
+                    max_columns,
+                });
+            }
+        }
+        Ok(schema)
+    }
+
+    /// Marks all fields as nullable, unless the [`MappingErrorPolicy`] fails the batch, since
+    /// values which can not be converted are replaced with `NULL`.
+    fn nullable_for_mapping_errors(&self, schema: SchemaRef) -> SchemaRef {
+        if matches!(self.mapping_error_policy, MappingErrorPolicy::Fail) {
+            return schema;
+        }
+        let fields: Vec<_> = schema
+            .fields()
+            .iter()
+            .map(|field| field.as_ref().clone().with_nullable(true))
+            .collect();
+        Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone()))
+    }
+
+    /// Replaces the data types of the fields of columns passed to [`Self::with_column_strategy`].
+    fn with_custom_strategy_types(&self, schema: SchemaRef) -> Result<SchemaRef, Error> {
+        if self.custom_strategies.is_empty() {
+            return Ok(schema);
+        }
+        let num_columns = schema.fields().len();
+        if let Some(&index) = self
+            .custom_strategies
+            .keys()
+            .find(|&&index| index >= num_columns)
+        {
+            return Err(Error::UnknownColumnIndex { index, num_columns });
+        }
+        let fields: Vec<_> = schema
+            .fields()
+            .iter()
+            .enumerate()
+            .map(|(index, field)| match self.custom_strategies.get(&index) {
+                Some(custom) => {
+                    Field::new(field.name(), custom.data_type.clone(), field.is_nullable())
+                        .with_metadata(field.metadata().clone())
+                }
+                None => field.as_ref().clone(),
+            })
+            .collect();
+        Ok(Arc::new(Schema::new_with_metadata(
+            fields,
+            schema.metadata().clone(),
+        )))
+    }
+
+    /// Replaces the unit of timestamp fields without time zone with the one passed to
+    /// [`Self::with_timestamp_unit`].
+    fn with_timestamp_unit_for_timestamp_columns(&self, schema: Schema) -> Schema {
+        let Some(unit) = &self.timestamp_unit else {
+            return schema;
+        };
+        let fields: Vec<_> = schema
+            .fields()
+            .iter()
+            .map(|field| match field.data_type() {
+                DataType::Timestamp(_, None) => Field::new(
+                    field.name(),
+                    DataType::Timestamp(unit.clone(), None),
+                    field.is_nullable(),
+                )
+                .with_metadata(field.metadata().clone()),
+                _ => field.as_ref().clone(),
+            })
+            .collect();
+        Schema::new_with_metadata(fields, schema.metadata().clone())
+    }
+
+    /// Replaces the timestamp fields of columns passed to [`Self::with_timestamp_as_text`] with
+    /// `Utf8` fields.
+    fn timestamps_as_text(&self, schema: Schema) -> Schema {
+        if self.timestamps_as_text.is_empty() {
+            return schema;
+        }
+        let fields: Vec<_> = schema
+            .fields()
+            .iter()
+            .map(|field| {
+                if matches!(field.data_type(), DataType::Timestamp(..))
+                    && self.timestamps_as_text.contains(field.name())
+                {
+                    Field::new(field.name(), DataType::Utf8, field.is_nullable())
+                        .with_metadata(field.metadata().clone())
+                } else {
+                    field.as_ref().clone()
+                }
+            })
+            .collect();
+        Schema::new_with_metadata(fields, schema.metadata().clone())
+    }
+
+    /// Replaces the timestamp fields of columns passed to [`Self::with_timestamp_as_date`] or
+    /// [`Self::with_timestamp_truncated_to_date`] with `Date32` fields.
+    fn timestamps_as_dates(&self, schema: Schema) -> Schema {
+        let timestamps_as_dates = &self.strategy_options.timestamps_as_dates;
+        let truncated = &self.strategy_options.timestamps_truncated_to_dates;
+        if timestamps_as_dates.is_empty() && truncated.is_empty() {
+            return schema;
+        }
+        let fields: Vec<_> = schema
+            .fields()
+            .iter()
+            .map(|field| {
+                if matches!(field.data_type(), DataType::Timestamp(..))
+                    && (timestamps_as_dates.contains(field.name())
+                        || truncated.contains(field.name()))
+                {
+                    Field::new(field.name(), DataType::Date32, field.is_nullable())
+                        .with_metadata(field.metadata().clone())
+                } else {
+                    field.as_ref().clone()
+                }
+            })
+            .collect();
+        Schema::new_with_metadata(fields, schema.metadata().clone())
+    }
+
+    /// Replaces the text fields of columns passed to [`Self::with_dictionary_encoding`] with
+    /// `Dictionary(Int32, Utf8)` fields.
+    fn dictionary_encode(&self, schema: Schema) -> Schema {
+        if self.dictionary_columns.is_empty() {
+            return schema;
+        }
+        let fields: Vec<_> = schema
+            .fields()
+            .iter()
+            .map(|field| {
+                if matches!(field.data_type(), DataType::Utf8 | DataType::LargeUtf8)
+                    && self.dictionary_columns.contains(field.name())
+                {
+                    let data_type =
+                        DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8));
+                    Field::new(field.name(), data_type, field.is_nullable())
+                        .with_metadata(field.metadata().clone())
+                } else {
+                    field.as_ref().clone()
+                }
+            })
+            .collect();
+        Schema::new_with_metadata(fields, schema.metadata().clone())
+    }
+
+    /// Changes the nullability of fields of columns passed to [`Self::with_nullability`].
+    fn override_nullability(&self, schema: Schema) -> Schema {
+        if self.nullability_overrides.is_empty() {
+            return schema;
+        }
+        let fields: Vec<_> = schema
+            .fields()
+            .iter()
+            .map(|field| match self.nullability_overrides.get(field.name()) {
+                Some(&nullable) => field.as_ref().clone().with_nullable(nullable),
+                None => field.as_ref().clone(),
+            })
+            .collect();
+        Schema::new_with_metadata(fields, schema.metadata().clone())
+    }
+
+    /// Replaces the data types of fields with a matching type rule.
+    fn apply_type_rules(&self, schema: Schema) -> Schema {
+        if self.type_rules.is_empty() {
+            return schema;
+        }
+        let fields: Vec<_> = schema
+            .fields()
+            .iter()
+            .map(|field| {
+                let rule = self
+                    .type_rules
+                    .iter()
+                    .find(|(regex, _)| regex.is_match(field.name()));
+                match rule {
+                    Some((_, data_type)) => {
+                        Field::new(field.name(), data_type.clone(), field.is_nullable())
+                            .with_metadata(field.metadata().clone())
+                    }
+                    None => field.as_ref().clone(),
+                }
+            })
+            .collect();
+        Schema::new_with_metadata(fields, schema.metadata().clone())
+    }
+
+    fn column_strategy(
+        &self,
+        field: &Field,
+        metadata: &mut impl ResultSetMetadata,
+        index: usize,
+    ) -> Result<Box<dyn ReadStrategy>, Error> {
+        let col_index = (index + 1).try_into().unwrap();
+        let strategy = if let Some(custom) = self.custom_strategies.get(&index) {
+            custom_strategy(custom, field)
+        } else if let Some(&max_text_size) = self.max_text_sizes.get(field.name()) {
+            let mut options = self.strategy_options.clone();
+            options.buffer_allocation_options.max_text_size = Some(max_text_size);
+            choose_column_strategy(field, metadata, col_index, &options)
+        } else {
+            choose_column_strategy(field, metadata, col_index, &self.strategy_options)
+        }
+        .map_err(|cause| cause.into_crate_error(field.name().clone(), index))?;
+        let strategy = if (self.trim_fixed_size_text
+            || self.backend_profile.trims_fixed_size_text())
+            && field.data_type() == &DataType::Utf8
+            && is_fixed_size_text(metadata, col_index)
+                .map_err(|cause| cause.into_crate_error(field.name().clone(), index))?
+        {
+            Box::new(TrimTrailingSpaces::new(strategy))
+        } else {
+            strategy
+        };
+        let strategy = match (
+            self.big_endian_elements.get(field.name()),
+            field.data_type(),
+        ) {
+            (Some(&element_size), DataType::Binary | DataType::FixedSizeBinary(_)) => {
+                Box::new(ByteSwap::new(strategy, element_size))
+            }
+            _ => strategy,
+        };
+        let strategy = if self.is_hex(field) {
+            Box::new(HexText::new(strategy))
+        } else {
+            strategy
+        };
+        let strategy = match self.text_coercion(field) {
+            Some((data_type, _)) if !data_type.is_numeric() => {
+                return Err(Error::UnsupportedCoercion {
+                    name: field.name().clone(),
+                    data_type: data_type.clone(),
+                })
+            }
+            Some((data_type, decimal_separator)) => Box::new(CoerceText::new(
+                strategy,
+                data_type.clone(),
+                *decimal_separator,
+            )),
+            None => strategy,
+        };
+        let strategy = match self.column_lookups.get(field.name()) {
+            Some(lookup) => Box::new(Lookup::new(strategy, lookup.clone())),
+            None => strategy,
+        };
+        let strategy = match self.target_field(field) {
+            Some(target) => {
+                let source_type = self.converted_field(field).data_type().clone();
+                let target_type = target.data_type();
+                if &source_type == target_type {
+                    strategy
+                } else if can_cast_types(&source_type, target_type) {
+                    Box::new(Cast::new(strategy, target_type.clone()))
+                } else {
+                    return Err(ColumnFailure::UnsupportedCast {
+                        source_type,
+                        target_type: target_type.clone(),
+                    }
+                    .into_crate_error(field.name().clone(), index));
+                }
+            }
+            None => strategy,
+        };
+        // Nulls in non nullable arrays would fail assembling the record batch.
+        let strategy = if field.is_nullable() {
+            strategy
+        } else {
+            Box::new(NonNullable::new(strategy))
+        };
+        #[cfg(feature = "dump-buffers")]
+        let strategy = Box::new(crate::read_strategy::DumpOnFailure::new(
+            strategy,
+            field.clone(),
+            index,
+        ));
+        Ok(strategy)
+    }
+
+    /// Numeric type and decimal separator the values of the field are parsed into, if it is
+    /// fetched as text.
+    fn text_coercion(&self, field: &Field) -> Option<&(DataType, char)> {
+        if field.data_type() != &DataType::Utf8 {
+            return None;
+        }
+        self.text_coercions.get(field.name())
+    }
+
+    /// Field of the target schema the column described by `field` is cast into.
+    fn target_field(&self, field: &Field) -> Option<&Field> {
+        self.target_schema
+            .as_ref()?
+            .fields()
+            .iter()
+            .find(|target| target.name() == field.name())
+            .map(AsRef::as_ref)
+    }
+
+    /// Field describing the arrays of the column, after parsing its text or looking up its values.
+    fn converted_field(&self, field: &Field) -> Field {
+        let field = if self.is_hex(field) {
+            Field::new(field.name(), DataType::Utf8, field.is_nullable())
+                .with_metadata(field.metadata().clone())
         } else {
-            Arc::new(arrow_schema_from(&mut cursor)?)
+            field.clone()
+        };
+        let field = match self.text_coercion(&field) {
+            Some((data_type, _)) => {
+                Field::new(field.name(), data_type.clone(), field.is_nullable())
+                    .with_metadata(field.metadata().clone())
+            }
+            None => field.clone(),
+        };
+        match self.column_lookups.get(field.name()) {
+            Some(lookup) => lookup.map_field(&field),
+            None => field,
+        }
+    }
+
+    /// `true` if the values of the binary column are emitted as hex text.
+    fn is_hex(&self, field: &Field) -> bool {
+        matches!(
+            field.data_type(),
+            DataType::Binary | DataType::LargeBinary | DataType::FixedSizeBinary(_)
+        ) && (self.all_binary_as_hex || self.hex_columns.contains(field.name()))
+    }
+
+    /// Checks that each field of the target schema names a column of the result set.
+    fn check_target_schema(&self, source_schema: &Schema) -> Result<(), Error> {
+        let Some(target_schema) = &self.target_schema else {
+            return Ok(());
         };
+        for target in target_schema.fields() {
+            if source_schema.field_with_name(target.name()).is_err() {
+                return Err(Error::UnknownColumn {
+                    name: target.name().clone(),
+                });
+            }
+        }
+        Ok(())
+    }
 
-        let column_strategies: Vec<Box<dyn ReadStrategy>> = schema
+    /// Checks the pairs of columns passed to [`Self::with_sort_key`] against the schema of the
+    /// emitted record batches.
+    fn check_sort_keys(&self, schema: &Schema) -> Result<(), Error> {
+        self.sort_keys
+            .iter()
+            .try_for_each(|(text, sort_key)| check_sort_key(schema, text, sort_key))
+    }
+
+    /// Schema of the record batches emitted by the reader, given the schema of the values fetched
+    /// from the data source.
+    fn output_schema(&self, source_schema: &SchemaRef) -> SchemaRef {
+        if self.column_lookups.is_empty()
+            && self.text_coercions.is_empty()
+            && self.hex_columns.is_empty()
+            && !self.all_binary_as_hex
+            && self.field_ids.is_none()
+            && self.parquet_hints.is_none()
+            && self.null_flags.is_empty()
+            && self.sort_keys.is_empty()
+            && self.target_schema.is_none()
+        {
+            return source_schema.clone();
+        }
+        let fields: Vec<_> = source_schema
             .fields()
             .iter()
             .enumerate()
             .map(|(index, field)| {
-                let col_index = (index + 1).try_into().unwrap();
-                choose_column_strategy(field, &mut cursor, col_index, buffer_allocation_options)
-                    .map_err(|cause| cause.into_crate_error(field.name().clone(), index))
+                let field = self.converted_field(field);
+                let field = match self.target_field(&field) {
+                    Some(target) => Field::new(
+                        field.name(),
+                        target.data_type().clone(),
+                        field.is_nullable(),
+                    )
+                    .with_metadata(field.metadata().clone()),
+                    None => field,
+                };
+                let field = match &self.field_ids {
+                    Some(field_ids) => field_ids.assign(field, index),
+                    None => field,
+                };
+                let field = match &self.parquet_hints {
+                    Some(parquet_hints) => parquet_hints.annotate(field),
+                    None => field,
+                };
+                let field = match self
+                    .sort_keys
+                    .iter()
+                    .find(|(_, sort_key)| sort_key == field.name())
+                {
+                    Some((text, _)) => mark_sort_key(field, text),
+                    None => field,
+                };
+                if self
+                    .null_flags
+                    .iter()
+                    .any(|(value, _)| value == field.name())
+                {
+                    Field::new(field.name(), field.data_type().clone(), true)
+                        .with_metadata(field.metadata().clone())
+                } else {
+                    field
+                }
             })
-            .collect::<Result<_, _>>()?;
+            .collect();
+        Arc::new(Schema::new_with_metadata(
+            fields,
+            source_schema.metadata().clone(),
+        ))
+    }
+}
+
+/// Checks whether the result set of `query` could be fetched using an [`OdbcReader`] with default
+/// options, without executing the query. See [`OdbcReaderBuilder::validate`].
+///
+/// # Example
+///
+/// ```no_run
+/// use arrow_odbc::{odbc_api::Connection, validate};
+///
+/// fn check_query(connection: &Connection<'_>) -> Result<(), anyhow::Error> {
+///     let schema = validate(connection, "SELECT * FROM MyTable")?;
+///     println!("{schema:?}");
+///     Ok(())
+/// }
+/// ```
+pub fn validate(connection: &Connection<'_>, query: &str) -> Result<SchemaRef, Error> {
+    OdbcReaderBuilder::new().validate(connection, query)
+}
 
-        let descs = column_strategies.iter().map(|cs| cs.buffer_desc());
+/// SQL types of all columns in the result set.
+fn column_types(metadata: &mut impl ResultSetMetadata) -> Result<Vec<OdbcDataType>, Error> {
+    let num_cols: u16 = metadata
+        .num_result_cols()
+        .map_err(Error::UnableToRetrieveNumCols)?
+        .try_into()
+        .unwrap();
+    (1..=num_cols)
+        .map(|col_index| {
+            metadata.col_data_type(col_index).map_err(|cause| {
+                let index = (col_index - 1).into();
+                let name = metadata.col_name(col_index).unwrap_or_default();
+                ColumnFailure::FailedToDescribeColumn(cause).into_crate_error(name, index)
+            })
+        })
+        .collect()
+}
 
-        let row_set_buffer = if buffer_allocation_options.fallibale_allocations {
-            ColumnarAnyBuffer::try_from_descs(max_batch_size, descs)
-                .map_err(|err| map_allocation_error(err, &schema))?
+/// Replaces the inferred fields of time of day columns with `Time32` or `Time64` fields of
+/// `time_unit`.
+fn with_time_unit_for_time_columns(
+    schema: Schema,
+    metadata: &mut impl ResultSetMetadata,
+    time_unit: TimeUnit,
+) -> Result<Schema, Error> {
+    let data_type = match time_unit {
+        TimeUnit::Second | TimeUnit::Millisecond => DataType::Time32(time_unit),
+        TimeUnit::Microsecond | TimeUnit::Nanosecond => DataType::Time64(time_unit),
+    };
+    let mut fields = Vec::new();
+    for (index, field) in schema.fields().iter().enumerate() {
+        let sql_type = metadata
+            .col_data_type((index + 1).try_into().unwrap())
+            .map_err(|cause| {
+                ColumnFailure::FailedToDescribeColumn(cause)
+                    .into_crate_error(field.name().clone(), index)
+            })?;
+        let field = if time_precision(sql_type).is_some() {
+            Field::new(field.name(), data_type.clone(), field.is_nullable())
         } else {
-            ColumnarAnyBuffer::from_descs(max_batch_size, descs)
+            field.as_ref().clone()
         };
-        let cursor = cursor.bind_buffer(row_set_buffer).unwrap();
+        fields.push(field);
+    }
+    Ok(Schema::new_with_metadata(fields, schema.metadata().clone()))
+}
 
-        Ok(Self {
-            column_strategies,
-            schema,
-            cursor,
+/// Replaces the fields of integer columns the driver reports as unsigned with unsigned integer
+/// fields of the same width.
+fn unsigned_integers(schema: Schema, metadata: &mut impl ResultSetMetadata) -> Schema {
+    let mut fields = Vec::new();
+    for (index, field) in schema.fields().iter().enumerate() {
+        let col_index = (index + 1).try_into().unwrap();
+        let unsigned_type = match field.data_type() {
+            DataType::Int8 => Some(DataType::UInt8),
+            DataType::Int16 => Some(DataType::UInt16),
+            DataType::Int32 => Some(DataType::UInt32),
+            DataType::Int64 => Some(DataType::UInt64),
+            _ => None,
+        };
+        let field = match unsigned_type {
+            Some(data_type) if is_unsigned(metadata, col_index) => {
+                Field::new(field.name(), data_type, field.is_nullable())
+                    .with_metadata(field.metadata().clone())
+            }
+            _ => field.as_ref().clone(),
+        };
+        fields.push(field);
+    }
+    Schema::new_with_metadata(fields, schema.metadata().clone())
+}
+
+/// Replaces the fields of `Int8`, `Int16`, `Int32`, `UInt8`, `UInt16` and `UInt32` columns with
+/// `Int64` fields.
+fn widen_integers(schema: Schema) -> Schema {
+    let fields: Vec<_> = schema
+        .fields()
+        .iter()
+        .map(|field| match field.data_type() {
+            DataType::Int8
+            | DataType::Int16
+            | DataType::Int32
+            | DataType::UInt8
+            | DataType::UInt16
+            | DataType::UInt32 => Field::new(field.name(), DataType::Int64, field.is_nullable())
+                .with_metadata(field.metadata().clone()),
+            _ => field.as_ref().clone(),
+        })
+        .collect();
+    Schema::new_with_metadata(fields, schema.metadata().clone())
+}
+
+/// Replaces the fields of all integer columns with fields of `data_type`.
+fn unify_integers(schema: Schema, data_type: &DataType) -> Schema {
+    let fields: Vec<_> = schema
+        .fields()
+        .iter()
+        .map(|field| {
+            if field.data_type().is_integer() {
+                Field::new(field.name(), data_type.clone(), field.is_nullable())
+                    .with_metadata(field.metadata().clone())
+            } else {
+                field.as_ref().clone()
+            }
         })
+        .collect();
+    Schema::new_with_metadata(fields, schema.metadata().clone())
+}
+
+/// `true` if the column has the SQL type `CHAR` or `NCHAR`, whose values are padded to their
+/// length.
+fn is_fixed_size_text(
+    metadata: &mut impl ResultSetMetadata,
+    col_index: u16,
+) -> Result<bool, ColumnFailure> {
+    let sql_type = metadata
+        .col_data_type(col_index)
+        .map_err(ColumnFailure::FailedToDescribeColumn)?;
+    Ok(matches!(
+        sql_type,
+        OdbcDataType::Char { .. } | OdbcDataType::WChar { .. }
+    ))
+}
+
+/// Adds the name of the SQL type, the column size and the decimal digits reported by the driver to
+/// the metadata of each field.
+fn with_sql_type_metadata(
+    schema: Schema,
+    metadata: &mut impl ResultSetMetadata,
+) -> Result<Schema, Error> {
+    let mut fields = Vec::new();
+    for (index, field) in schema.fields().iter().enumerate() {
+        let col_index = (index + 1).try_into().unwrap();
+        let sql_type = metadata.col_data_type(col_index).map_err(|cause| {
+            ColumnFailure::FailedToDescribeColumn(cause)
+                .into_crate_error(field.name().clone(), index)
+        })?;
+        let mut field_metadata = field.metadata().clone();
+        if let Some(type_name) = type_name(metadata, col_index) {
+            field_metadata.insert("arrow_odbc.sql_type".to_owned(), type_name);
+        }
+        field_metadata.insert(
+            "arrow_odbc.column_size".to_owned(),
+            sql_type.column_size().to_string(),
+        );
+        field_metadata.insert(
+            "arrow_odbc.decimal_digits".to_owned(),
+            sql_type.decimal_digits().to_string(),
+        );
+        fields.push(field.as_ref().clone().with_metadata(field_metadata));
     }
+    Ok(Schema::new_with_metadata(fields, schema.metadata().clone()))
+}
 
-    /// Destroy the ODBC arrow reader and yield the underlyinng cursor object.
-    ///
-    /// One application of this is to process more than one result set in case you executed a stored
-    /// procedure.
-    pub fn into_cursor(self) -> Result<C, odbc_api::Error> {
-        let (cursor, _buffer) = self.cursor.unbind()?;
-        Ok(cursor)
+/// Replaces the fields of `rowversion` columns with `UInt64` fields.
+fn rowversions_as_uint64(
+    schema: Schema,
+    metadata: &mut impl ResultSetMetadata,
+) -> Result<Schema, Error> {
+    let mut fields = Vec::new();
+    for (index, field) in schema.fields().iter().enumerate() {
+        let col_index = (index + 1).try_into().unwrap();
+        let sql_type = metadata.col_data_type(col_index).map_err(|cause| {
+            ColumnFailure::FailedToDescribeColumn(cause)
+                .into_crate_error(field.name().clone(), index)
+        })?;
+        let field = if is_rowversion(metadata, col_index, sql_type) {
+            Field::new(field.name(), DataType::UInt64, field.is_nullable())
+                .with_metadata(field.metadata().clone())
+        } else {
+            field.as_ref().clone()
+        };
+        fields.push(field);
+    }
+    Ok(Schema::new_with_metadata(fields, schema.metadata().clone()))
+}
+
+/// Replaces the fields of GUID columns with `FixedSizeBinary(16)` fields.
+fn guids_as_binary(schema: Schema, metadata: &mut impl ResultSetMetadata) -> Result<Schema, Error> {
+    let mut fields = Vec::new();
+    for (index, field) in schema.fields().iter().enumerate() {
+        let col_index = (index + 1).try_into().unwrap();
+        let sql_type = metadata.col_data_type(col_index).map_err(|cause| {
+            ColumnFailure::FailedToDescribeColumn(cause)
+                .into_crate_error(field.name().clone(), index)
+        })?;
+        let field = if is_guid(sql_type) {
+            Field::new(
+                field.name(),
+                DataType::FixedSizeBinary(16),
+                field.is_nullable(),
+            )
+            .with_metadata(field.metadata().clone())
+        } else {
+            field.as_ref().clone()
+        };
+        fields.push(field);
+    }
+    Ok(Schema::new_with_metadata(fields, schema.metadata().clone()))
+}
+
+/// Wraps the strategy provided by the user, after checking its buffer against the column.
+fn custom_strategy(
+    custom: &CustomStrategy,
+    field: &Field,
+) -> Result<Box<dyn ReadStrategy>, ColumnFailure> {
+    let buffer_desc = custom.strategy.buffer_desc();
+    if field.is_nullable() && !is_nullable_buffer(buffer_desc) {
+        return Err(ColumnFailure::NonNullableBuffer { buffer_desc });
+    }
+    Ok(Box::new(Custom::new(
+        custom.strategy.clone(),
+        custom.data_type.clone(),
+    )))
+}
+
+fn log_strategies(
+    schema: &Schema,
+    columns: &[usize],
+    column_strategies: &[Box<dyn ReadStrategy>],
+    metadata: &mut impl ResultSetMetadata,
+) {
+    let fields = schema.fields().iter().zip(columns);
+    for ((field, &index), strategy) in fields.zip(column_strategies) {
+        let sql_type = metadata.col_data_type((index + 1).try_into().unwrap());
+        let sql_type = match &sql_type {
+            Ok(sql_type) => format!("{sql_type:?}"),
+            Err(_) => "unknown".to_owned(),
+        };
+        log::info!(
+            "Column {index} '{}' with SQL type {sql_type} is fetched into {:?} using buffer {:?}.",
+            field.name(),
+            field.data_type(),
+            strategy.buffer_desc()
+        );
+    }
+}
+
+/// Invokes `callback` for each diagnostic record of the last ODBC function called on the statement.
+fn report_diagnostics(
+    metadata: &mut impl ResultSetMetadata,
+    column_index: usize,
+    field: &Field,
+    callback: &(dyn Fn(&ColumnDiagnostic) + Send + Sync),
+) {
+    let stmt = metadata.as_stmt_ref();
+    let mut record = Record::default();
+    let mut record_number = 1;
+    while record.fill_from(&stmt, record_number) {
+        callback(&ColumnDiagnostic {
+            column_index,
+            column_name: field.name().clone(),
+            state: record.state.as_str().to_owned(),
+            native_error: record.native_error,
+            message: slice_to_cow_utf8(&record.message).into_owned(),
+        });
+        record_number += 1;
+    }
+}
+
+/// Compares the schema passed to [`OdbcReaderBuilder::with_schema`] with the columns of the result
+/// set. Fails if it has more fields than there are columns, or in `strict` mode unless the names of
+/// its fields are the names of the columns in the same order.
+fn check_user_schema(
+    schema: &Schema,
+    metadata: &mut impl ResultSetMetadata,
+    strict: bool,
+) -> Result<(), Error> {
+    let num_columns: u16 = metadata
+        .num_result_cols()
+        .map_err(Error::UnableToRetrieveNumCols)?
+        .try_into()
+        .unwrap();
+    let num_columns = usize::from(num_columns);
+    let num_fields = schema.fields().len();
+    if !strict && num_fields <= num_columns {
+        return Ok(());
+    }
+    let columns = (0..num_columns)
+        .map(|index| {
+            metadata
+                .col_name((index + 1).try_into().unwrap())
+                .map_err(|cause| {
+                    ColumnFailure::FailedToDescribeColumn(cause)
+                        .into_crate_error("Unknown".to_owned(), index)
+                })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let fields: Vec<&String> = schema.fields().iter().map(|field| field.name()).collect();
+    if num_fields == num_columns && fields.iter().zip(&columns).all(|(f, c)| *f == c) {
+        return Ok(());
+    }
+    let missing = columns
+        .iter()
+        .filter(|&column| !fields.contains(&column))
+        .cloned()
+        .collect();
+    let extra = fields
+        .iter()
+        .filter(|&&field| !columns.contains(field))
+        .map(|&field| field.clone())
+        .collect();
+    let reordered = fields
+        .iter()
+        .enumerate()
+        .filter(|&(index, &field)| columns.contains(field) && columns.get(index) != Some(field))
+        .map(|(_, &field)| field.clone())
+        .collect();
+    Err(Error::SchemaMismatch {
+        num_fields,
+        num_columns,
+        missing,
+        extra,
+        reordered,
+    })
+}
+
+/// Fails with [`ColumnFailure::UnexpectedTextFallback`] for the first `Utf8` field, whose column
+/// does not have a text SQL type. Fields named in `requested` have been asked to be text.
+fn check_text_fallback(
+    schema: &Schema,
+    metadata: &mut impl ResultSetMetadata,
+    requested: &HashSet<String>,
+) -> Result<(), Error> {
+    for (index, field) in schema.fields().iter().enumerate() {
+        if field.data_type() != &DataType::Utf8 || requested.contains(field.name()) {
+            continue;
+        }
+        let sql_type = metadata
+            .col_data_type((index + 1).try_into().unwrap())
+            .map_err(|cause| {
+                ColumnFailure::FailedToDescribeColumn(cause)
+                    .into_crate_error(field.name().clone(), index)
+            })?;
+        let is_text = matches!(
+            sql_type,
+            OdbcDataType::Char { .. }
+                | OdbcDataType::WChar { .. }
+                | OdbcDataType::Varchar { .. }
+                | OdbcDataType::WVarchar { .. }
+                | OdbcDataType::LongVarchar { .. }
+                | OdbcDataType::Other {
+                    data_type: SqlDataType::EXT_W_LONG_VARCHAR,
+                    ..
+                }
+        );
+        if !is_text {
+            return Err(ColumnFailure::UnexpectedTextFallback { sql_type }
+                .into_crate_error(field.name().clone(), index));
+        }
     }
+    Ok(())
+}
+
+/// Bytes of the buffers for the columns fetched with `column_strategies` for each row.
+fn bytes_per_row(column_strategies: &[Box<dyn ReadStrategy>]) -> usize {
+    column_strategies
+        .iter()
+        .map(|strategy| strategy.buffer_desc().bytes_per_row())
+        .fold(0, usize::saturating_add)
 }
 
-fn map_allocation_error(error: odbc_api::Error, schema: &Schema) -> Error {
+fn map_allocation_error(error: odbc_api::Error, schema: &Schema, columns: &[usize]) -> Error {
     match error {
         odbc_api::Error::TooLargeColumnBufferSize {
             buffer_index,
@@ -202,7 +2696,7 @@ fn map_allocation_error(error: odbc_api::Error, schema: &Schema) -> Error {
             element_size,
         } => Error::ColumnFailure {
             name: schema.field(buffer_index as usize).name().clone(),
-            index: buffer_index as usize,
+            index: columns[buffer_index as usize],
             source: ColumnFailure::TooLarge {
                 num_elements,
                 element_size,
@@ -221,24 +2715,84 @@ where
     type Item = Result<RecordBatch, ArrowError>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        match self.cursor.fetch_with_truncation_check(true) {
+        if is_row_limit_reached(self.num_rows_fetched, self.max_total_rows) {
+            return None;
+        }
+        if let Some(cancellation) = &mut self.cancellation {
+            if cancellation.is_cancelled() {
+                return cancellation.cancelled();
+            }
+        }
+        // Strategies report truncated values themselves, naming the buffer length of the column.
+        let fetch_start = self.on_timing.as_ref().map(|_| Instant::now());
+        let fetched = self.cursor.fetch_with_truncation_check(false);
+        let fetch_duration = fetch_start.map(|start| start.elapsed());
+        if let Ok(Some(_)) = &fetched {
+            self.statement.collect_warnings(&mut self.warnings);
+        }
+        match fetched {
             // We successfully fetched a batch from the database. Try to copy it into a record batch
             // and forward errors if any.
             Ok(Some(batch)) => {
-                let result_columns = odbc_batch_to_arrow_columns(&self.column_strategies, batch);
+                count_truncated_batches(batch, &mut self.truncated_batches);
+                let result_columns = match (&self.on_timing, fetch_duration) {
+                    (Some(TimingCallback(callback)), Some(fetch)) => {
+                        timed_odbc_batch_to_arrow_columns(
+                            &self.column_strategies,
+                            batch,
+                            &self.mapping_error_policy,
+                        )
+                        .map(|(columns, durations)| {
+                            callback(&BatchTiming {
+                                fetch,
+                                columns: durations,
+                            });
+                            columns
+                        })
+                    }
+                    _ => odbc_batch_to_arrow_columns(
+                        &self.column_strategies,
+                        batch,
+                        &self.mapping_error_policy,
+                    ),
+                };
                 // Fetching the but has been succesful, but could we convert all the values returned
                 // by the database into their respective arrow data types?
                 match result_columns {
-                    Ok(columns) => {
-                        let arrow_batch =
-                            RecordBatch::try_new(self.schema.clone(), columns).unwrap();
-                        Some(Ok(arrow_batch))
-                    }
+                    Ok(columns) => Some(apply_null_flags(columns, &self.null_flags).and_then(
+                        |columns| {
+                            let batch =
+                                RecordBatch::try_new(self.batch_schema.clone(), columns).unwrap();
+                            let batch = truncate_to_row_limit(
+                                batch,
+                                self.num_rows_fetched,
+                                self.max_total_rows,
+                            );
+                            self.num_rows_fetched += batch.num_rows();
+                            self.num_batches_fetched += 1;
+                            if let Some(on_progress) = &self.on_progress {
+                                on_progress.report(self.num_batches_fetched, self.num_rows_fetched);
+                            }
+                            match &self.batch_transformer {
+                                Some(transformer) => transformer.transform(batch, &self.schema),
+                                None => Ok(batch),
+                            }
+                        },
+                    )),
                     Err(err) => Some(Err(ArrowError::ExternalError(Box::new(err)))),
                 }
             }
             // We ran out of batches in the result set. End the iterator.
             Ok(None) => None,
+            // A fetch interrupted by cancelling the read fails.
+            Err(_)
+                if self
+                    .cancellation
+                    .as_ref()
+                    .is_some_and(Registration::is_cancelled) =>
+            {
+                self.cancellation.as_mut().unwrap().cancelled()
+            }
             // We had an error fetching the next batch from the database, let's report it as an
             // external error.
             Err(odbc_error) => Some(Err(ArrowError::ExternalError(Box::new(odbc_error)))),
@@ -255,25 +2809,72 @@ where
     }
 }
 
+/// `true` if a reader yielded `num_rows_fetched` rows and must not yield any more.
+fn is_row_limit_reached(num_rows_fetched: usize, max_total_rows: Option<usize>) -> bool {
+    max_total_rows.is_some_and(|max_total_rows| num_rows_fetched >= max_total_rows)
+}
+
+/// Truncates `batch`, so the total number of rows yielded by a reader does not exceed
+/// `max_total_rows`, given it yielded `num_rows_fetched` rows before.
+fn truncate_to_row_limit(
+    batch: RecordBatch,
+    num_rows_fetched: usize,
+    max_total_rows: Option<usize>,
+) -> RecordBatch {
+    match max_total_rows {
+        Some(max_total_rows) if num_rows_fetched + batch.num_rows() > max_total_rows => {
+            batch.slice(0, max_total_rows - num_rows_fetched)
+        }
+        _ => batch,
+    }
+}
+
 fn odbc_batch_to_arrow_columns(
     column_strategies: &[Box<dyn ReadStrategy>],
     batch: &ColumnarBuffer<AnyBuffer>,
+    mapping_error_policy: &MappingErrorPolicy,
 ) -> Result<Vec<ArrayRef>, MappingError> {
-    let arrow_columns = (0..column_strategies.len()).map(|i| {
-        let strat = &column_strategies[i];
-        let column_view = batch.column(i);
-        (strat, column_view)
-    }).collect::<Vec<_>>().par_iter().map(|(strat, column_view)| {
-        strat.fill_arrow_array(*column_view)
-    }).collect::<Result<Vec<_>, _>>()?;
-    // let arrow_columns = column_strategies
-    //     .as_parallel_slice()
-    //     .par_iter()
-    //     .enumerate()
-    //     .map(|(index, strat)| {
-    //         let column_view = batch.column(index);
-    //         strat.fill_arrow_array(column_view)
-    //     })
-    //     .collect::<Result<Vec<_>, _>>()?;
+    let columns = column_strategies
+        .iter()
+        .enumerate()
+        .map(|(index, strategy)| (index, strategy, batch.column(index)));
+    // Each strategy fills its own array from a distinct column view, so the columns of a batch
+    // can be converted in parallel.
+    #[cfg(feature = "rayon")]
+    let arrow_columns = columns
+        .collect::<Vec<_>>()
+        .into_par_iter()
+        .map(|(index, strategy, column_view)| {
+            strategy.fill_arrow_array(column_view, &ValueErrors::new(mapping_error_policy, index))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    #[cfg(not(feature = "rayon"))]
+    let arrow_columns = columns
+        .map(|(index, strategy, column_view)| {
+            strategy.fill_arrow_array(column_view, &ValueErrors::new(mapping_error_policy, index))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
     Ok(arrow_columns)
 }
+
+/// Like [`odbc_batch_to_arrow_columns`], but also measures how long filling the array of each
+/// column takes. The columns are converted one after another, so the durations of concurrently
+/// converted columns do not skew each other.
+fn timed_odbc_batch_to_arrow_columns(
+    column_strategies: &[Box<dyn ReadStrategy>],
+    batch: &ColumnarBuffer<AnyBuffer>,
+    mapping_error_policy: &MappingErrorPolicy,
+) -> Result<(Vec<ArrayRef>, Vec<Duration>), MappingError> {
+    let mut arrow_columns = Vec::with_capacity(column_strategies.len());
+    let mut durations = Vec::with_capacity(column_strategies.len());
+    for (index, strategy) in column_strategies.iter().enumerate() {
+        let start = Instant::now();
+        let array = strategy.fill_arrow_array(
+            batch.column(index),
+            &ValueErrors::new(mapping_error_policy, index),
+        )?;
+        durations.push(start.elapsed());
+        arrow_columns.push(array);
+    }
+    Ok((arrow_columns, durations))
+}
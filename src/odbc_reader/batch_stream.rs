@@ -0,0 +1,156 @@
+use std::{
+    future::Future,
+    panic::resume_unwind,
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+
+use arrow::{
+    datatypes::SchemaRef,
+    record_batch::{RecordBatch, RecordBatchReader},
+};
+use futures_core::Stream;
+use tokio::{
+    sync::{mpsc, oneshot},
+    task::{spawn_blocking, JoinHandle},
+};
+
+use crate::Error;
+
+/// Yields the batches of a reader as a [`Stream`], for async applications running on tokio. The
+/// reader is constructed and iterated on the blocking thread pool of tokio, so fetching does not
+/// block the async runtime. Batches are handed over through a bounded channel, so the reader
+/// fetches ahead at most `capacity` batches of the consumer.
+///
+/// Dropping the stream stops the reader, once the batch it is currently fetching (if any) has
+/// been fetched. The reader is then dropped on its thread, closing the cursor. To interrupt a
+/// long running fetch, pass a [`crate::CancellationToken`] to the builder of the reader and
+/// cancel it before dropping the stream.
+///
+/// A panic of the thread reading the batches is resumed on the task polling the stream. If tokio
+/// cancels reading the batches, because the runtime shuts down, the stream yields
+/// [`Error::BatchStreamCancelled`].
+///
+/// Requires the `async` feature.
+///
+/// # Example
+///
+/// ```no_run
+/// use arrow_odbc::{
+///     odbc_api::{ConnectionOptions, Environment},
+///     BatchStream, Error, OdbcReaderBuilder,
+/// };
+/// use futures_util::StreamExt;
+/// use std::sync::OnceLock;
+///
+/// static ENV: OnceLock<Environment> = OnceLock::new();
+///
+/// async fn read() -> Result<(), anyhow::Error> {
+///     let env = ENV.get_or_init(|| Environment::new().unwrap());
+///     let mut batches = BatchStream::new(2, move || {
+///         let sql = "SELECT * FROM MyTable";
+///         let cursor = env
+///             .connect_with_connection_string("DSN=MyDsn;", ConnectionOptions::default())
+///             .and_then(|connection| connection.into_cursor(sql, ()))
+///             .map_err(|source| Error::ExecutingQuery { source, sql: sql.to_owned() })?
+///             .ok_or_else(|| Error::NoResultSet { sql: sql.to_owned() })?;
+///         OdbcReaderBuilder::new().build(cursor)
+///     })
+///     .await?;
+///     while let Some(batch) = batches.next().await {
+///         let batch = batch?;
+///         // ... process batch ...
+///     }
+///     Ok(())
+/// }
+/// ```
+pub struct BatchStream {
+    schema: SchemaRef,
+    batches: mpsc::Receiver<Result<RecordBatch, Error>>,
+    /// Thread reading the batches. Awaited once the channel is closed, to resume its panic. `None`
+    /// once it has been awaited.
+    worker: Option<JoinHandle<()>>,
+}
+
+impl BatchStream {
+    /// Constructs the reader with `make_reader` on the blocking thread pool of tokio, and streams
+    /// its batches. Resolves once the reader has been constructed, failing with the error of
+    /// `make_reader`, if any, or with [`Error::BatchStreamCancelled`] if the runtime shuts down
+    /// before the reader has been constructed. Readers which can be send to other threads can also
+    /// be constructed up front and moved into `make_reader`.
+    ///
+    /// # Panics
+    ///
+    /// If `capacity` is `0`, or if called outside of a tokio runtime.
+    pub async fn new<R>(
+        capacity: usize,
+        make_reader: impl FnOnce() -> Result<R, Error> + Send + 'static,
+    ) -> Result<Self, Error>
+    where
+        R: RecordBatchReader,
+    {
+        let (schema_sender, schema_receiver) = oneshot::channel();
+        let (batch_sender, batches) = mpsc::channel(capacity);
+        let worker = spawn_blocking(move || {
+            let reader = match make_reader() {
+                Ok(reader) => reader,
+                Err(error) => {
+                    let _ = schema_sender.send(Err(error));
+                    return;
+                }
+            };
+            if schema_sender.send(Ok(reader.schema())).is_err() {
+                return;
+            }
+            for batch in reader {
+                // The stream has been dropped, so nobody is interested in further batches.
+                if batch_sender
+                    .blocking_send(batch.map_err(Error::ReadingBatch))
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        });
+        match schema_receiver.await {
+            Ok(Ok(schema)) => Ok(Self {
+                schema,
+                batches,
+                worker: Some(worker),
+            }),
+            Ok(Err(error)) => Err(error),
+            // The sender has only been dropped without sending, if `make_reader` panicked, or if
+            // the task has been cancelled before it ran.
+            Err(_) => match worker.await {
+                Err(error) if error.is_panic() => resume_unwind(error.into_panic()),
+                _ => Err(Error::BatchStreamCancelled),
+            },
+        }
+    }
+
+    /// Schema of the record batches yielded by the stream.
+    pub fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+impl Stream for BatchStream {
+    type Item = Result<RecordBatch, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if let Some(batch) = ready!(self.batches.poll_recv(cx)) {
+            return Poll::Ready(Some(batch));
+        }
+        // The reader is done. It has either been exhausted, panicked or been cancelled.
+        let Some(worker) = &mut self.worker else {
+            return Poll::Ready(None);
+        };
+        let joined = ready!(Pin::new(worker).poll(cx));
+        self.worker = None;
+        match joined {
+            Ok(()) => Poll::Ready(None),
+            Err(error) if error.is_panic() => resume_unwind(error.into_panic()),
+            Err(_) => Poll::Ready(Some(Err(Error::BatchStreamCancelled))),
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use std::sync::{Arc, Mutex};
+
+use arrow::{datatypes::SchemaRef, error::ArrowError, record_batch::RecordBatch};
+
+type TransformFn = dyn FnMut(RecordBatch) -> Result<RecordBatch, ArrowError> + Send;
+
+/// Wraps the function passed to [`crate::OdbcReaderBuilder::with_batch_transformer`], so the
+/// builder can implement `Debug` and `Clone`. All readers built by the same builder share it.
+#[derive(Clone)]
+pub struct BatchTransformer(Arc<Mutex<TransformFn>>);
+
+impl std::fmt::Debug for BatchTransformer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("BatchTransformer")
+    }
+}
+
+impl BatchTransformer {
+    pub fn new(
+        transform: impl FnMut(RecordBatch) -> Result<RecordBatch, ArrowError> + Send + 'static,
+    ) -> Self {
+        Self(Arc::new(Mutex::new(transform)))
+    }
+
+    /// Schema of the transformed batches, determined by transforming an empty batch of `schema`.
+    pub fn output_schema(&self, schema: &SchemaRef) -> Result<SchemaRef, ArrowError> {
+        let empty = RecordBatch::new_empty(schema.clone());
+        Ok(self.call(empty)?.schema())
+    }
+
+    /// Transforms `batch` and checks that the result has the `expected` schema.
+    pub fn transform(
+        &self,
+        batch: RecordBatch,
+        expected: &SchemaRef,
+    ) -> Result<RecordBatch, ArrowError> {
+        let transformed = self.call(batch)?;
+        if transformed.schema() != *expected {
+            return Err(ArrowError::SchemaError(format!(
+                "The batch transformer changed the schema of the batches from {expected:?} to \
+                {:?}. It must emit the same schema for every batch.",
+                transformed.schema()
+            )));
+        }
+        Ok(transformed)
+    }
+
+    fn call(&self, batch: RecordBatch) -> Result<RecordBatch, ArrowError> {
+        // A transformer which panicked holds no invariants of ours, so keep using it.
+        let mut transform = self
+            .0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        transform(batch)
+    }
+}
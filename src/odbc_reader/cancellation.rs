@@ -0,0 +1,142 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use arrow::{error::ArrowError, record_batch::RecordBatch};
+use odbc_api::sys::{HStmt, SQLCancel};
+use thiserror::Error;
+
+/// Cancels the reads of all readers built by an [`crate::OdbcReaderBuilder`] with this token, see
+/// [`crate::OdbcReaderBuilder::with_cancellation_token`]. Clones share the same state, so one
+/// clone can be moved to another thread in order to cancel the read from there.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<CancellationState>);
+
+#[derive(Debug, Default)]
+struct CancellationState {
+    cancelled: AtomicBool,
+    /// Statements of the readers currently registered with the token, by registration id.
+    statements: Mutex<HashMap<usize, StatementHandle>>,
+    next_id: AtomicUsize,
+}
+
+/// Statement handle of a reader, which is cancelled from another thread.
+#[derive(Debug, Clone, Copy)]
+struct StatementHandle(HStmt);
+
+// Safety: The handle is only passed to `SQLCancel`, which ODBC allows to be called from any
+// thread. Registrations remove their handle before the statement is freed.
+unsafe impl Send for StatementHandle {}
+
+impl CancellationToken {
+    /// A token, which has not been cancelled yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels the reads. Readers stop before fetching their next batch, and a fetch currently
+    /// blocking in the driver is interrupted using `SQLCancel`. Each reader then yields
+    /// [`Cancelled`] once, and ends afterwards. Cancelling can not be undone.
+    pub fn cancel(&self) {
+        self.0.cancelled.store(true, Ordering::SeqCst);
+        let statements = self.0.statements.lock().unwrap();
+        for statement in statements.values() {
+            // Safety: The statement is registered, so it has not been freed yet. It can not be
+            // freed while we hold the lock. The return value is ignored, the fetch reports the
+            // outcome.
+            unsafe {
+                let _ = SQLCancel(statement.0);
+            }
+        }
+    }
+
+    /// `true` if [`Self::cancel`] has been called.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Registers the statement of a reader, so it is cancelled along with the token.
+    ///
+    /// # Safety
+    ///
+    /// `statement` must stay valid until the returned registration is dropped, or updated.
+    pub(crate) unsafe fn register(&self, statement: HStmt) -> Registration {
+        let id = self.0.next_id.fetch_add(1, Ordering::SeqCst);
+        self.0
+            .statements
+            .lock()
+            .unwrap()
+            .insert(id, StatementHandle(statement));
+        Registration {
+            token: self.clone(),
+            id,
+            reported: false,
+        }
+    }
+}
+
+/// The statement of a reader registered with a [`CancellationToken`]. Removes the statement from
+/// the token then dropped, so it must be dropped before the statement is freed.
+#[derive(Debug)]
+pub(crate) struct Registration {
+    token: CancellationToken,
+    id: usize,
+    /// `true` once the reader yielded [`Cancelled`].
+    reported: bool,
+}
+
+impl Registration {
+    /// Replaces the registered statement, e.g. then the buffers of a reader are bound to another
+    /// cursor.
+    ///
+    /// # Safety
+    ///
+    /// `statement` must stay valid until the registration is dropped, or updated again.
+    pub unsafe fn update(&self, statement: HStmt) {
+        self.token
+            .0
+            .statements
+            .lock()
+            .unwrap()
+            .insert(self.id, StatementHandle(statement));
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// Item yielded by a cancelled reader. [`Cancelled`] the first time, then the end of the
+    /// iteration.
+    pub fn cancelled(&mut self) -> Option<Result<RecordBatch, ArrowError>> {
+        if std::mem::replace(&mut self.reported, true) {
+            None
+        } else {
+            Some(Err(ArrowError::ExternalError(Box::new(Cancelled))))
+        }
+    }
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        self.token.0.statements.lock().unwrap().remove(&self.id);
+    }
+}
+
+/// Yielded by a reader, whose read has been cancelled using [`CancellationToken::cancel`]. Readers
+/// yield [`ArrowError`]s, so it is boxed as an external error. Use [`is_cancelled`] to tell it
+/// apart from other errors.
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("The read has been cancelled.")]
+pub struct Cancelled;
+
+/// `true` if `error` has been yielded by a reader, because its read has been cancelled.
+pub fn is_cancelled(error: &ArrowError) -> bool {
+    match error {
+        ArrowError::ExternalError(source) => source.is::<Cancelled>(),
+        _ => false,
+    }
+}
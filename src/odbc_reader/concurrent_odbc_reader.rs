@@ -0,0 +1,286 @@
+use std::{
+    sync::{
+        mpsc::{channel, Receiver, Sender},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+};
+
+use arrow::{
+    datatypes::SchemaRef,
+    error::ArrowError,
+    record_batch::{RecordBatch, RecordBatchReader},
+};
+use odbc_api::{buffers::ColumnarAnyBuffer, Cursor, RowSetBuffer};
+
+use crate::read_strategy::{MappingErrorPolicy, ReadStrategy};
+
+use super::{
+    batch_transformer::BatchTransformer,
+    cancellation::Registration,
+    is_row_limit_reached,
+    null_flag::{apply_null_flags, NullFlag},
+    odbc_batch_to_arrow_columns, truncate_to_row_limit, OdbcReader, ProgressCallback,
+};
+
+/// The result of converting a batch, together with the buffer it has been converted from, so the
+/// buffer can be reused for fetching.
+type Converted = (Result<RecordBatch, ArrowError>, ColumnarAnyBuffer);
+
+/// Arrow ODBC reader which converts batches into arrow arrays on a separate system thread, while
+/// fetching further batches from the data source. It cycles through a ring of buffer sets, so
+/// fetching can stay several batches ahead of the conversion. This smoothes throughput over
+/// variable network latency, at the cost of memory for the additional buffers.
+///
+/// ODBC cursors can not be send to other threads, so fetching still happens on the thread calling
+/// [`Iterator::next`]. Each call fetches into all the buffers currently not in use by the
+/// conversion, before waiting for the oldest batch to be converted.
+///
+/// Construct it using [`OdbcReader::into_concurrent`].
+pub struct ConcurrentOdbcReader<C: Cursor> {
+    /// Registration of the statement with a cancellation token, if any. Declared before `cursor`,
+    /// so it is dropped before the statement is freed.
+    cancellation: Option<Registration>,
+    /// `None` only during fetching, then the cursor is bound to a buffer.
+    cursor: Option<C>,
+    schema: SchemaRef,
+    /// Buffers which are currently neither filled with fetched rows nor converted.
+    free_buffers: Vec<ColumnarAnyBuffer>,
+    /// Number of batches send to the conversion thread, but not yet received back.
+    in_flight: usize,
+    /// Sends fetched batches to the conversion thread. `None` only while dropping.
+    to_convert: Option<Sender<ColumnarAnyBuffer>>,
+    converted: Receiver<Converted>,
+    converter: Option<JoinHandle<()>>,
+    /// Applied to each converted batch, before it is yielded.
+    batch_transformer: Option<BatchTransformer>,
+    /// `true` once the cursor reported the end of the result set, or fetching failed.
+    exhausted: bool,
+    /// An error fetching a batch. Reported after all batches fetched before it.
+    fetch_error: Option<ArrowError>,
+    /// Total number of rows in the batches yielded so far.
+    num_rows_fetched: usize,
+    /// Number of batches yielded so far.
+    num_batches_fetched: usize,
+    /// Invoked after each batch, with the progress of the reader.
+    on_progress: Option<ProgressCallback>,
+    /// The reader stops once it yielded this many rows. `None` means no limit.
+    max_total_rows: Option<usize>,
+}
+
+impl<C: Cursor> OdbcReader<C> {
+    /// Converts the reader into a [`ConcurrentOdbcReader`], which converts batches into arrow
+    /// arrays on a separate thread while fetching further batches.
+    ///
+    /// # Parameters
+    ///
+    /// * `ring_depth`: Number of buffer sets the fetched batches cycle through. This is the
+    ///   maximum number of batches which are fetched, but not yet yielded by the reader. Each
+    ///   buffer set takes as much memory as the buffers bound by this reader. `2` means double
+    ///   buffering. Higher values reduce stalls then latency spikes.
+    ///
+    /// # Panics
+    ///
+    /// If `ring_depth` is `0`.
+    pub fn into_concurrent(
+        self,
+        ring_depth: usize,
+    ) -> Result<ConcurrentOdbcReader<C>, odbc_api::Error> {
+        assert!(ring_depth > 0, "Ring depth must be at least one.");
+        let schema = self.schema;
+        let batch_schema = self.batch_schema;
+        let strategies = Arc::new(self.column_strategies);
+        let null_flags = self.null_flags;
+        let mapping_error_policy = self.mapping_error_policy;
+        let (cursor, buffer) = self.cursor.unbind()?;
+
+        let max_rows = buffer.row_array_size();
+        let mut free_buffers = Vec::with_capacity(ring_depth);
+        for _ in 1..ring_depth {
+            let descs = self
+                .column_indices
+                .iter()
+                .zip(strategies.iter())
+                .map(|(&col_index, strategy)| (col_index, strategy.buffer_desc()));
+            free_buffers.push(ColumnarAnyBuffer::from_descs_and_indices(max_rows, descs));
+        }
+        free_buffers.push(buffer);
+
+        let (to_convert, to_convert_receiver) = channel();
+        let (converted_sender, converted) = channel();
+        let converter = thread::spawn(move || {
+            convert(
+                &batch_schema,
+                &strategies,
+                &null_flags,
+                &mapping_error_policy,
+                to_convert_receiver,
+                converted_sender,
+            )
+        });
+
+        Ok(ConcurrentOdbcReader {
+            cancellation: self.cancellation,
+            cursor: Some(cursor),
+            schema,
+            free_buffers,
+            in_flight: 0,
+            to_convert: Some(to_convert),
+            converted,
+            converter: Some(converter),
+            exhausted: false,
+            fetch_error: None,
+            num_rows_fetched: self.num_rows_fetched,
+            num_batches_fetched: self.num_batches_fetched,
+            on_progress: self.on_progress,
+            batch_transformer: self.batch_transformer,
+            max_total_rows: self.max_total_rows,
+        })
+    }
+}
+
+/// Runs on the conversion thread, until the reader is dropped.
+fn convert(
+    schema: &SchemaRef,
+    strategies: &[Box<dyn ReadStrategy>],
+    null_flags: &[NullFlag],
+    mapping_error_policy: &MappingErrorPolicy,
+    to_convert: Receiver<ColumnarAnyBuffer>,
+    converted: Sender<Converted>,
+) {
+    for buffer in to_convert {
+        let result = match odbc_batch_to_arrow_columns(strategies, &buffer, mapping_error_policy) {
+            Ok(columns) => apply_null_flags(columns, null_flags)
+                .map(|columns| RecordBatch::try_new(schema.clone(), columns).unwrap()),
+            Err(err) => Err(ArrowError::ExternalError(Box::new(err))),
+        };
+        if converted.send((result, buffer)).is_err() {
+            // The reader has been dropped.
+            break;
+        }
+    }
+}
+
+impl<C: Cursor> ConcurrentOdbcReader<C> {
+    /// Destroy the reader and yield the underlyinng cursor object. Batches which have been fetched
+    /// ahead, but not yet yielded, are lost.
+    ///
+    /// # Panics
+    ///
+    /// If the reader previously failed to unbind the buffers from the cursor, in which case the
+    /// cursor is lost.
+    pub fn into_cursor(mut self) -> C {
+        self.cursor.take().unwrap()
+    }
+
+    /// Total number of rows in the batches yielded by the reader so far, including those yielded
+    /// before converting it into a concurrent reader. See [`OdbcReader::num_rows_fetched`].
+    pub fn num_rows_fetched(&self) -> usize {
+        self.num_rows_fetched
+    }
+
+    /// Fetch batches into all free buffers and send them to the conversion thread.
+    fn fetch_ahead(&mut self) {
+        while !self.exhausted {
+            let Some(buffer) = self.free_buffers.pop() else {
+                break;
+            };
+            let cursor = self.cursor.take().unwrap();
+            let mut block_cursor = cursor.bind_buffer(buffer).unwrap();
+            let fetched = block_cursor
+                .fetch_with_truncation_check(false)
+                .map(|batch| batch.is_some());
+            let (cursor, buffer) = match block_cursor.unbind() {
+                Ok(cursor_and_buffer) => cursor_and_buffer,
+                Err(odbc_error) => {
+                    // Without the cursor, there is no way to fetch any further batches.
+                    self.exhausted = true;
+                    self.fetch_error = Some(ArrowError::ExternalError(Box::new(odbc_error)));
+                    break;
+                }
+            };
+            self.cursor = Some(cursor);
+            match fetched {
+                Ok(true) => {
+                    self.to_convert.as_ref().unwrap().send(buffer).unwrap();
+                    self.in_flight += 1;
+                }
+                Ok(false) => {
+                    self.exhausted = true;
+                    self.free_buffers.push(buffer);
+                }
+                Err(odbc_error) => {
+                    self.exhausted = true;
+                    self.fetch_error = Some(ArrowError::ExternalError(Box::new(odbc_error)));
+                    self.free_buffers.push(buffer);
+                }
+            }
+        }
+    }
+}
+
+impl<C> Iterator for ConcurrentOdbcReader<C>
+where
+    C: Cursor,
+{
+    type Item = Result<RecordBatch, ArrowError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if is_row_limit_reached(self.num_rows_fetched, self.max_total_rows) {
+            return None;
+        }
+        if let Some(cancellation) = &mut self.cancellation {
+            if cancellation.is_cancelled() {
+                return cancellation.cancelled();
+            }
+        }
+        self.fetch_ahead();
+        // A fetch interrupted by cancelling the read fails, so check again before reporting it.
+        if let Some(cancellation) = &mut self.cancellation {
+            if cancellation.is_cancelled() {
+                return cancellation.cancelled();
+            }
+        }
+        if self.in_flight == 0 {
+            return self.fetch_error.take().map(Err);
+        }
+        // The conversion thread only stops once we drop the sender, so it must still be running.
+        let (result, buffer) = self.converted.recv().unwrap();
+        self.in_flight -= 1;
+        self.free_buffers.push(buffer);
+        let result = result
+            .map(|batch| truncate_to_row_limit(batch, self.num_rows_fetched, self.max_total_rows));
+        if let Ok(batch) = &result {
+            self.num_rows_fetched += batch.num_rows();
+            self.num_batches_fetched += 1;
+            if let Some(on_progress) = &self.on_progress {
+                on_progress.report(self.num_batches_fetched, self.num_rows_fetched);
+            }
+        }
+        Some(match &self.batch_transformer {
+            Some(transformer) => {
+                result.and_then(|batch| transformer.transform(batch, &self.schema))
+            }
+            None => result,
+        })
+    }
+}
+
+impl<C> RecordBatchReader for ConcurrentOdbcReader<C>
+where
+    C: Cursor,
+{
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+impl<C: Cursor> Drop for ConcurrentOdbcReader<C> {
+    fn drop(&mut self) {
+        // Closing the channel stops the conversion thread, once it finished its current batch.
+        self.to_convert.take();
+        if let Some(converter) = self.converter.take() {
+            let _ = converter.join();
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use arrow::{
+    array::{ArrayRef, BooleanArray},
+    compute::{cast, nullif},
+    datatypes::{DataType, Schema},
+    error::ArrowError,
+};
+
+use crate::Error;
+
+/// A column whose values are replaced with `NULL` wherever a companion flag column is `true`, or a
+/// non zero integer.
+#[derive(Debug, Clone, Copy)]
+pub struct NullFlag {
+    /// Index of the column whose values are masked.
+    value: usize,
+    /// Index of the boolean or integer column flagging the values which are `NULL`.
+    flag: usize,
+}
+
+impl NullFlag {
+    /// Looks up the columns by name in the schema of the emitted record batches.
+    pub fn resolve(schema: &Schema, value_column: &str, flag_column: &str) -> Result<Self, Error> {
+        let index_of = |name: &str| {
+            schema.index_of(name).map_err(|_| Error::UnknownColumn {
+                name: name.to_owned(),
+            })
+        };
+        let value = index_of(value_column)?;
+        let flag = index_of(flag_column)?;
+        let data_type = schema.field(flag).data_type();
+        if !(data_type == &DataType::Boolean || data_type.is_integer()) {
+            return Err(Error::UnsupportedNullFlagType {
+                name: flag_column.to_owned(),
+                data_type: data_type.clone(),
+            });
+        }
+        Ok(Self { value, flag })
+    }
+}
+
+/// Replaces the values flagged by each of `null_flags` with `NULL`. `NULL` flags do not mask their
+/// value.
+pub fn apply_null_flags(
+    mut columns: Vec<ArrayRef>,
+    null_flags: &[NullFlag],
+) -> Result<Vec<ArrayRef>, ArrowError> {
+    for null_flag in null_flags {
+        let flag = cast(&columns[null_flag.flag], &DataType::Boolean)?;
+        let flag = flag.as_any().downcast_ref::<BooleanArray>().unwrap();
+        columns[null_flag.value] = nullif(&columns[null_flag.value], flag)?;
+    }
+    Ok(columns)
+}
@@ -0,0 +1,250 @@
+use std::{
+    sync::{
+        mpsc::{channel, sync_channel, Receiver, Sender, SyncSender},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+};
+
+use arrow::{
+    datatypes::{Schema, SchemaRef},
+    error::ArrowError,
+    record_batch::{RecordBatch, RecordBatchReader},
+};
+use odbc_api::{parameter::InputParameter, Connection};
+
+use crate::Error;
+
+use super::OdbcReaderBuilder;
+
+/// Opens a connection to the data source. Shared by the threads reading the partitions.
+type ConnectFn = dyn Fn() -> Result<Connection<'static>, odbc_api::Error> + Send + Sync;
+
+/// A batch fetched by one of the partitions, or the error fetching it, tagged with the index of
+/// the partition.
+type Fetched = Result<RecordBatch, ArrowError>;
+
+/// Reads the partitions of a query concurrently, each on its own connection and thread, and
+/// yields their batches through a single iterator. Each batch holds rows of only one partition,
+/// but the batches of different partitions are interleaved in the order they are fetched. Batches
+/// of the same partition are yielded in the order of its result set.
+///
+/// Errors fetching a batch are yielded in place of that batch, like the reader of the partition
+/// would, but as an [`Error::Partition`] naming the partition. The other partitions keep being
+/// read. If the thread reading a partition panics, the reader yields an [`Error::Partition`]
+/// wrapping an [`Error::PartitionPanicked`] once all other partitions have been read. Both are
+/// boxed in an [`ArrowError::ExternalError`].
+///
+/// Construct it using [`crate::OdbcReaderBuilder::build_partitioned`].
+pub struct PartitionedOdbcReader {
+    schema: SchemaRef,
+    /// `None` only while dropping.
+    batches: Option<Receiver<Fetched>>,
+    /// Threads reading the partitions, together with the index of their partition. Joined once
+    /// all batches have been received.
+    workers: Vec<(usize, JoinHandle<()>)>,
+}
+
+impl OdbcReaderBuilder {
+    /// Reads the partitions of `query` concurrently into a [`PartitionedOdbcReader`]. `query` is
+    /// executed once for each element of `partitions` with its lower and upper bound as the two
+    /// parameters, e.g. `SELECT * FROM MyTable WHERE id BETWEEN ? AND ?`. Each partition is read by
+    /// a reader built by this builder, using its own connection opened with `connect` on a thread
+    /// of its own.
+    ///
+    /// Fails with [`Error::Partition`] if connecting, executing the query or building the reader
+    /// fails for any of the partitions, or if the thread reading a partition panics before its
+    /// reader is built (see [`Error::PartitionPanicked`]), and with
+    /// [`Error::PartitionSchemaMismatch`] if not all readers have the same schema, which is
+    /// required to yield their batches as one stream.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use arrow_odbc::{odbc_api::{ConnectionOptions, Environment}, OdbcReaderBuilder};
+    /// use std::sync::OnceLock;
+    ///
+    /// static ENV: OnceLock<Environment> = OnceLock::new();
+    ///
+    /// fn main() -> Result<(), anyhow::Error> {
+    ///     let env = ENV.get_or_init(|| Environment::new().unwrap());
+    ///     let connect = move || {
+    ///         env.connect_with_connection_string("DSN=MyDsn;", ConnectionOptions::default())
+    ///     };
+    ///     let reader = OdbcReaderBuilder::new().build_partitioned(
+    ///         connect,
+    ///         "SELECT * FROM MyTable WHERE id BETWEEN ? AND ?",
+    ///         vec![(1i64, 1_000_000i64), (1_000_001, 2_000_000)],
+    ///     )?;
+    ///     for batch in reader {
+    ///         let batch = batch?;
+    ///         // ... process batch ...
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn build_partitioned<T>(
+        &self,
+        connect: impl Fn() -> Result<Connection<'static>, odbc_api::Error> + Send + Sync + 'static,
+        query: &str,
+        partitions: Vec<(T, T)>,
+    ) -> Result<PartitionedOdbcReader, Error>
+    where
+        T: InputParameter + Send + 'static,
+    {
+        let connect: Arc<ConnectFn> = Arc::new(connect);
+        let query: Arc<str> = self.rewrite(query).into();
+        let num_partitions = partitions.len();
+        let (schemas, schema_receiver) = channel();
+        // Bounded, so fast partitions do not pile up batches in memory.
+        let (batches, batch_receiver) = sync_channel(num_partitions);
+        let workers: Vec<_> = partitions
+            .into_iter()
+            .enumerate()
+            .map(|(index, bounds)| {
+                let builder = self.clone();
+                let connect = connect.clone();
+                let query = query.clone();
+                let schemas = schemas.clone();
+                let batches = batches.clone();
+                let worker = thread::spawn(move || {
+                    read_partition(&builder, &*connect, &query, index, bounds, schemas, batches)
+                });
+                (index, worker)
+            })
+            .collect();
+        // Only the workers hold senders now, so the channels close once all of them are done, or
+        // panicked.
+        drop(schemas);
+        drop(batches);
+        // Constructed before receiving the schemas, so dropping it stops the workers if building
+        // fails.
+        let mut reader = PartitionedOdbcReader {
+            schema: Arc::new(Schema::empty()),
+            batches: Some(batch_receiver),
+            workers,
+        };
+        let mut schemas: Vec<Option<SchemaRef>> = vec![None; num_partitions];
+        for _ in 0..num_partitions {
+            // Every worker sends its schema or error, before sending any batches. Receiving fails
+            // only if a worker panicked before.
+            let Ok((index, schema)) = schema_receiver.recv() else {
+                let index = schemas.iter().position(Option::is_none).unwrap();
+                return Err(Error::Partition {
+                    index,
+                    source: Box::new(Error::PartitionPanicked),
+                });
+            };
+            let schema = schema.map_err(|source| Error::Partition {
+                index,
+                source: Box::new(source),
+            })?;
+            schemas[index] = Some(schema);
+        }
+        let mut schemas = schemas.into_iter().map(Option::unwrap);
+        if let Some(first) = schemas.next() {
+            if let Some(index) = schemas.position(|schema| schema != first) {
+                return Err(Error::PartitionSchemaMismatch { index: index + 1 });
+            }
+            reader.schema = first;
+        }
+        Ok(reader)
+    }
+}
+
+/// Runs on the thread reading the partition at `index`, until all of its batches are fetched or
+/// the reader is dropped.
+fn read_partition<T: InputParameter>(
+    builder: &OdbcReaderBuilder,
+    connect: &ConnectFn,
+    query: &str,
+    index: usize,
+    (lower, upper): (T, T),
+    schemas: Sender<(usize, Result<SchemaRef, Error>)>,
+    batches: SyncSender<Fetched>,
+) {
+    let connection = match connect() {
+        Ok(connection) => connection,
+        Err(source) => {
+            let _ = schemas.send((index, Err(Error::Connecting { source })));
+            return;
+        }
+    };
+    // The query has already been rewritten.
+    let cursor = match connection.execute(query, (&lower, &upper)) {
+        Ok(Some(cursor)) => cursor,
+        Ok(None) => {
+            let sql = query.to_owned();
+            let _ = schemas.send((index, Err(Error::NoResultSet { sql })));
+            return;
+        }
+        Err(source) => {
+            let sql = query.to_owned();
+            let _ = schemas.send((index, Err(Error::ExecutingQuery { source, sql })));
+            return;
+        }
+    };
+    let reader = match builder.build(cursor) {
+        Ok(reader) => reader,
+        Err(error) => {
+            let _ = schemas.send((index, Err(error)));
+            return;
+        }
+    };
+    if schemas.send((index, Ok(reader.schema()))).is_err() {
+        return;
+    }
+    for batch in reader {
+        let batch = batch.map_err(|source| {
+            ArrowError::ExternalError(Box::new(Error::Partition {
+                index,
+                source: Box::new(Error::ReadingBatch(source)),
+            }))
+        });
+        if batches.send(batch).is_err() {
+            // The partitioned reader has been dropped.
+            break;
+        }
+    }
+}
+
+impl Iterator for PartitionedOdbcReader {
+    type Item = Result<RecordBatch, ArrowError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Receiving fails once all partitions have been read to the end, or panicked.
+        if let Ok(batch) = self.batches.as_ref().unwrap().recv() {
+            return Some(batch);
+        }
+        while !self.workers.is_empty() {
+            let (index, worker) = self.workers.remove(0);
+            if worker.join().is_err() {
+                return Some(Err(ArrowError::ExternalError(Box::new(Error::Partition {
+                    index,
+                    source: Box::new(Error::PartitionPanicked),
+                }))));
+            }
+        }
+        None
+    }
+}
+
+impl RecordBatchReader for PartitionedOdbcReader {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+impl Drop for PartitionedOdbcReader {
+    fn drop(&mut self) {
+        // Closing the channel stops the workers, once they fetched their current batch.
+        self.batches.take();
+        for (index, worker) in self.workers.drain(..) {
+            // Panics can not be yielded anymore. Not raised again either, since the reader may be
+            // dropped because building it failed due to this very panic.
+            if worker.join().is_err() {
+                log::warn!("The thread reading partition {index} panicked.");
+            }
+        }
+    }
+}
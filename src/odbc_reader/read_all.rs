@@ -0,0 +1,49 @@
+use arrow::{
+    array::{Array, AsArray},
+    compute::concat_batches,
+    datatypes::{DataType, SchemaRef},
+    record_batch::RecordBatch,
+};
+
+use crate::Error;
+
+/// Concatenates `batches` into a single batch with `schema`, see [`crate::OdbcReader::read_all`].
+/// Fails with [`Error::OffsetOverflow`], rather than panicking, if the values of a column with
+/// 32 Bit offsets do not fit into one array.
+pub(crate) fn concat_all(
+    schema: &SchemaRef,
+    batches: &[RecordBatch],
+) -> Result<RecordBatch, Error> {
+    for (index, field) in schema.fields().iter().enumerate() {
+        let mut num_values = 0;
+        for batch in batches {
+            num_values += match offset_span(batch.column(index)) {
+                Some(span) => span,
+                // Columns of other types do not have 32 Bit offsets.
+                None => break,
+            };
+        }
+        if num_values > i32::MAX as usize {
+            return Err(Error::OffsetOverflow {
+                index,
+                name: field.name().clone(),
+                data_type: field.data_type().clone(),
+                num_values,
+            });
+        }
+    }
+    concat_batches(schema, batches).map_err(Error::ConcatenatingBatches)
+}
+
+/// Number of values spanned by the 32 Bit offsets of `array`, i.e. bytes for `Utf8` and `Binary`
+/// or elements for `List`. `None` for arrays of any other type.
+fn offset_span(array: &dyn Array) -> Option<usize> {
+    let offsets = match array.data_type() {
+        DataType::Utf8 => array.as_string::<i32>().value_offsets(),
+        DataType::Binary => array.as_binary::<i32>().value_offsets(),
+        DataType::List(_) => array.as_list::<i32>().value_offsets(),
+        _ => return None,
+    };
+    let span = offsets[offsets.len() - 1] - offsets[0];
+    Some(span as usize)
+}
@@ -0,0 +1,100 @@
+use odbc_api::{
+    buffers::{AnyBuffer, AnySlice, ColumnarBuffer},
+    handles::{slice_to_cow_utf8, AsHandle, Record},
+    sys::{HStmt, Handle, HandleType},
+};
+
+/// What a reader encountered while fetching its rows, see [`crate::OdbcReader::summary`]. Useful
+/// to monitor the quality of the data read, after the iteration is complete.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReadSummary {
+    /// Total number of rows in the batches yielded. Same as
+    /// [`crate::OdbcReader::num_rows_fetched`].
+    pub num_rows: usize,
+    /// Number of fetched batches in which the driver truncated values of the column, one element
+    /// for each field of the batches, before they are transformed. Only text and binary columns
+    /// can be truncated, and truncated values are an error unless truncation is allowed (see
+    /// [`crate::OdbcReaderBuilder::with_text_truncation`]). ODBC reports only whether values have
+    /// been truncated in a batch, not how many.
+    pub truncated_batches: Vec<usize>,
+    /// Warnings (SQLSTATE `01xxx`) the driver reported for the fetches, e.g. `01004` for string
+    /// data, which has been truncated on the right.
+    pub warnings: Vec<FetchWarning>,
+}
+
+/// Diagnostic record of a warning reported by the driver while fetching a batch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FetchWarning {
+    /// Five character SQLSTATE code, starting with `01`.
+    pub state: String,
+    /// Driver specific error code.
+    pub native_error: i32,
+    /// Message text of the diagnostic record.
+    pub message: String,
+}
+
+/// Statement handle of a reader, used to collect the warnings of its fetches. The cursor binding
+/// the buffers of the reader owns the statement, but does not give access to it.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct FetchStatement(HStmt);
+
+// Safety: The handle belongs to the cursor owned by the same reader, which is sent along with it.
+unsafe impl Send for FetchStatement {}
+
+// Safety: The handle is a valid statement handle, as long as the cursor of the reader is alive.
+unsafe impl AsHandle for FetchStatement {
+    fn as_handle(&self) -> Handle {
+        self.0 as Handle
+    }
+
+    fn handle_type(&self) -> HandleType {
+        HandleType::Stmt
+    }
+}
+
+impl FetchStatement {
+    /// # Safety
+    ///
+    /// `statement` must stay valid as long as [`Self::collect_warnings`] is called.
+    pub unsafe fn new(statement: HStmt) -> Self {
+        Self(statement)
+    }
+
+    /// Appends the warnings of the last function called on the statement to `warnings`. ODBC
+    /// clears the diagnostic records of a statement with each call, so this must be called right
+    /// after each fetch.
+    pub fn collect_warnings(&self, warnings: &mut Vec<FetchWarning>) {
+        let mut record = Record::default();
+        let mut record_number = 1;
+        while record.fill_from(self, record_number) {
+            let state = record.state.as_str();
+            if state.starts_with("01") {
+                warnings.push(FetchWarning {
+                    state: state.to_owned(),
+                    native_error: record.native_error,
+                    message: slice_to_cow_utf8(&record.message).into_owned(),
+                });
+            }
+            record_number += 1;
+        }
+    }
+}
+
+/// Increments the element of `truncated_batches` for each column of `batch` with truncated
+/// values.
+pub(crate) fn count_truncated_batches(
+    batch: &ColumnarBuffer<AnyBuffer>,
+    truncated_batches: &mut [usize],
+) {
+    for (index, count) in truncated_batches.iter_mut().enumerate() {
+        let truncated = match batch.column(index) {
+            AnySlice::Text(view) => view.has_truncated_values(),
+            AnySlice::WText(view) => view.has_truncated_values(),
+            AnySlice::Binary(view) => view.has_truncated_values(),
+            _ => false,
+        };
+        if truncated {
+            *count += 1;
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use std::collections::VecDeque;
+
+use arrow::{
+    compute::concat_batches,
+    datatypes::SchemaRef,
+    error::ArrowError,
+    record_batch::{RecordBatch, RecordBatchReader},
+};
+
+/// Wraps a reader and yields its rows in batches of exactly `num_rows` rows, except for the last
+/// one, which holds the remaining rows. The batches of an [`crate::OdbcReader`] are sized by the
+/// rows the data source returns in one fetch, which may vary and is often smaller than what
+/// downstream consumers prefer. Batches of the wrapped reader are buffered until enough rows are
+/// available, and sliced if they span several output batches. A batch assembled from a single
+/// slice is not copied.
+///
+/// Errors of the wrapped reader are yielded right away. The rows buffered so far are kept, and
+/// yielded by the following batches.
+///
+/// # Example
+///
+/// ```no_run
+/// use arrow_odbc::{arrow::record_batch::RecordBatchReader, RebatchReader};
+///
+/// fn exactly_100_000_rows(reader: impl RecordBatchReader) -> impl RecordBatchReader {
+///     RebatchReader::new(reader, 100_000)
+/// }
+/// ```
+pub struct RebatchReader<R> {
+    inner: R,
+    schema: SchemaRef,
+    num_rows: usize,
+    /// Batches of `inner`, or what remains of them, not yielded yet.
+    pending: VecDeque<RecordBatch>,
+    num_pending_rows: usize,
+    /// `true` once `inner` has no more batches.
+    exhausted: bool,
+}
+
+impl<R> RebatchReader<R>
+where
+    R: RecordBatchReader,
+{
+    /// # Panics
+    ///
+    /// If `num_rows` is `0`.
+    pub fn new(inner: R, num_rows: usize) -> Self {
+        assert!(num_rows > 0, "Batches must hold at least one row.");
+        Self {
+            schema: inner.schema(),
+            inner,
+            num_rows,
+            pending: VecDeque::new(),
+            num_pending_rows: 0,
+            exhausted: false,
+        }
+    }
+
+    /// Destroy the adapter and yield the wrapped reader. Rows buffered, but not yet yielded are
+    /// discarded.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Removes the first `num_rows` buffered rows and concatenates them into one batch.
+    fn take_rows(&mut self, num_rows: usize) -> Result<RecordBatch, ArrowError> {
+        let mut parts = Vec::new();
+        let mut remaining = num_rows;
+        while remaining > 0 {
+            let front = self.pending.pop_front().unwrap();
+            let len = front.num_rows();
+            if len <= remaining {
+                remaining -= len;
+                parts.push(front);
+            } else {
+                parts.push(front.slice(0, remaining));
+                self.pending
+                    .push_front(front.slice(remaining, len - remaining));
+                remaining = 0;
+            }
+        }
+        self.num_pending_rows -= num_rows;
+        if parts.len() == 1 {
+            Ok(parts.pop().unwrap())
+        } else {
+            concat_batches(&self.schema, &parts)
+        }
+    }
+}
+
+impl<R> Iterator for RebatchReader<R>
+where
+    R: RecordBatchReader,
+{
+    type Item = Result<RecordBatch, ArrowError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.exhausted && self.num_pending_rows < self.num_rows {
+            match self.inner.next() {
+                Some(Ok(batch)) => {
+                    if batch.num_rows() != 0 {
+                        self.num_pending_rows += batch.num_rows();
+                        self.pending.push_back(batch);
+                    }
+                }
+                Some(Err(error)) => return Some(Err(error)),
+                None => self.exhausted = true,
+            }
+        }
+        if self.num_pending_rows == 0 {
+            return None;
+        }
+        Some(self.take_rows(self.num_rows.min(self.num_pending_rows)))
+    }
+}
+
+impl<R> RecordBatchReader for RebatchReader<R>
+where
+    R: RecordBatchReader,
+{
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
@@ -0,0 +1,412 @@
+use std::{char::decode_utf16, sync::Arc};
+
+use arrow::{
+    array::{ArrayRef, LargeStringArray, StringArray},
+    datatypes::{DataType, SchemaRef},
+    error::ArrowError,
+    record_batch::{RecordBatch, RecordBatchReader},
+};
+use odbc_api::{
+    buffers::{AnyBuffer, ColumnBuffer},
+    Cursor, CursorRow, Nullable,
+};
+
+use crate::{
+    read_strategy::{
+        DecodingFallback, MappingError, MappingErrorPolicy, ReadStrategy, ValueErrors,
+    },
+    ColumnFailure, Error,
+};
+
+use super::{
+    is_row_limit_reached,
+    null_flag::{apply_null_flags, NullFlag},
+    BatchTransformer, OdbcReaderBuilder,
+};
+
+/// Reads a result set row by row, retrieving each value with `SQLGetData`, rather than binding
+/// buffers to the cursor. The text columns marked with
+/// [`crate::OdbcReaderBuilder::with_streamed_text`] are retrieved piecewise, so their values are
+/// emitted completely, regardless of their length, without allocating a buffer large enough for
+/// the largest possible value. The values of the other columns are collected into buffers like
+/// the ones an [`crate::OdbcReader`] would bind, which grow to fit longer text or binary values,
+/// and converted by the same strategies.
+///
+/// Fetching row by row takes one roundtrip to the data source for each row, so this is a lot
+/// slower than the bulk fetches of [`crate::OdbcReader`]. Use it for result sets with text
+/// columns, whose values are too large to be fetched in bulk, like `VARCHAR(max)` holding whole
+/// documents. Progress, timing and cancellation options of the builder are not supported.
+///
+/// Construct it using [`crate::OdbcReaderBuilder::build_row_wise`].
+pub struct RowWiseOdbcReader<C: Cursor> {
+    cursor: C,
+    /// One for each field of `batch_schema`.
+    columns: Vec<RowWiseColumn>,
+    /// Positions of the columns in `columns`, ordered by their index in the result set. Most
+    /// drivers only support retrieving the values of a row in that order.
+    fetch_order: Vec<usize>,
+    /// If `true` streamed text is retrieved as UTF-16.
+    wide: bool,
+    /// Decodes streamed text, which is not valid UTF-8.
+    decoding_fallback: Option<DecodingFallback>,
+    /// Decodes all streamed narrow text, instead of UTF-8.
+    narrow_text_decoder: Option<DecodingFallback>,
+    /// If `true` unpaired surrogates in streamed wide text are replaced with `U+FFFD`.
+    lossy_utf16: bool,
+    /// Arrow schema describing the arrays filled from the columns.
+    batch_schema: SchemaRef,
+    /// Schema of the record batches yielded by the reader. Differs from `batch_schema` only if the
+    /// batches are transformed.
+    schema: SchemaRef,
+    /// Applied to the arrays created from the columns.
+    null_flags: Vec<NullFlag>,
+    /// Applied to each batch, before it is yielded.
+    batch_transformer: Option<BatchTransformer>,
+    /// What to do with values which can not be converted into their arrow type.
+    mapping_error_policy: MappingErrorPolicy,
+    max_num_rows_per_batch: usize,
+    /// The reader stops once it yielded this many rows. `None` means no limit.
+    max_total_rows: Option<usize>,
+    /// Total number of rows in the batches yielded so far.
+    num_rows_fetched: usize,
+    /// `true` once the cursor reported the end of the result set.
+    exhausted: bool,
+}
+
+/// A column of the result set read by a [`RowWiseOdbcReader`].
+enum RowWiseColumn {
+    /// Values are collected in a buffer, which is converted by `strategy` for each batch.
+    Buffered {
+        /// One based index of the column in the result set.
+        col_index: u16,
+        strategy: Box<dyn ReadStrategy>,
+        buffer: AnyBuffer,
+    },
+    /// Values are retrieved piecewise and decoded as they arrive.
+    Streamed {
+        /// One based index of the column in the result set.
+        col_index: u16,
+        /// If `true` the column is emitted as `LargeUtf8`, rather than `Utf8`.
+        large: bool,
+        values: Vec<Option<String>>,
+    },
+}
+
+impl RowWiseColumn {
+    fn col_index(&self) -> u16 {
+        match self {
+            RowWiseColumn::Buffered { col_index, .. }
+            | RowWiseColumn::Streamed { col_index, .. } => *col_index,
+        }
+    }
+}
+
+impl OdbcReaderBuilder {
+    /// Construct a [`RowWiseOdbcReader`], which fetches the result set of `cursor` row by row,
+    /// retrieving the values of the columns marked with [`Self::with_streamed_text`] piecewise.
+    /// Streamed columns must be inferred or specified as `Utf8` or `LargeUtf8`, otherwise building
+    /// the reader fails with [`ColumnFailure::UnsupportedArrowType`]. In contrast to
+    /// [`Self::build`] no read strategy is chosen for them, so their columns may be too large
+    /// for any buffer, e.g. `VARCHAR(max)` reported with a size of zero.
+    pub fn build_row_wise<C: Cursor>(&self, mut cursor: C) -> Result<RowWiseOdbcReader<C>, Error> {
+        let (source_schema, indices) = self.projected_schema(&mut cursor)?;
+        let mut columns = Vec::with_capacity(indices.len());
+        for (field, &index) in source_schema.fields().iter().zip(&indices) {
+            let col_index = (index + 1).try_into().unwrap();
+            let column = if self.streamed_text.contains(field.name()) {
+                let large = match field.data_type() {
+                    DataType::Utf8 => false,
+                    DataType::LargeUtf8 => true,
+                    other => {
+                        return Err(ColumnFailure::UnsupportedArrowType(other.clone())
+                            .into_crate_error(field.name().clone(), index))
+                    }
+                };
+                RowWiseColumn::Streamed {
+                    col_index,
+                    large,
+                    values: Vec::new(),
+                }
+            } else {
+                let strategy = self.column_strategy(field, &mut cursor, index)?;
+                let buffer =
+                    AnyBuffer::from_desc(self.max_num_rows_per_batch, strategy.buffer_desc());
+                RowWiseColumn::Buffered {
+                    col_index,
+                    strategy,
+                    buffer,
+                }
+            };
+            columns.push(column);
+        }
+        let mut fetch_order: Vec<usize> = (0..columns.len()).collect();
+        fetch_order.sort_by_key(|&position| columns[position].col_index());
+        let (batch_schema, null_flags) = self.batch_schema(&source_schema)?;
+        let schema = self.transformed_schema(&batch_schema)?;
+        Ok(RowWiseOdbcReader {
+            cursor,
+            columns,
+            fetch_order,
+            wide: self.strategy_options.text_encoding.is_wide(),
+            decoding_fallback: self.strategy_options.decoding_fallback.clone(),
+            narrow_text_decoder: self.strategy_options.narrow_text_decoder.clone(),
+            lossy_utf16: self.strategy_options.lossy_utf16,
+            batch_schema,
+            schema,
+            null_flags,
+            batch_transformer: self.batch_transformer.clone(),
+            mapping_error_policy: self.mapping_error_policy.clone(),
+            max_num_rows_per_batch: self.max_num_rows_per_batch,
+            max_total_rows: self.max_total_rows,
+            num_rows_fetched: 0,
+            exhausted: false,
+        })
+    }
+}
+
+impl<C: Cursor> RowWiseOdbcReader<C> {
+    /// Total number of rows in the batches yielded so far.
+    pub fn num_rows_fetched(&self) -> usize {
+        self.num_rows_fetched
+    }
+
+    /// Destroy the reader and yield the underlying cursor object.
+    pub fn into_cursor(self) -> C {
+        self.cursor
+    }
+
+    /// Fetches up to one batch of rows into the columns and returns the number of rows fetched.
+    fn fetch_rows(&mut self) -> Result<usize, ArrowError> {
+        let mut max_rows = self.max_num_rows_per_batch;
+        if let Some(max_total_rows) = self.max_total_rows {
+            max_rows = max_rows.min(max_total_rows - self.num_rows_fetched);
+        }
+        let mut bytes = Vec::new();
+        let mut utf16 = Vec::new();
+        let mut num_rows = 0;
+        while num_rows < max_rows {
+            let Some(mut row) = self.cursor.next_row().map_err(external)? else {
+                self.exhausted = true;
+                break;
+            };
+            for &position in &self.fetch_order {
+                let errors = ValueErrors::new(&self.mapping_error_policy, position);
+                match &mut self.columns[position] {
+                    RowWiseColumn::Buffered {
+                        col_index, buffer, ..
+                    } => get_cell(
+                        &mut row, *col_index, buffer, num_rows, &mut bytes, &mut utf16,
+                    )
+                    .map_err(external)?,
+                    RowWiseColumn::Streamed {
+                        col_index, values, ..
+                    } => {
+                        let is_some = if self.wide {
+                            row.get_wide_text(*col_index, &mut utf16)
+                        } else {
+                            row.get_text(*col_index, &mut bytes)
+                        }
+                        .map_err(external)?;
+                        let value = if !is_some {
+                            None
+                        } else if self.wide {
+                            errors
+                                .recover(decode_wide(&utf16, self.lossy_utf16, num_rows))
+                                .map_err(external)?
+                        } else {
+                            errors
+                                .recover(decode_narrow(
+                                    &bytes,
+                                    self.narrow_text_decoder.as_ref(),
+                                    self.decoding_fallback.as_ref(),
+                                    num_rows,
+                                ))
+                                .map_err(external)?
+                        };
+                        values.push(value);
+                    }
+                }
+            }
+            num_rows += 1;
+        }
+        Ok(num_rows)
+    }
+
+    /// Converts the first `num_rows` rows of the columns into a record batch.
+    fn take_batch(&mut self, num_rows: usize) -> Result<RecordBatch, ArrowError> {
+        let columns = self
+            .columns
+            .iter_mut()
+            .enumerate()
+            .map(|(position, column)| match column {
+                RowWiseColumn::Buffered {
+                    strategy, buffer, ..
+                } => strategy
+                    .fill_arrow_array(
+                        buffer.view(num_rows),
+                        &ValueErrors::new(&self.mapping_error_policy, position),
+                    )
+                    .map_err(external),
+                RowWiseColumn::Streamed { large, values, .. } => {
+                    let values = std::mem::take(values);
+                    let array: ArrayRef = if *large {
+                        Arc::new(LargeStringArray::from(values))
+                    } else {
+                        Arc::new(StringArray::from(values))
+                    };
+                    Ok(array)
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let columns = apply_null_flags(columns, &self.null_flags)?;
+        let batch = RecordBatch::try_new(self.batch_schema.clone(), columns)?;
+        self.num_rows_fetched += batch.num_rows();
+        match &self.batch_transformer {
+            Some(transformer) => transformer.transform(batch, &self.schema),
+            None => Ok(batch),
+        }
+    }
+}
+
+/// Decodes the streamed narrow text of the row at `index`. `decoder` is used for all values,
+/// otherwise values are decoded as UTF-8 and `fallback` is used for invalid ones.
+fn decode_narrow(
+    bytes: &[u8],
+    decoder: Option<&DecodingFallback>,
+    fallback: Option<&DecodingFallback>,
+    index: usize,
+) -> Result<String, MappingError> {
+    if let Some(DecodingFallback(decoder)) = decoder {
+        return Ok(decoder(bytes));
+    }
+    match (std::str::from_utf8(bytes), fallback) {
+        (Ok(text), _) => Ok(text.to_owned()),
+        (Err(_), Some(DecodingFallback(fallback))) => Ok(fallback(bytes)),
+        (Err(_), None) => Err(MappingError::InvalidUtf8 { index }),
+    }
+}
+
+/// Decodes the streamed wide text of the row at `index`.
+fn decode_wide(utf16: &[u16], lossy: bool, index: usize) -> Result<String, MappingError> {
+    if lossy {
+        return Ok(String::from_utf16_lossy(utf16));
+    }
+    decode_utf16(utf16.iter().cloned())
+        .map(|c| {
+            c.map_err(|error| MappingError::InvalidUtf16 {
+                code_unit: error.unpaired_surrogate(),
+                index,
+            })
+        })
+        .collect()
+}
+
+/// Retrieves the value of the nullable column `col_index` into the element at `index` of `column`.
+macro_rules! get_nullable {
+    ($row:expr, $col_index:expr, $column:expr, $index:expr) => {{
+        let mut value = Nullable::null();
+        $row.get_data($col_index, &mut value)?;
+        $column
+            .writer_n($index + 1)
+            .set_cell($index, value.into_opt());
+    }};
+}
+
+/// Retrieves the value of the column `col_index` into the element at `index` of `buffer`. Text and
+/// binary buffers grow to fit the value. `bytes` and `utf16` are reused between calls to avoid
+/// allocations.
+fn get_cell(
+    row: &mut CursorRow,
+    col_index: u16,
+    buffer: &mut AnyBuffer,
+    index: usize,
+    bytes: &mut Vec<u8>,
+    utf16: &mut Vec<u16>,
+) -> Result<(), odbc_api::Error> {
+    match buffer {
+        AnyBuffer::Binary(column) => {
+            let is_some = row.get_binary(col_index, bytes)?;
+            if bytes.len() > column.max_len() {
+                column.resize_max_element_length(bytes.len(), index);
+            }
+            column.set_value(index, is_some.then_some(bytes.as_slice()));
+        }
+        AnyBuffer::Text(column) => {
+            let is_some = row.get_text(col_index, bytes)?;
+            if bytes.len() > column.max_len() {
+                column.resize_max_str(bytes.len(), index);
+            }
+            column.set_value(index, is_some.then_some(bytes.as_slice()));
+        }
+        AnyBuffer::WText(column) => {
+            let is_some = row.get_wide_text(col_index, utf16)?;
+            if utf16.len() > column.max_len() {
+                column.resize_max_str(utf16.len(), index);
+            }
+            column.set_value(index, is_some.then_some(utf16.as_slice()));
+        }
+        AnyBuffer::Date(values) => row.get_data(col_index, &mut values[index])?,
+        AnyBuffer::Time(values) => row.get_data(col_index, &mut values[index])?,
+        AnyBuffer::Timestamp(values) => row.get_data(col_index, &mut values[index])?,
+        AnyBuffer::F64(values) => row.get_data(col_index, &mut values[index])?,
+        AnyBuffer::F32(values) => row.get_data(col_index, &mut values[index])?,
+        AnyBuffer::I8(values) => row.get_data(col_index, &mut values[index])?,
+        AnyBuffer::I16(values) => row.get_data(col_index, &mut values[index])?,
+        AnyBuffer::I32(values) => row.get_data(col_index, &mut values[index])?,
+        AnyBuffer::I64(values) => row.get_data(col_index, &mut values[index])?,
+        AnyBuffer::U8(values) => row.get_data(col_index, &mut values[index])?,
+        AnyBuffer::Bit(values) => row.get_data(col_index, &mut values[index])?,
+        AnyBuffer::NullableDate(column) => get_nullable!(row, col_index, column, index),
+        AnyBuffer::NullableTime(column) => get_nullable!(row, col_index, column, index),
+        AnyBuffer::NullableTimestamp(column) => get_nullable!(row, col_index, column, index),
+        AnyBuffer::NullableF64(column) => get_nullable!(row, col_index, column, index),
+        AnyBuffer::NullableF32(column) => get_nullable!(row, col_index, column, index),
+        AnyBuffer::NullableI8(column) => get_nullable!(row, col_index, column, index),
+        AnyBuffer::NullableI16(column) => get_nullable!(row, col_index, column, index),
+        AnyBuffer::NullableI32(column) => get_nullable!(row, col_index, column, index),
+        AnyBuffer::NullableI64(column) => get_nullable!(row, col_index, column, index),
+        AnyBuffer::NullableU8(column) => get_nullable!(row, col_index, column, index),
+        AnyBuffer::NullableBit(column) => get_nullable!(row, col_index, column, index),
+    }
+    Ok(())
+}
+
+fn external(error: impl std::error::Error + Send + Sync + 'static) -> ArrowError {
+    ArrowError::ExternalError(Box::new(error))
+}
+
+impl<C> Iterator for RowWiseOdbcReader<C>
+where
+    C: Cursor,
+{
+    type Item = Result<RecordBatch, ArrowError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.exhausted || is_row_limit_reached(self.num_rows_fetched, self.max_total_rows) {
+            return None;
+        }
+        let num_rows = match self.fetch_rows() {
+            Ok(0) => return None,
+            Ok(num_rows) => num_rows,
+            Err(error) => {
+                // Values of the streamed columns of the rows fetched so far belong to no batch.
+                for column in &mut self.columns {
+                    if let RowWiseColumn::Streamed { values, .. } = column {
+                        values.clear();
+                    }
+                }
+                return Some(Err(error));
+            }
+        };
+        Some(self.take_batch(num_rows))
+    }
+}
+
+impl<C> RecordBatchReader for RowWiseOdbcReader<C>
+where
+    C: Cursor,
+{
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
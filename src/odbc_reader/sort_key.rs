@@ -0,0 +1,42 @@
+use arrow::datatypes::{DataType, Field, Schema};
+
+use crate::Error;
+
+/// Metadata entry marking a binary column as the collation sort key of a text column. The value
+/// is the name of the text column.
+const SORT_KEY_FOR: &str = "arrow_odbc.sort_key_for";
+
+/// Checks that both columns of a pair passed to [`crate::OdbcReaderBuilder::with_sort_key`] are
+/// part of the schema of the emitted record batches and that the sort key is binary.
+pub fn check_sort_key(
+    schema: &Schema,
+    text_column: &str,
+    sort_key_column: &str,
+) -> Result<(), Error> {
+    let field = |name: &str| {
+        schema
+            .field_with_name(name)
+            .map_err(|_| Error::UnknownColumn {
+                name: name.to_owned(),
+            })
+    };
+    field(text_column)?;
+    let data_type = field(sort_key_column)?.data_type();
+    if !matches!(
+        data_type,
+        DataType::Binary | DataType::LargeBinary | DataType::FixedSizeBinary(_)
+    ) {
+        return Err(Error::UnsupportedSortKeyType {
+            name: sort_key_column.to_owned(),
+            data_type: data_type.clone(),
+        });
+    }
+    Ok(())
+}
+
+/// Annotates the field of a sort key column with the name of the text column it sorts.
+pub fn mark_sort_key(field: Field, text_column: &str) -> Field {
+    let mut metadata = field.metadata().clone();
+    metadata.insert(SORT_KEY_FOR.to_owned(), text_column.to_owned());
+    field.with_metadata(metadata)
+}
@@ -0,0 +1,158 @@
+use std::sync::Arc;
+
+use arrow::{
+    array::ArrayRef,
+    datatypes::{Schema, SchemaRef},
+    error::ArrowError,
+    record_batch::{RecordBatch, RecordBatchReader},
+};
+use odbc_api::Cursor;
+use thiserror::Error;
+
+use crate::Error;
+
+use super::OdbcReader;
+
+/// Reads result sets which are too wide to be bound at once, by reading columns of the same rows
+/// with several readers and zipping their batches together. Each reader fetches a different subset
+/// of the columns, e.g. using one query per subset.
+///
+/// All readers must yield the same rows in the same order, and the same number of rows per batch.
+/// To ensure this, each query must select a stable unique key column and order by it, and each
+/// reader must have been built with the same maximum number of rows per batch. The key column is
+/// emitted once, as the first column of the schema. It is compared for every batch and a mismatch
+/// is reported as an error, rather than silently combining the values of different rows. Be aware
+/// that concurrent modifications of the table between the queries may still cause mismatches.
+///
+/// Construct it using [`SplitOdbcReader::new`] or [`crate::OdbcReaderBuilder::build_split`].
+pub struct SplitOdbcReader<C: Cursor> {
+    readers: Vec<OdbcReader<C>>,
+    /// Index of the key column in the schema of each reader.
+    key_indices: Vec<usize>,
+    schema: SchemaRef,
+}
+
+impl<C: Cursor> SplitOdbcReader<C> {
+    /// # Parameters
+    ///
+    /// * `readers`: Each reader fetches a subset of the columns. Columns other than the key must
+    ///   only be fetched by one reader.
+    /// * `key`: Name of the key column, which must be part of the schema of every reader.
+    pub fn new(readers: Vec<OdbcReader<C>>, key: &str) -> Result<Self, Error> {
+        let key_indices = readers
+            .iter()
+            .map(|reader| {
+                reader
+                    .schema
+                    .index_of(key)
+                    .map_err(|_| Error::UnknownColumn {
+                        name: key.to_owned(),
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut fields = Vec::new();
+        if let Some(first) = readers.first() {
+            fields.push(first.schema.field(key_indices[0]).clone());
+        }
+        for (reader, &key_index) in readers.iter().zip(&key_indices) {
+            for (index, field) in reader.schema.fields().iter().enumerate() {
+                if index == key_index {
+                    continue;
+                }
+                if fields.iter().any(|other| other.name() == field.name()) {
+                    return Err(Error::DuplicateColumn {
+                        name: field.name().clone(),
+                    });
+                }
+                fields.push(field.as_ref().clone());
+            }
+        }
+        Ok(Self {
+            readers,
+            key_indices,
+            schema: Arc::new(Schema::new(fields)),
+        })
+    }
+
+    /// Destroy the reader and yield the underlying cursor objects, in the order of the readers
+    /// passed to [`Self::new`].
+    pub fn into_cursors(self) -> Result<Vec<C>, odbc_api::Error> {
+        self.readers
+            .into_iter()
+            .map(OdbcReader::into_cursor)
+            .collect()
+    }
+
+    fn zip_batches(&self, batches: Vec<RecordBatch>) -> Result<RecordBatch, SplitError> {
+        let key = batches[0].column(self.key_indices[0]);
+        let mut columns: Vec<ArrayRef> = vec![key.clone()];
+        for (index, (batch, &key_index)) in batches.iter().zip(&self.key_indices).enumerate() {
+            if batch.num_rows() != key.len() {
+                return Err(SplitError::RowCountMismatch { reader: index });
+            }
+            if batch.column(key_index) != key {
+                return Err(SplitError::KeyMismatch { reader: index });
+            }
+            columns.extend(
+                batch
+                    .columns()
+                    .iter()
+                    .enumerate()
+                    .filter(|&(column_index, _)| column_index != key_index)
+                    .map(|(_, column)| column.clone()),
+            );
+        }
+        Ok(RecordBatch::try_new(self.schema.clone(), columns).unwrap())
+    }
+}
+
+/// The readers of a [`SplitOdbcReader`] did not yield the same rows.
+#[derive(Error, Debug)]
+enum SplitError {
+    #[error(
+        "Reader {reader} of a split reader fetched a different number of rows than the first one. \
+        Make sure all readers use the same batch size and query the same rows."
+    )]
+    RowCountMismatch { reader: usize },
+    #[error(
+        "Reader {reader} of a split reader fetched different keys than the first one. Make sure \
+        all queries order their rows by the key column."
+    )]
+    KeyMismatch { reader: usize },
+}
+
+impl<C> Iterator for SplitOdbcReader<C>
+where
+    C: Cursor,
+{
+    type Item = Result<RecordBatch, ArrowError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut batches = Vec::with_capacity(self.readers.len());
+        for reader in &mut self.readers {
+            match reader.next() {
+                Some(Ok(batch)) => batches.push(Some(batch)),
+                Some(Err(error)) => return Some(Err(error)),
+                None => batches.push(None),
+            }
+        }
+        if batches.iter().all(Option::is_none) {
+            return None;
+        }
+        let result = match batches.iter().position(Option::is_none) {
+            // Some, but not all readers ran out of rows.
+            Some(reader) => Err(SplitError::RowCountMismatch { reader }),
+            None => self.zip_batches(batches.into_iter().map(Option::unwrap).collect()),
+        };
+        Some(result.map_err(|error| ArrowError::ExternalError(Box::new(error))))
+    }
+}
+
+impl<C> RecordBatchReader for SplitOdbcReader<C>
+where
+    C: Cursor,
+{
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
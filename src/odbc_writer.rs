@@ -15,28 +15,36 @@ use arrow::{
 };
 use odbc_api::{
     buffers::{AnyBuffer, AnySliceMut, BufferDesc},
-    handles::{AsStatementRef, StatementImpl},
-    ColumnarBulkInserter, Connection, Prepared, StatementConnection,
+    handles::{AsStatementRef, Record, StatementImpl},
+    ColumnarBulkInserter, Connection, DataType as OdbcDataType, Prepared, StatementConnection,
 };
 
 use crate::{
     date_time::{
-        epoch_to_date, epoch_to_timestamp, sec_since_midnight_to_time, NullableTimeAsText,
+        epoch_ns_to_timestamp, epoch_to_date, epoch_to_timestamp, sec_since_midnight_to_time,
+        NullableTimeAsText,
     },
     decimal::{NullableDecimal128AsText, NullableDecimal256AsText},
+    error::{diagnostic_record, odbc_error_of},
+    read_strategy::is_timestamp_offset,
 };
 
+pub use self::create_table::{create_table_statement_from_schema, SqlDialect};
+
 use self::{
-    binary::VariadicBinary,
+    binary::{FixedSizeBinary, LargeVariadicBinary, VariadicBinary},
     boolean::boolean_to_bit,
     map_arrow_to_odbc::MapArrowToOdbc,
     text::{LargeUtf8ToNativeText, Utf8ToNativeText},
+    timestamp_tz::timestamp_tz_strategy,
 };
 
 mod binary;
 mod boolean;
+mod create_table;
 mod map_arrow_to_odbc;
 mod text;
+mod timestamp_tz;
 
 /// Fastest and most convinient way to stream the contents of arrow record batches into a database
 /// table. For usecase there you want to insert repeatedly into the same table from different
@@ -129,11 +137,79 @@ pub enum WriterError {
         source: odbc_api::Error,
         sql: String,
     },
+    #[error(
+        "The timestamp {value} (time unit: {time_unit:?}, relative to unix epoch) is outside of \
+        the range which can be represented by an ODBC timestamp."
+    )]
+    TimestampOutOfRange { value: i64, time_unit: TimeUnit },
+    #[error(
+        "The time zone '{time_zone}' of a timestamp field is not supported. Supported are 'UTC', \
+        fixed offsets like '+05:30' and names of the IANA time zone database like 'Europe/Berlin'."
+    )]
+    InvalidTimeZone { time_zone: String },
+    #[error(
+        "Column '{field}' holds decimals with precision {precision} and scale {scale}, which can \
+        not be inserted without loss into a parameter with precision {target_precision} and \
+        scale {target_scale}."
+    )]
+    IncompatibleDecimalPrecision {
+        field: String,
+        precision: u8,
+        scale: i8,
+        target_precision: usize,
+        target_scale: i16,
+    },
+    #[error("Failure to disable autocommit mode in order to start a transaction.\n{0}")]
+    DisableAutocommit(#[source] odbc_api::Error),
+    #[error("Failure to commit the transaction.\n{0}")]
+    Commit(#[source] odbc_api::Error),
+    #[error("Failure to switch autocommit mode back on after the transaction ended.\n{0}")]
+    EnableAutocommit(#[source] odbc_api::Error),
+    #[error(
+        "Failure to roll back the transaction after an error occurred during insertion.\n\
+        Rollback error: {source}\nError causing the rollback: {cause}"
+    )]
+    Rollback {
+        #[source]
+        source: odbc_api::Error,
+        cause: Box<WriterError>,
+    },
     #[deprecated(note = "Use Variant UnsupportedArrowDataType instead")]
     #[error("Inserting arrays with timestamp information is currently not supported.")]
     TimeZonesNotSupported,
 }
 
+impl WriterError {
+    /// The error reported by the ODBC driver or driver manager, which caused this error. `None` if
+    /// it has not been caused by a failing ODBC function call. The error causing a rollback takes
+    /// precedence over an error rolling back.
+    pub fn odbc_error(&self) -> Option<&odbc_api::Error> {
+        match self {
+            WriterError::BindParameterBuffers(source)
+            | WriterError::ExecuteStatment(source)
+            | WriterError::RebindBuffer(source)
+            | WriterError::PreparingInsertStatement { source, .. }
+            | WriterError::DisableAutocommit(source)
+            | WriterError::Commit(source) => Some(source),
+            WriterError::Rollback { source, cause } => cause.odbc_error().or(Some(source)),
+            WriterError::ReadingRecordBatch(source) => odbc_error_of(source),
+            _ => None,
+        }
+    }
+
+    /// Diagnostic record of the ODBC error causing this error, with SQLSTATE, native error code
+    /// and message. See [`Self::odbc_error`].
+    pub fn diagnostic_record(&self) -> Option<&Record> {
+        self.odbc_error().and_then(diagnostic_record)
+    }
+
+    /// Five character SQLSTATE of the ODBC error causing this error, e.g. `40001` for a
+    /// serialization failure, like a deadlock, after which the insertion can be retried.
+    pub fn sql_state(&self) -> Option<&str> {
+        self.diagnostic_record().map(|record| record.state.as_str())
+    }
+}
+
 /// Inserts batches from an [`arrow::record_batch::RecordBatchReader`] into a database.
 pub struct OdbcWriter<S> {
     /// Prepared statement with bound array parameter buffers. Data is copied into these buffers
@@ -173,13 +249,20 @@ where
     pub fn new(
         row_capacity: usize,
         schema: &Schema,
-        statement: Prepared<S>,
+        mut statement: Prepared<S>,
     ) -> Result<Self, WriterError> {
         let strategies: Vec<_> = schema
             .fields()
             .iter()
-            .map(|field| field_to_write_strategy(field.as_ref()))
+            .enumerate()
+            .map(|(index, field)| {
+                let target_has_offset = parameter_has_offset(&mut statement, index, field);
+                field_to_write_strategy(field.as_ref(), target_has_offset)
+            })
             .collect::<Result<_, _>>()?;
+        for (index, field) in schema.fields().iter().enumerate() {
+            check_decimal_fits_parameter(&mut statement, index, field)?;
+        }
         let descriptions = strategies.iter().map(|cws| cws.buffer_desc());
         let inserter = statement
             .into_column_inserter(row_capacity, descriptions)
@@ -296,6 +379,255 @@ impl<'o> OdbcWriter<StatementImpl<'o>> {
     }
 }
 
+/// Allows setting options for an [`OdbcWriter`] before constructing it.
+///
+/// # Example
+///
+/// ```no_run
+/// use arrow_odbc::{OdbcWriterBuilder, arrow::datatypes::Schema, odbc_api::Connection};
+///
+/// fn writer_for_table<'o>(
+///     connection: &'o Connection<'o>,
+///     schema: &Schema,
+/// ) -> Result<(), anyhow::Error> {
+///     let mut writer = OdbcWriterBuilder::new()
+///         // Send 5000 rows to the database with each roundtrip
+///         .with_insert_chunk_size(5000)
+///         .build_with_connection(connection, schema, "MyTable")?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct OdbcWriterBuilder {
+    insert_chunk_size: usize,
+    /// `None` means the inserts are not wrapped into an explicit transaction.
+    commit_every: Option<usize>,
+}
+
+impl Default for OdbcWriterBuilder {
+    fn default() -> Self {
+        Self {
+            insert_chunk_size: 1000,
+            commit_every: None,
+        }
+    }
+}
+
+impl OdbcWriterBuilder {
+    /// Creates a builder with default options. The default insert chunk size is `1000` rows.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of rows bound as array parameters and send to the database with each call to
+    /// `SQLExecute`. Larger chunks mean fewer roundtrips to the database, but the parameter buffers
+    /// need to hold the entire chunk in memory. For text and binary columns this is the number of
+    /// rows times the largest element inserted so far.
+    ///
+    /// Drivers differ in how they handle parameter arrays. Some drivers do not support them
+    /// natively and emulate them by executing the statement once for every row, so there is
+    /// little to gain in increasing the chunk size. Others may reject very large parameter arrays
+    /// or limit the total number of parameters which can be send at once.
+    ///
+    /// # Panics
+    ///
+    /// If `insert_chunk_size` is `0`.
+    pub fn with_insert_chunk_size(&mut self, insert_chunk_size: usize) -> &mut Self {
+        assert!(
+            insert_chunk_size > 0,
+            "Insert chunk size must be at least one."
+        );
+        self.insert_chunk_size = insert_chunk_size;
+        self
+    }
+
+    /// Run the inserts of [`Self::insert_into_table`] within an explicit transaction, which is
+    /// committed every `batches` record batches and once more after the last batch. Should an
+    /// error occur, the transaction is rolled back, so the table contains only the rows of the
+    /// batches committed so far. Without this option the autocommit mode of the connection is left
+    /// untouched.
+    ///
+    /// Before each commit the rows still held in the parameter buffers are send to the database,
+    /// so every commit covers complete record batches.
+    ///
+    /// # Panics
+    ///
+    /// If `batches` is `0`.
+    pub fn with_commit_every(&mut self, batches: usize) -> &mut Self {
+        assert!(batches > 0, "Commit interval must be at least one batch.");
+        self.commit_every = Some(batches);
+        self
+    }
+
+    /// Inserts all batches of `batches` into the table `table_name`, whose column names must match
+    /// the field names of the schema. If [`Self::with_commit_every`] has been specified, the
+    /// inserts are wrapped into a transaction. Autocommit is switched back on once the insert is
+    /// done, regardless of whether it succeeded or not. Failing to do so is reported as
+    /// [`WriterError::EnableAutocommit`], unless the insert itself failed, in which case it is
+    /// logged as a warning and the error of the insert is returned.
+    ///
+    /// `odbc-api` offers no way to query the autocommit mode of a [`Connection`]. So the
+    /// connection is expected to be in autocommit mode, if [`Self::with_commit_every`] is
+    /// specified. Switching autocommit on commits any transaction already open on the connection.
+    ///
+    /// **Note:**
+    ///
+    /// If table or column names are derived from user input, be sure to sanatize the input in order
+    /// to prevent SQL injection attacks.
+    pub fn insert_into_table(
+        &self,
+        connection: &Connection,
+        batches: &mut impl RecordBatchReader,
+        table_name: &str,
+    ) -> Result<(), WriterError> {
+        let schema = batches.schema();
+        let mut writer = self.build_with_connection(connection, schema.as_ref(), table_name)?;
+        let Some(commit_every) = self.commit_every else {
+            return writer.write_all(batches);
+        };
+
+        connection
+            .set_autocommit(false)
+            .map_err(WriterError::DisableAutocommit)?;
+        let result = write_and_commit(&mut writer, connection, batches, commit_every);
+        let result = match result {
+            Ok(()) => Ok(()),
+            Err(cause) => match connection.rollback() {
+                Ok(()) => Err(cause),
+                Err(source) => Err(WriterError::Rollback {
+                    source,
+                    cause: Box::new(cause),
+                }),
+            },
+        };
+        // Any change to the database has already been either committed or rolled back at this
+        // point. So a failure to restore autocommit is only worth an error, if there is no other.
+        match (result, connection.set_autocommit(true)) {
+            (result, Ok(())) => result,
+            (Ok(()), Err(source)) => Err(WriterError::EnableAutocommit(source)),
+            (Err(cause), Err(source)) => {
+                log::warn!("Failure to switch autocommit mode back on: {source}");
+                Err(cause)
+            }
+        }
+    }
+
+    /// Construct a writer using an already existing prepared statement. See [`OdbcWriter::new`].
+    pub fn build<S>(
+        &self,
+        schema: &Schema,
+        statement: Prepared<S>,
+    ) -> Result<OdbcWriter<S>, WriterError>
+    where
+        S: AsStatementRef,
+    {
+        OdbcWriter::new(self.insert_chunk_size, schema, statement)
+    }
+
+    /// A writer which borrows the connection and inserts the given schema into a table with
+    /// matching column names. See [`OdbcWriter::with_connection`].
+    pub fn build_with_connection<'o>(
+        &self,
+        connection: &'o Connection<'o>,
+        schema: &Schema,
+        table_name: &str,
+    ) -> Result<OdbcWriter<StatementImpl<'o>>, WriterError> {
+        OdbcWriter::with_connection(connection, schema, table_name, self.insert_chunk_size)
+    }
+
+    /// A writer which takes ownership of the connection and inserts the given schema into a table
+    /// with matching column names. See [`OdbcWriter::from_connection`].
+    pub fn build_from_connection<'env>(
+        &self,
+        connection: Connection<'env>,
+        schema: &Schema,
+        table_name: &str,
+    ) -> Result<OdbcWriter<StatementConnection<'env>>, WriterError> {
+        OdbcWriter::from_connection(connection, schema, table_name, self.insert_chunk_size)
+    }
+}
+
+/// Decimals are send to the database as text, which represents them exactly. Yet the database
+/// would silently round them, if the parameter has fewer fractional or integral digits than the
+/// arrow field. We rather emit an error, if the parameter can be described by the driver as
+/// `NUMERIC` or `DECIMAL` and is too narrow.
+fn check_decimal_fits_parameter<S>(
+    statement: &mut Prepared<S>,
+    index: usize,
+    field: &Field,
+) -> Result<(), WriterError>
+where
+    S: AsStatementRef,
+{
+    let (precision, scale) = match field.data_type() {
+        DataType::Decimal128(p, s) | DataType::Decimal256(p, s) => (*p, *s),
+        _ => return Ok(()),
+    };
+    // Not every driver is able to describe parameters. In that case we leave the check to the
+    // database.
+    let Ok(description) = statement.describe_param((index + 1).try_into().unwrap()) else {
+        return Ok(());
+    };
+    let (target_precision, target_scale) = match description.data_type {
+        OdbcDataType::Numeric { precision, scale } | OdbcDataType::Decimal { precision, scale } => {
+            (precision, scale)
+        }
+        _ => return Ok(()),
+    };
+    let fractional_digits = scale.max(0) as i64;
+    let integral_digits = precision as i64 - scale as i64;
+    let target_fractional_digits = target_scale as i64;
+    let target_integral_digits = target_precision as i64 - target_scale as i64;
+    if fractional_digits > target_fractional_digits || integral_digits > target_integral_digits {
+        return Err(WriterError::IncompatibleDecimalPrecision {
+            field: field.name().clone(),
+            precision,
+            scale,
+            target_precision,
+            target_scale,
+        });
+    }
+    Ok(())
+}
+
+/// `true` if `field` holds timestamps with a time zone, and the parameter is described as a
+/// timestamp with offset by the driver, e.g. a `DATETIMEOFFSET` column of Microsoft SQL Server.
+fn parameter_has_offset<S>(statement: &mut Prepared<S>, index: usize, field: &Field) -> bool
+where
+    S: AsStatementRef,
+{
+    if !matches!(field.data_type(), DataType::Timestamp(_, Some(_))) {
+        return false;
+    }
+    // Not every driver is able to describe parameters. In that case the timestamps are bound like
+    // for any other column without offset.
+    statement
+        .describe_param((index + 1).try_into().unwrap())
+        .is_ok_and(|description| is_timestamp_offset(description.data_type))
+}
+
+/// Writes all batches, committing the transaction every `commit_every` batches and after the last
+/// one.
+fn write_and_commit(
+    writer: &mut OdbcWriter<StatementImpl<'_>>,
+    connection: &Connection,
+    batches: impl Iterator<Item = Result<RecordBatch, ArrowError>>,
+    commit_every: usize,
+) -> Result<(), WriterError> {
+    let commit = |writer: &mut OdbcWriter<StatementImpl<'_>>| {
+        writer.flush()?;
+        connection.commit().map_err(WriterError::Commit)
+    };
+    for (index, result) in batches.enumerate() {
+        let record_batch = result.map_err(WriterError::ReadingRecordBatch)?;
+        writer.write_batch(&record_batch)?;
+        if (index + 1) % commit_every == 0 {
+            commit(writer)?;
+        }
+    }
+    commit(writer)
+}
+
 pub trait WriteStrategy {
     /// Describe the buffer used to hold the array parameters for the column
     fn buffer_desc(&self) -> BufferDesc;
@@ -314,7 +646,11 @@ pub trait WriteStrategy {
     ) -> Result<(), WriterError>;
 }
 
-fn field_to_write_strategy(field: &Field) -> Result<Box<dyn WriteStrategy>, WriterError> {
+/// `target_has_offset` is `true` if the parameter is described as a timestamp with offset.
+fn field_to_write_strategy(
+    field: &Field,
+    target_has_offset: bool,
+) -> Result<Box<dyn WriteStrategy>, WriterError> {
     let is_nullable = field.is_nullable();
     let strategy = match field.data_type() {
         DataType::Utf8 => Box::new(Utf8ToNativeText {}),
@@ -325,30 +661,32 @@ fn field_to_write_strategy(field: &Field) -> Result<Box<dyn WriteStrategy>, Writ
         DataType::Int32 => Int32Type::identical(is_nullable),
         DataType::Int64 => Int64Type::identical(is_nullable),
         DataType::UInt8 => UInt8Type::identical(is_nullable),
-        DataType::Float16 => Float16Type::map_with(is_nullable, |half| half.to_f32()),
+        DataType::Float16 => Float16Type::map_with(is_nullable, |half| Ok(half.to_f32())),
         DataType::Float32 => Float32Type::identical(is_nullable),
         DataType::Float64 => Float64Type::identical(is_nullable),
-        DataType::Timestamp(TimeUnit::Second, None) => {
+        // Arrow timestamps with a time zone are relative to unix epoch in UTC. Depending on the
+        // target they are send with their offset, or as wall clock time in their time zone.
+        DataType::Timestamp(unit, Some(time_zone)) => {
+            timestamp_tz_strategy(unit, time_zone, target_has_offset, is_nullable)?
+        }
+        DataType::Timestamp(TimeUnit::Second, _) => {
             TimestampSecondType::map_with(is_nullable, epoch_to_timestamp::<1>)
         }
-        DataType::Timestamp(TimeUnit::Millisecond, None) => {
+        DataType::Timestamp(TimeUnit::Millisecond, _) => {
             TimestampMillisecondType::map_with(is_nullable, epoch_to_timestamp::<1_000>)
         }
-        DataType::Timestamp(TimeUnit::Microsecond, None) => {
+        DataType::Timestamp(TimeUnit::Microsecond, _) => {
             TimestampMicrosecondType::map_with(is_nullable, epoch_to_timestamp::<1_000_000>)
         }
-        DataType::Timestamp(TimeUnit::Nanosecond, None) => {
-            TimestampNanosecondType::map_with(is_nullable, |ns| {
-                // Drop the last to digits of precision, since we bind it with precision 7 and not 9.
-                epoch_to_timestamp::<10_000_000>(ns / 100)
-            })
+        DataType::Timestamp(TimeUnit::Nanosecond, _) => {
+            TimestampNanosecondType::map_with(is_nullable, epoch_ns_to_timestamp)
         }
-        DataType::Date32 => Date32Type::map_with(is_nullable, epoch_to_date),
+        DataType::Date32 => Date32Type::map_with(is_nullable, |days| Ok(epoch_to_date(days))),
         DataType::Date64 => Date64Type::map_with(is_nullable, |days_since_epoch| {
-            epoch_to_date(days_since_epoch.try_into().unwrap())
+            Ok(epoch_to_date(days_since_epoch.try_into().unwrap()))
         }),
         DataType::Time32(TimeUnit::Second) => {
-            Time32SecondType::map_with(is_nullable, sec_since_midnight_to_time)
+            Time32SecondType::map_with(is_nullable, |sec| Ok(sec_since_midnight_to_time(sec)))
         }
         DataType::Time32(TimeUnit::Millisecond) => {
             Box::new(NullableTimeAsText::<Time32MillisecondType>::new())
@@ -360,8 +698,9 @@ fn field_to_write_strategy(field: &Field) -> Result<Box<dyn WriteStrategy>, Writ
             Box::new(NullableTimeAsText::<Time64NanosecondType>::new())
         }
         DataType::Binary => Box::new(VariadicBinary::new(1)),
+        DataType::LargeBinary => Box::new(LargeVariadicBinary::new(1)),
         DataType::FixedSizeBinary(length) => {
-            Box::new(VariadicBinary::new((*length).try_into().unwrap()))
+            Box::new(FixedSizeBinary::new((*length).try_into().unwrap()))
         }
         DataType::Decimal128(precision, scale) => {
             Box::new(NullableDecimal128AsText::new(*precision, *scale))
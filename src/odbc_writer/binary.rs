@@ -1,5 +1,5 @@
-use arrow::array::{Array, BinaryArray};
-use odbc_api::buffers::{AnySliceMut, BufferDesc};
+use arrow::array::{Array, BinaryArray, FixedSizeBinaryArray, LargeBinaryArray};
+use odbc_api::buffers::{AnySliceMut, BinColumnSliceMut, BufferDesc};
 
 use super::{WriteStrategy, WriterError};
 
@@ -27,16 +27,96 @@ impl WriteStrategy for VariadicBinary {
         from: &dyn Array,
     ) -> Result<(), WriterError> {
         let from = from.as_any().downcast_ref::<BinaryArray>().unwrap();
+        let to = to.as_bin_view().unwrap();
+        insert_into_bin_slice(from.iter(), to, param_offset)
+    }
+}
+
+pub struct LargeVariadicBinary {
+    buffer_start_size: usize,
+}
+
+impl LargeVariadicBinary {
+    pub fn new(buffer_start_size: usize) -> Self {
+        LargeVariadicBinary { buffer_start_size }
+    }
+}
+
+impl WriteStrategy for LargeVariadicBinary {
+    fn buffer_desc(&self) -> BufferDesc {
+        BufferDesc::Binary {
+            length: self.buffer_start_size,
+        }
+    }
+
+    fn write_rows(
+        &self,
+        param_offset: usize,
+        to: AnySliceMut<'_>,
+        from: &dyn Array,
+    ) -> Result<(), WriterError> {
+        let from = from.as_any().downcast_ref::<LargeBinaryArray>().unwrap();
+        let to = to.as_bin_view().unwrap();
+        insert_into_bin_slice(from.iter(), to, param_offset)
+    }
+}
+
+pub struct FixedSizeBinary {
+    length: usize,
+}
+
+impl FixedSizeBinary {
+    pub fn new(length: usize) -> Self {
+        FixedSizeBinary { length }
+    }
+}
+
+impl WriteStrategy for FixedSizeBinary {
+    fn buffer_desc(&self) -> BufferDesc {
+        BufferDesc::Binary {
+            length: self.length,
+        }
+    }
+
+    fn write_rows(
+        &self,
+        param_offset: usize,
+        to: AnySliceMut<'_>,
+        from: &dyn Array,
+    ) -> Result<(), WriterError> {
+        let from = from
+            .as_any()
+            .downcast_ref::<FixedSizeBinaryArray>()
+            .unwrap();
         let mut to = to.as_bin_view().unwrap();
+        // All elements have the same length, so the buffer is large enough from the start and
+        // never needs to be rebound.
         for (row_index, element) in from.iter().enumerate() {
-            if let Some(bytes) = element {
-                to.ensure_max_element_length(bytes.len(), row_index)
-                    .map_err(WriterError::RebindBuffer)?;
-                to.set_cell(param_offset + row_index, Some(bytes))
-            } else {
-                to.set_cell(param_offset + row_index, None);
-            }
+            to.set_cell(param_offset + row_index, element);
         }
         Ok(())
     }
 }
+
+/// Values are send to the database as binary parameters with an indicator, so `NULL`s are
+/// represented by `None`. Large values are bound as a whole, rather than streamed using
+/// `SQLPutData`, since the columnar bulk inserter binds all parameters as arrays. The buffer grows
+/// to accommodate the largest element.
+fn insert_into_bin_slice<'a>(
+    from: impl Iterator<Item = Option<&'a [u8]>>,
+    mut to: BinColumnSliceMut<'_>,
+    param_offset: usize,
+) -> Result<(), WriterError> {
+    for (row_index, element) in from.enumerate() {
+        if let Some(bytes) = element {
+            // Preserve the rows already written to the buffer, including the ones from previous
+            // batches.
+            to.ensure_max_element_length(bytes.len(), param_offset + row_index)
+                .map_err(WriterError::RebindBuffer)?;
+            to.set_cell(param_offset + row_index, Some(bytes))
+        } else {
+            to.set_cell(param_offset + row_index, None);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,156 @@
+use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+
+use super::WriterError;
+
+/// SQL dialect of the database management system a statement is generated for, accounting for the
+/// differences in the names of column types.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SqlDialect {
+    /// Microsoft SQL Server
+    MsSql,
+    /// PostgreSQL
+    PostgreSql,
+}
+
+/// Creates an SQL statement creating a table with one column for each field of an arrow schema.
+/// Each arrow type is mapped to a SQL type the reader would infer the same arrow type for, where
+/// practical. E.g. `Utf8` becomes `NVARCHAR` for Microsoft SQL Server and `VARCHAR` for
+/// PostgreSQL, `Timestamp` without time zone becomes `DATETIME2` or `TIMESTAMP` with a precision
+/// matching the time unit, and `Decimal128` becomes `DECIMAL` with the same precision and scale.
+/// Columns of non nullable fields are `NOT NULL`.
+///
+/// Arrow text and binary types have no maximum length. Columns are sized by the metadata entry
+/// `arrow_odbc.column_size` of their field, if present (see
+/// [`crate::OdbcReaderBuilder::with_sql_type_metadata`]). Otherwise they are unbounded, e.g.
+/// `NVARCHAR(MAX)` or `TEXT`.
+///
+/// Fails with [`WriterError::UnsupportedArrowDataType`] for arrow types without a SQL counterpart
+/// in the dialect.
+///
+/// **Note:**
+///
+/// If table or column names are derived from user input, be sure to sanatize the input in order to
+/// prevent SQL injection attacks.
+///
+/// # Example
+///
+/// ```
+/// use arrow_odbc::{
+///     create_table_statement_from_schema, SqlDialect,
+///     arrow::datatypes::{Field, DataType, Schema},
+/// };
+///
+/// let field_a = Field::new("a", DataType::Int64, false);
+/// let field_b = Field::new("b", DataType::Utf8, true);
+///
+/// let schema = Schema::new(vec![field_a, field_b]);
+///
+/// let sql = create_table_statement_from_schema(&schema, "MyTable", SqlDialect::MsSql).unwrap();
+/// assert_eq!("CREATE TABLE MyTable (a BIGINT NOT NULL, b NVARCHAR(MAX) NULL)", sql);
+///
+/// let sql = create_table_statement_from_schema(&schema, "MyTable", SqlDialect::PostgreSql)
+///     .unwrap();
+/// assert_eq!("CREATE TABLE MyTable (a BIGINT NOT NULL, b TEXT NULL)", sql);
+/// ```
+pub fn create_table_statement_from_schema(
+    schema: &Schema,
+    table_name: &str,
+    dialect: SqlDialect,
+) -> Result<String, WriterError> {
+    let columns = schema
+        .fields()
+        .iter()
+        .map(|field| {
+            let sql_type = sql_type(field, dialect)?;
+            let nullability = if field.is_nullable() {
+                "NULL"
+            } else {
+                "NOT NULL"
+            };
+            Ok(format!("{} {sql_type} {nullability}", field.name()))
+        })
+        .collect::<Result<Vec<_>, WriterError>>()?
+        .join(", ");
+    // No terminating semicolon, like for the insert statement.
+    Ok(format!("CREATE TABLE {table_name} ({columns})"))
+}
+
+/// Name of the SQL column type for values of `field`.
+fn sql_type(field: &Field, dialect: SqlDialect) -> Result<String, WriterError> {
+    use SqlDialect::{MsSql, PostgreSql};
+    let length = column_size(field);
+    let sql_type = match (field.data_type(), dialect) {
+        (DataType::Boolean, MsSql) => "BIT".to_owned(),
+        (DataType::Boolean, PostgreSql) => "BOOLEAN".to_owned(),
+        // `TINYINT` of Microsoft SQL Server is unsigned.
+        (DataType::Int8 | DataType::Int16, _) => "SMALLINT".to_owned(),
+        (DataType::UInt8, MsSql) => "TINYINT".to_owned(),
+        (DataType::UInt8, PostgreSql) => "SMALLINT".to_owned(),
+        (DataType::Int32 | DataType::UInt16, _) => "INTEGER".to_owned(),
+        (DataType::Int64 | DataType::UInt32, _) => "BIGINT".to_owned(),
+        (DataType::UInt64, _) => "DECIMAL(20, 0)".to_owned(),
+        (DataType::Float16 | DataType::Float32, _) => "REAL".to_owned(),
+        (DataType::Float64, MsSql) => "FLOAT".to_owned(),
+        (DataType::Float64, PostgreSql) => "DOUBLE PRECISION".to_owned(),
+        (DataType::Decimal128(precision, scale) | DataType::Decimal256(precision, scale), _)
+            if *scale >= 0 && (dialect == PostgreSql || *precision <= 38) =>
+        {
+            format!("DECIMAL({precision}, {scale})")
+        }
+        (DataType::Date32 | DataType::Date64, _) => "DATE".to_owned(),
+        (DataType::Time32(unit) | DataType::Time64(unit), _) => {
+            format!("TIME({})", precision(unit, dialect))
+        }
+        (DataType::Timestamp(unit, None), MsSql) => {
+            format!("DATETIME2({})", precision(unit, dialect))
+        }
+        (DataType::Timestamp(unit, None), PostgreSql) => {
+            format!("TIMESTAMP({})", precision(unit, dialect))
+        }
+        (DataType::Timestamp(unit, Some(_)), MsSql) => {
+            format!("DATETIMEOFFSET({})", precision(unit, dialect))
+        }
+        (DataType::Timestamp(unit, Some(_)), PostgreSql) => {
+            format!("TIMESTAMPTZ({})", precision(unit, dialect))
+        }
+        (DataType::Utf8 | DataType::LargeUtf8, MsSql) => match length {
+            Some(length) if length <= 4000 => format!("NVARCHAR({length})"),
+            _ => "NVARCHAR(MAX)".to_owned(),
+        },
+        (DataType::Utf8 | DataType::LargeUtf8, PostgreSql) => match length {
+            Some(length) => format!("VARCHAR({length})"),
+            None => "TEXT".to_owned(),
+        },
+        (DataType::Binary | DataType::LargeBinary, MsSql) => match length {
+            Some(length) if length <= 8000 => format!("VARBINARY({length})"),
+            _ => "VARBINARY(MAX)".to_owned(),
+        },
+        (DataType::FixedSizeBinary(length), MsSql) => format!("BINARY({length})"),
+        (DataType::Binary | DataType::LargeBinary | DataType::FixedSizeBinary(_), PostgreSql) => {
+            "BYTEA".to_owned()
+        }
+        (other, _) => return Err(WriterError::UnsupportedArrowDataType(other.clone())),
+    };
+    Ok(sql_type)
+}
+
+/// Maximum length of the text or binary values of the column, if known from the metadata of its
+/// field.
+fn column_size(field: &Field) -> Option<usize> {
+    field
+        .metadata()
+        .get("arrow_odbc.column_size")
+        .and_then(|size| size.parse().ok())
+        .filter(|&size| size > 0)
+}
+
+/// Fractional second digits of time and timestamp columns for the arrow time unit. PostgreSQL
+/// supports at most microseconds.
+fn precision(unit: &TimeUnit, dialect: SqlDialect) -> u8 {
+    match (unit, dialect) {
+        (TimeUnit::Second, _) => 0,
+        (TimeUnit::Millisecond, _) => 3,
+        (TimeUnit::Microsecond, _) | (TimeUnit::Nanosecond, SqlDialect::PostgreSql) => 6,
+        (TimeUnit::Nanosecond, SqlDialect::MsSql) => 7,
+    }
+}
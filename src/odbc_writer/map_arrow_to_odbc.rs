@@ -15,7 +15,7 @@ pub trait MapArrowToOdbc {
 
     fn map_with<U>(
         nullable: bool,
-        arrow_to_odbc: impl Fn(Self::ArrowElement) -> U + 'static,
+        arrow_to_odbc: impl Fn(Self::ArrowElement) -> Result<U, WriterError> + 'static,
     ) -> Box<dyn WriteStrategy>
     where
         U: Item;
@@ -33,7 +33,7 @@ where
 
     fn map_with<U>(
         nullable: bool,
-        arrow_to_odbc: impl Fn(Self::ArrowElement) -> U + 'static,
+        arrow_to_odbc: impl Fn(Self::ArrowElement) -> Result<U, WriterError> + 'static,
     ) -> Box<dyn WriteStrategy>
     where
         U: Item,
@@ -75,7 +75,7 @@ impl<P, F> Nullable<P, F> {
 impl<P, F, U> WriteStrategy for Nullable<P, F>
 where
     P: ArrowPrimitiveType,
-    F: Fn(P::Native) -> U,
+    F: Fn(P::Native) -> Result<U, WriterError>,
     U: Item,
 {
     fn buffer_desc(&self) -> BufferDesc {
@@ -91,7 +91,10 @@ where
         let from = array.as_any().downcast_ref::<PrimitiveArray<P>>().unwrap();
         let mut to = column_buf.as_nullable_slice::<U>().unwrap();
         for (index, cell) in from.iter().enumerate() {
-            to.set_cell(index + param_offset, cell.map(&self.arrow_to_odbc))
+            to.set_cell(
+                index + param_offset,
+                cell.map(&self.arrow_to_odbc).transpose()?,
+            )
         }
         Ok(())
     }
@@ -115,7 +118,7 @@ impl<P, F> NonNullable<P, F> {
 impl<P, F, U> WriteStrategy for NonNullable<P, F>
 where
     P: ArrowPrimitiveType,
-    F: Fn(P::Native) -> U,
+    F: Fn(P::Native) -> Result<U, WriterError>,
     U: Item,
 {
     fn buffer_desc(&self) -> BufferDesc {
@@ -131,7 +134,7 @@ where
         let from = array.as_any().downcast_ref::<PrimitiveArray<P>>().unwrap();
         let to = column_buf.as_slice::<U>().unwrap();
         for index in 0..from.len() {
-            to[index + param_offset] = (self.arrow_to_odbc)(from.value(index))
+            to[index + param_offset] = (self.arrow_to_odbc)(from.value(index))?
         }
         Ok(())
     }
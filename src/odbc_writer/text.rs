@@ -72,6 +72,8 @@ fn insert_into_narrow_slice<'a>(
     Ok(())
 }
 
+// Only used on windows by default, but we want it to be compiled and checked on every platform.
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
 pub struct Utf8ToWide;
 
 impl WriteStrategy for Utf8ToWide {
@@ -92,6 +94,7 @@ impl WriteStrategy for Utf8ToWide {
     }
 }
 
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
 pub struct LargeUtf8ToWide;
 
 impl WriteStrategy for LargeUtf8ToWide {
@@ -112,6 +115,7 @@ impl WriteStrategy for LargeUtf8ToWide {
     }
 }
 
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
 fn insert_into_wide_slice<'a>(
     from: impl Iterator<Item = Option<&'a str>>,
     mut to: TextColumnSliceMut<u16>,
@@ -0,0 +1,183 @@
+use std::marker::PhantomData;
+
+use arrow::{
+    array::{Array, PrimitiveArray},
+    datatypes::{
+        ArrowTimestampType, TimeUnit, TimestampMicrosecondType, TimestampMillisecondType,
+        TimestampNanosecondType, TimestampSecondType,
+    },
+};
+use chrono::{FixedOffset, NaiveDateTime, Offset, TimeZone};
+use chrono_tz::Tz;
+use odbc_api::buffers::{AnySliceMut, BufferDesc};
+
+use crate::{
+    date_time::{
+        epoch_ns_to_timestamp, epoch_to_date_time, epoch_to_timestamp, timestamp_out_of_range,
+    },
+    WriterError,
+};
+
+use super::{map_arrow_to_odbc::MapArrowToOdbc, WriteStrategy};
+
+/// Chooses how timestamps with the time zone `time_zone` are bound. If `target_has_offset` they are
+/// send as text with the offset of their time zone, e.g. for `DATETIMEOFFSET` columns of Microsoft
+/// SQL Server. Otherwise they are send as the wall clock time in their time zone, e.g. for
+/// `DATETIME2` columns.
+pub fn timestamp_tz_strategy(
+    unit: &TimeUnit,
+    time_zone: &str,
+    target_has_offset: bool,
+    is_nullable: bool,
+) -> Result<Box<dyn WriteStrategy>, WriterError> {
+    let tz = parse_time_zone(time_zone)?;
+    let strategy: Box<dyn WriteStrategy> = if target_has_offset {
+        match unit {
+            TimeUnit::Second => Box::new(TimestampAsOffsetText::<TimestampSecondType>::new(tz, 1)),
+            TimeUnit::Millisecond => Box::new(
+                TimestampAsOffsetText::<TimestampMillisecondType>::new(tz, 1_000),
+            ),
+            TimeUnit::Microsecond => Box::new(
+                TimestampAsOffsetText::<TimestampMicrosecondType>::new(tz, 1_000_000),
+            ),
+            TimeUnit::Nanosecond => Box::new(
+                TimestampAsOffsetText::<TimestampNanosecondType>::new(tz, 1_000_000_000),
+            ),
+        }
+    } else {
+        match unit {
+            TimeUnit::Second => TimestampSecondType::map_with(is_nullable, move |from| {
+                epoch_to_timestamp::<1>(to_wall_clock(from, 1, &tz)?)
+            }),
+            TimeUnit::Millisecond => TimestampMillisecondType::map_with(is_nullable, move |from| {
+                epoch_to_timestamp::<1_000>(to_wall_clock(from, 1_000, &tz)?)
+            }),
+            TimeUnit::Microsecond => TimestampMicrosecondType::map_with(is_nullable, move |from| {
+                epoch_to_timestamp::<1_000_000>(to_wall_clock(from, 1_000_000, &tz)?)
+            }),
+            TimeUnit::Nanosecond => TimestampNanosecondType::map_with(is_nullable, move |from| {
+                epoch_ns_to_timestamp(to_wall_clock(from, 1_000_000_000, &tz)?)
+            }),
+        }
+    };
+    Ok(strategy)
+}
+
+/// Time zone of an arrow timestamp field.
+#[derive(Clone, Copy)]
+enum TimestampTz {
+    Fixed(FixedOffset),
+    /// Named time zone, whose offset depends on the instant, e.g. due to daylight saving time.
+    Named(Tz),
+}
+
+impl TimestampTz {
+    /// Offset from UTC of the time zone at the instant `utc`.
+    fn offset_at(&self, utc: &NaiveDateTime) -> FixedOffset {
+        match self {
+            TimestampTz::Fixed(offset) => *offset,
+            TimestampTz::Named(tz) => tz.offset_from_utc_datetime(utc).fix(),
+        }
+    }
+}
+
+/// Parses `UTC`, a fixed offset like `+05:30`, `+0530` or `+05`, or the name of a time zone in the
+/// IANA time zone database, like `Europe/Berlin`. These are the time zones arrow supports.
+fn parse_time_zone(time_zone: &str) -> Result<TimestampTz, WriterError> {
+    let invalid = || WriterError::InvalidTimeZone {
+        time_zone: time_zone.to_owned(),
+    };
+    if time_zone.eq_ignore_ascii_case("UTC") || time_zone == "Z" {
+        return Ok(TimestampTz::Fixed(FixedOffset::east_opt(0).unwrap()));
+    }
+    let sign = match time_zone.as_bytes().first() {
+        Some(b'+') => 1,
+        Some(b'-') => -1,
+        _ => {
+            return time_zone
+                .parse()
+                .map(TimestampTz::Named)
+                .map_err(|_| invalid())
+        }
+    };
+    let digits = time_zone[1..].replace(':', "");
+    if !matches!(digits.len(), 2 | 4) || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    let hours: i32 = digits[..2].parse().unwrap();
+    let minutes: i32 = digits
+        .get(2..)
+        .map_or(0, |minutes| minutes.parse().unwrap());
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+        .map(TimestampTz::Fixed)
+        .ok_or_else(invalid)
+}
+
+/// Shifts the instant `from` by the offset of `tz` at that instant, so it is relative to unix epoch
+/// in wall clock time of `tz`.
+fn to_wall_clock(from: i64, unit_factor: i64, tz: &TimestampTz) -> Result<i64, WriterError> {
+    let offset = tz.offset_at(&epoch_to_date_time(from, unit_factor)?);
+    from.checked_add(i64::from(offset.local_minus_utc()) * unit_factor)
+        .ok_or_else(|| timestamp_out_of_range(from, unit_factor))
+}
+
+/// Length of `yyyy-mm-dd hh:mm:ss.fffffff +hh:mm`.
+const MAX_STR_LEN: usize = 34;
+
+/// Sends timestamps as text with the offset of their time zone, e.g.
+/// `2023-01-01 12:00:00.1234567 +05:30`. Fractional seconds beyond seven digits are truncated.
+struct TimestampAsOffsetText<P> {
+    tz: TimestampTz,
+    /// Number of units of `P` in a second.
+    unit_factor: i64,
+    _timestamp_type: PhantomData<P>,
+}
+
+impl<P> TimestampAsOffsetText<P>
+where
+    P: ArrowTimestampType,
+{
+    fn new(tz: TimestampTz, unit_factor: i64) -> Self {
+        Self {
+            tz,
+            unit_factor,
+            _timestamp_type: PhantomData,
+        }
+    }
+}
+
+impl<P> WriteStrategy for TimestampAsOffsetText<P>
+where
+    P: ArrowTimestampType,
+{
+    fn buffer_desc(&self) -> BufferDesc {
+        BufferDesc::Text {
+            max_str_len: MAX_STR_LEN,
+        }
+    }
+
+    fn write_rows(
+        &self,
+        param_offset: usize,
+        column_buf: AnySliceMut<'_>,
+        array: &dyn Array,
+    ) -> Result<(), WriterError> {
+        let from = array.as_any().downcast_ref::<PrimitiveArray<P>>().unwrap();
+        let mut to = column_buf.as_text_view().unwrap();
+        for (index, since_epoch) in from.iter().enumerate() {
+            let Some(since_epoch) = since_epoch else {
+                to.set_cell(index + param_offset, None);
+                continue;
+            };
+            let utc = epoch_to_date_time(since_epoch, self.unit_factor)?;
+            let local = self.tz.offset_at(&utc).from_utc_datetime(&utc);
+            let text = local.format("%Y-%m-%d %H:%M:%S%.7f %:z").to_string();
+            // Only years beyond 9999 do not fit.
+            if text.len() > MAX_STR_LEN {
+                return Err(timestamp_out_of_range(since_epoch, self.unit_factor));
+            }
+            to.set_cell(index + param_offset, Some(text.as_bytes()));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+
+use arrow::datatypes::{DataType, Field};
+
+/// Metadata key for the preferred parquet encoding of a column.
+const ENCODING: &str = "parquet.encoding";
+/// Metadata key for the preferred parquet compression codec of a column.
+const COMPRESSION: &str = "parquet.compression";
+
+/// Encoding and compression hints for writing the emitted record batches to parquet. arrow-odbc
+/// does not encode or compress anything itself, it only annotates the fields of the emitted
+/// schema with the metadata entries `parquet.encoding` and `parquet.compression`, so a downstream
+/// parquet writer can configure its column properties from them.
+///
+/// By default integer, date, time and timestamp columns are hinted to use `DELTA_BINARY_PACKED`,
+/// floating point columns `BYTE_STREAM_SPLIT`. Other columns are not annotated, leaving the choice
+/// (e.g. dictionary encoding for text) to the parquet writer.
+///
+/// Use [`crate::OdbcReaderBuilder::with_parquet_hints`] to annotate the schema.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParquetHints {
+    /// Encodings by column name, overriding the default for the arrow type.
+    encodings: HashMap<String, String>,
+    /// Compression codecs by column name, overriding `default_compression`.
+    compressions: HashMap<String, String>,
+    default_compression: Option<String>,
+}
+
+impl ParquetHints {
+    /// Hints with the default encodings per arrow type and no compression hints.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Hint `encoding` (e.g. `PLAIN`) for the column named `column_name`, instead of the default
+    /// for its arrow type.
+    pub fn with_encoding(
+        mut self,
+        column_name: impl Into<String>,
+        encoding: impl Into<String>,
+    ) -> Self {
+        self.encodings.insert(column_name.into(), encoding.into());
+        self
+    }
+
+    /// Hint `compression` (e.g. `ZSTD`) for the column named `column_name`.
+    pub fn with_compression(
+        mut self,
+        column_name: impl Into<String>,
+        compression: impl Into<String>,
+    ) -> Self {
+        self.compressions
+            .insert(column_name.into(), compression.into());
+        self
+    }
+
+    /// Hint `compression` for all columns without a compression hint of their own.
+    pub fn with_default_compression(mut self, compression: impl Into<String>) -> Self {
+        self.default_compression = Some(compression.into());
+        self
+    }
+
+    /// Adds the hints for `field` to its metadata.
+    pub(crate) fn annotate(&self, field: Field) -> Field {
+        let encoding = self
+            .encodings
+            .get(field.name())
+            .map(String::as_str)
+            .or_else(|| default_encoding(field.data_type()));
+        let compression = self
+            .compressions
+            .get(field.name())
+            .or(self.default_compression.as_ref());
+        if encoding.is_none() && compression.is_none() {
+            return field;
+        }
+        let mut metadata = field.metadata().clone();
+        if let Some(encoding) = encoding {
+            metadata.insert(ENCODING.to_owned(), encoding.to_owned());
+        }
+        if let Some(compression) = compression {
+            metadata.insert(COMPRESSION.to_owned(), compression.clone());
+        }
+        field.with_metadata(metadata)
+    }
+}
+
+fn default_encoding(data_type: &DataType) -> Option<&'static str> {
+    match data_type {
+        DataType::Int8
+        | DataType::Int16
+        | DataType::Int32
+        | DataType::Int64
+        | DataType::UInt8
+        | DataType::UInt16
+        | DataType::UInt32
+        | DataType::UInt64
+        | DataType::Date32
+        | DataType::Date64
+        | DataType::Time32(_)
+        | DataType::Time64(_)
+        | DataType::Timestamp(_, _) => Some("DELTA_BINARY_PACKED"),
+        DataType::Float32 | DataType::Float64 => Some("BYTE_STREAM_SPLIT"),
+        _ => None,
+    }
+}
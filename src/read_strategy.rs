@@ -1,45 +1,114 @@
-use std::{convert::TryInto, sync::Arc};
+use std::{collections::HashSet, convert::TryInto, sync::Arc};
 
 use arrow::{
-    array::{ArrayRef, BooleanBuilder, Decimal128Builder},
+    array::{ArrayRef, BooleanBuilder, Decimal128Builder, NullArray, UInt64Builder},
     datatypes::{
-        DataType as ArrowDataType, Date32Type, Field, Float32Type, Float64Type, Int16Type,
-        Int32Type, Int64Type, Int8Type, TimeUnit, TimestampMicrosecondType,
-        TimestampMillisecondType, TimestampNanosecondType, TimestampSecondType, UInt8Type,
+        DataType as ArrowDataType, Date32Type, Field, Float16Type, Float32Type, Float64Type,
+        Int16Type, Int32Type, Int64Type, Int8Type, IntervalUnit, TimeUnit,
+        TimestampMicrosecondType, TimestampMillisecondType, TimestampNanosecondType,
+        TimestampSecondType, UInt16Type, UInt32Type, UInt8Type,
     },
 };
 
-use atoi::FromRadix10Signed;
+use atoi::{FromRadix10Checked, FromRadix10SignedChecked};
 use odbc_api::{
-    buffers::{AnySlice, BufferDesc, Item},
+    buffers::{AnySlice, BufferDesc, Item, TextColumnView},
     Bit, DataType as OdbcDataType, ResultSetMetadata,
 };
 use thiserror::Error;
 
 mod binary;
+mod byte_swap;
+mod cast;
+mod coerce_text;
+mod custom;
+mod dictionary;
+#[cfg(feature = "dump-buffers")]
+mod dump_on_failure;
+mod guid;
+mod hex_text;
+mod interval;
+mod list;
+mod lookup;
 mod map_odbc_to_arrow;
+mod mapping_error_policy;
+mod non_nullable;
 mod text;
+mod time;
+mod timestamp_offset;
+mod trim_text;
+mod unified_integer;
 
-use self::map_odbc_to_arrow::MapOdbcToArrow;
+use self::map_odbc_to_arrow::{narrow_integer, to_float16};
+use self::unified_integer::choose_unified_integer_strategy;
 
 use crate::date_time::{
-    days_since_epoch, ms_since_epoch, ns_since_epoch, seconds_since_epoch, us_since_epoch,
+    days_since_epoch, days_since_epoch_of_day, days_since_epoch_of_midnight, ms_since_epoch,
+    ns_since_epoch, seconds_since_epoch, us_since_epoch, TimestampRounding,
 };
 
 pub use self::{
-    binary::{Binary, FixedSizedBinary},
-    text::choose_text_strategy,
+    binary::{Binary, FixedSizedBinary, LargeBinary, UInt64FromBigEndian},
+    byte_swap::ByteSwap,
+    cast::Cast,
+    coerce_text::CoerceText,
+    custom::{is_nullable_buffer, Custom},
+    dictionary::choose_dictionary_strategy,
+    guid::{is_guid, GuidAsCanonicalText, GuidByteOrder, GuidFromText, GuidRepresentation},
+    hex_text::HexText,
+    interval::{interval_fields, interval_unit, IntervalFromText},
+    list::ListFromText,
+    lookup::{ColumnLookup, Lookup, UnmappedValue},
+    mapping_error_policy::{MappingErrorPolicy, ValueErrors},
+    non_nullable::NonNullable,
+    text::{
+        choose_text_strategy, DecodingFallback, NarrowText, TextEncoding, WideText,
+        ZeroSizedColumnPolicy,
+    },
+    time::{choose_time_strategy, time_precision},
+    timestamp_offset::{is_timestamp_offset, TimestampOffsetFromText},
+    trim_text::TrimTrailingSpaces,
 };
 
-pub use self::map_odbc_to_arrow::MappingError;
+pub use self::map_odbc_to_arrow::{MapOdbcToArrow, MappingError};
+
+#[cfg(feature = "dump-buffers")]
+pub use self::dump_on_failure::DumpOnFailure;
 
 /// All decisions needed to copy data from an ODBC buffer to an Arrow Array
-pub trait ReadStrategy : Send + Sync {
+///
+/// Implement it in order to fetch a column in a way the reader does not anticipate, and assign it
+/// to the column using [`crate::OdbcReaderBuilder::with_column_strategy`].
+pub trait ReadStrategy: Send + Sync {
     /// Describes the buffer which is bound to the ODBC cursor.
     fn buffer_desc(&self) -> BufferDesc;
 
-    /// Create an arrow array from an ODBC buffer described in [`Self::buffer_description`].
-    fn fill_arrow_array(&self, column_view: AnySlice) -> Result<ArrayRef, MappingError>;
+    /// Create an arrow array from an ODBC buffer described in [`Self::buffer_desc`].
+    /// `errors` decides what happens with values which can not be converted.
+    fn fill_arrow_array(
+        &self,
+        column_view: AnySlice,
+        errors: &ValueErrors,
+    ) -> Result<ArrayRef, MappingError>;
+}
+
+/// Reports values truncated by the driver, because they do not fit into the buffer of the column,
+/// rather than converting only their beginning.
+fn check_text_truncation<C>(view: &TextColumnView<C>) -> Result<(), MappingError> {
+    if view.has_truncated_values() {
+        Err(MappingError::TextTruncated {
+            buffer_len: view.max_len(),
+        })
+    } else {
+        Ok(())
+    }
+}
+
+/// Interprets any non zero value as `true`. The C type `SQL_C_BIT` is always a single byte, but
+/// some drivers fill it with values other than `0` and `1` (e.g. `0xFF`), for which
+/// [`Bit::as_bool`] panics.
+fn bit_as_bool(bit: Bit) -> bool {
+    bit.0 != 0
 }
 
 pub struct NonNullableBoolean;
@@ -49,11 +118,15 @@ impl ReadStrategy for NonNullableBoolean {
         BufferDesc::Bit { nullable: false }
     }
 
-    fn fill_arrow_array(&self, column_view: AnySlice) -> Result<ArrayRef, MappingError> {
+    fn fill_arrow_array(
+        &self,
+        column_view: AnySlice,
+        _errors: &ValueErrors,
+    ) -> Result<ArrayRef, MappingError> {
         let values = Bit::as_slice(column_view).unwrap();
-        let mut builder = BooleanBuilder::new();
+        let mut builder = BooleanBuilder::with_capacity(values.len());
         for bit in values {
-            builder.append_value(bit.as_bool());
+            builder.append_value(bit_as_bool(*bit));
         }
         Ok(Arc::new(builder.finish()))
     }
@@ -66,11 +139,108 @@ impl ReadStrategy for NullableBoolean {
         BufferDesc::Bit { nullable: true }
     }
 
-    fn fill_arrow_array(&self, column_view: AnySlice) -> Result<ArrayRef, MappingError> {
+    fn fill_arrow_array(
+        &self,
+        column_view: AnySlice,
+        _errors: &ValueErrors,
+    ) -> Result<ArrayRef, MappingError> {
         let values = Bit::as_nullable_slice(column_view).unwrap();
-        let mut builder = BooleanBuilder::new();
+        let mut builder = BooleanBuilder::with_capacity(values.len());
         for bit in values {
-            builder.append_option(bit.copied().map(Bit::as_bool))
+            builder.append_option(bit.copied().map(bit_as_bool))
+        }
+        Ok(Arc::new(builder.finish()))
+    }
+}
+
+/// Fetches booleans represented as the characters `'0'` and `'1'`. Surrounding whitespace (e.g.
+/// padding of `CHAR` columns) is ignored.
+pub struct BooleanFromText;
+
+impl ReadStrategy for BooleanFromText {
+    fn buffer_desc(&self) -> BufferDesc {
+        // Room for unexpected values, so they can be reported, rather than being truncated.
+        BufferDesc::Text { max_str_len: 16 }
+    }
+
+    fn fill_arrow_array(
+        &self,
+        column_view: AnySlice,
+        errors: &ValueErrors,
+    ) -> Result<ArrayRef, MappingError> {
+        let view = column_view.as_text_view().unwrap();
+        check_text_truncation(&view)?;
+        let mut builder = BooleanBuilder::with_capacity(view.len());
+        for text in view.iter() {
+            let value = match text.map(<[u8]>::trim_ascii) {
+                Some(b"0") => Some(false),
+                Some(b"1") => Some(true),
+                Some(other) => errors.recover(Err(MappingError::InvalidBoolean {
+                    value: String::from_utf8_lossy(other).into_owned(),
+                }))?,
+                None => None,
+            };
+            builder.append_option(value);
+        }
+        Ok(Arc::new(builder.finish()))
+    }
+}
+
+/// Emits a [`NullArray`] for columns without values, e.g. of unknown type, or a left join which
+/// never matched. Values are fetched into a minimal text buffer, only to verify they are `NULL`.
+pub struct Null;
+
+impl ReadStrategy for Null {
+    fn buffer_desc(&self) -> BufferDesc {
+        BufferDesc::Text { max_str_len: 0 }
+    }
+
+    fn fill_arrow_array(
+        &self,
+        column_view: AnySlice,
+        _errors: &ValueErrors,
+    ) -> Result<ArrayRef, MappingError> {
+        let view = column_view.as_text_view().unwrap();
+        if let Some(index) = view.iter().position(|value| value.is_some()) {
+            return Err(MappingError::NotNull { index });
+        }
+        Ok(Arc::new(NullArray::new(view.len())))
+    }
+}
+
+/// Fetches unsigned 64 Bit integers in their text representation, since there is no unsigned
+/// buffer type for them and not all of their values fit into a signed 64 Bit integer.
+pub struct UInt64FromText;
+
+impl ReadStrategy for UInt64FromText {
+    fn buffer_desc(&self) -> BufferDesc {
+        BufferDesc::Text {
+            // 20 digits of u64::MAX and a sign, so negative values are reported, rather than being
+            // truncated.
+            max_str_len: 21,
+        }
+    }
+
+    fn fill_arrow_array(
+        &self,
+        column_view: AnySlice,
+        errors: &ValueErrors,
+    ) -> Result<ArrayRef, MappingError> {
+        let view = column_view.as_text_view().unwrap();
+        check_text_truncation(&view)?;
+        let mut builder = UInt64Builder::with_capacity(view.len());
+        for text in view.iter() {
+            let value = match text.map(<[u8]>::trim_ascii) {
+                Some(text) => match u64::from_radix_10_checked(text) {
+                    (Some(value), consumed) if consumed == text.len() => Some(value),
+                    _ => errors.recover(Err(MappingError::integer_out_of_range(
+                        String::from_utf8_lossy(text),
+                        ArrowDataType::UInt64,
+                    )))?,
+                },
+                None => None,
+            };
+            builder.append_option(value);
         }
         Ok(Arc::new(builder.finish()))
     }
@@ -95,20 +265,27 @@ impl ReadStrategy for Decimal {
         }
     }
 
-    fn fill_arrow_array(&self, column_view: AnySlice) -> Result<ArrayRef, MappingError> {
+    fn fill_arrow_array(
+        &self,
+        column_view: AnySlice,
+        errors: &ValueErrors,
+    ) -> Result<ArrayRef, MappingError> {
         let view = column_view.as_text_view().unwrap();
-        let mut builder = Decimal128Builder::new();
+        check_text_truncation(&view)?;
+        let mut builder = Decimal128Builder::with_capacity(view.len());
 
         let mut buf_digits = Vec::new();
 
         for opt in view.iter() {
             if let Some(text) = opt {
-                buf_digits.clear();
-                buf_digits.extend(text.iter().filter(|&&c| c != b'.'));
+                digits_with_scale(text, self.scale as usize, &mut buf_digits);
 
-                let (num, _consumed) = i128::from_radix_10_signed(&buf_digits);
+                let (num, _consumed) = i128::from_radix_10_signed_checked(&buf_digits);
+                let num = num.ok_or_else(|| MappingError::DecimalOutOfRange {
+                    value: String::from_utf8_lossy(text).into_owned(),
+                });
 
-                builder.append_value(num);
+                builder.append_option(errors.recover(num)?);
             } else {
                 builder.append_null();
             }
@@ -123,6 +300,41 @@ impl ReadStrategy for Decimal {
     }
 }
 
+/// Writes the sign and digits of the decimal `text` into `digits`, with exactly `scale` fractional
+/// digits. Missing fractional digits are padded with zeroes, since some data sources (e.g. Oracle
+/// for `NUMBER`) omit trailing zeroes and may even omit a leading zero (`.5`). Fractional digits
+/// beyond `scale` are rounded half away from zero, like arrow does casting text to decimals.
+fn digits_with_scale(text: &[u8], scale: usize, digits: &mut Vec<u8>) {
+    let text = text.trim_ascii();
+    let (integer, fraction) = match text.iter().position(|&c| c == b'.') {
+        Some(pos) => (&text[..pos], &text[(pos + 1)..]),
+        None => (text, &[][..]),
+    };
+    let (fraction, excess) = fraction.split_at(fraction.len().min(scale));
+    digits.clear();
+    digits.extend_from_slice(integer);
+    digits.extend_from_slice(fraction);
+    digits.resize(integer.len() + scale, b'0');
+    if excess.first().is_some_and(|&digit| digit >= b'5') {
+        round_up_magnitude(digits);
+    }
+}
+
+/// Increments the magnitude of the signed number represented by `digits` by one.
+fn round_up_magnitude(digits: &mut Vec<u8>) {
+    let num_sign = usize::from(matches!(digits.first(), Some(b'-' | b'+')));
+    for digit in digits[num_sign..].iter_mut().rev() {
+        if *digit == b'9' {
+            *digit = b'0';
+        } else {
+            *digit += 1;
+            return;
+        }
+    }
+    // All digits have been nines, so the carry adds another digit.
+    digits.insert(num_sign, b'1');
+}
+
 /// Allows setting limits for buffers bound to the ODBC data source. Check this out if you find that
 /// you get memory allocation, or zero sized column errors. Used than constructing a reader using
 /// [`crate::OdbcReader::with`].
@@ -139,7 +351,8 @@ pub struct BufferAllocationOptions {
     /// this translates to roughly the size in letters. On non windows systems this is the size in
     /// bytes and the datasource is assumed to utilize an UTF-8 encoding. `None` means no upper
     /// limit is set and the maximum element size, reported by ODBC is used to determine buffer
-    /// sizes.
+    /// sizes. Sizes too large to be allocated for each row are still limited, see
+    /// [`crate::OdbcReaderBuilder::with_huge_text_length`].
     pub max_text_size: Option<usize>,
     /// An upper limit for the size of buffers bound to variadic binary columns of the data source.
     /// This limit does not (directly) apply to the size of the created arrow buffers, but rather
@@ -156,46 +369,200 @@ pub struct BufferAllocationOptions {
     pub fallibale_allocations: bool,
 }
 
+/// Options of the reader consulted then choosing the strategy for a column.
+#[derive(Debug, Clone, Default)]
+pub struct StrategyOptions {
+    pub buffer_allocation_options: BufferAllocationOptions,
+    /// If `false` fetching a time of day column into an arrow time unit, which can not represent
+    /// all of its fractional seconds causes an error.
+    pub allow_time_truncation: bool,
+    /// Byte order of GUIDs fetched into `FixedSizeBinary(16)` arrays.
+    pub guid_byte_order: GuidByteOrder,
+    /// If [`GuidRepresentation::CanonicalText`] GUIDs fetched as text are normalized.
+    pub guid_representation: GuidRepresentation,
+    /// If `true` dictionary encoded text columns share one dictionary across all batches.
+    pub shared_dictionaries: bool,
+    /// Maximum number of distinct values in the dictionary of a dictionary encoded text column.
+    /// `None` means no limit.
+    pub max_dictionary_size: Option<usize>,
+    /// Applied to fractional seconds of timestamps, which can not be represented by the arrow
+    /// time unit.
+    pub timestamp_rounding: TimestampRounding,
+    /// Names of timestamp columns fetched into `Date32` arrays, validating their time of day is
+    /// midnight.
+    pub timestamps_as_dates: HashSet<String>,
+    /// Names of timestamp columns fetched into `Date32` arrays, discarding their time of day. In
+    /// strict mode their time of day is validated to be midnight, too.
+    pub timestamps_truncated_to_dates: HashSet<String>,
+    /// Encoding in which text columns are requested from the driver.
+    pub text_encoding: TextEncoding,
+    /// Applied to narrow text values, which are not valid UTF-8.
+    pub decoding_fallback: Option<DecodingFallback>,
+    /// Applied to all narrow text values in place of UTF-8. `None` means narrow text is UTF-8.
+    pub narrow_text_decoder: Option<DecodingFallback>,
+    /// If `true` unpaired surrogates in wide text are replaced with `U+FFFD`.
+    pub lossy_utf16: bool,
+    /// If `true` text values exceeding the buffer of their column are truncated, rather than
+    /// causing an error.
+    pub allow_text_truncation: bool,
+    /// Buffer length for columns fetched as text, whose length is reported by neither their SQL
+    /// type nor their display size. `None` means these columns cause an error.
+    pub default_text_length: Option<usize>,
+    /// Buffer length for text columns with a huge reported length, if `max_text_size` is not set.
+    /// `None` means `8192`.
+    pub huge_text_length: Option<usize>,
+    /// Applied to text columns the driver reports a size of zero for.
+    pub zero_sized_column_policy: ZeroSizedColumnPolicy,
+    /// If `true` values too large for `Float16` fields cause an error, rather than becoming
+    /// infinite.
+    pub strict_float16: bool,
+    /// If `true` empty text values of nullable columns are emitted as `NULL`.
+    pub empty_text_as_null: bool,
+    /// If `true` lossy conversions are errors, regardless of the options allowing them, and text
+    /// of columns without a text SQL type is verified to be ASCII.
+    pub strict: bool,
+    /// Integer type integer columns are fetched into regardless of their width. `None` means each
+    /// column is fetched into the integer type of its field.
+    pub unified_integers: Option<ArrowDataType>,
+}
+
+impl StrategyOptions {
+    fn allow_text_truncation(&self) -> bool {
+        self.allow_text_truncation && !self.strict
+    }
+
+    fn allow_time_truncation(&self) -> bool {
+        self.allow_time_truncation && !self.strict
+    }
+
+    fn strict_float16(&self) -> bool {
+        self.strict_float16 || self.strict
+    }
+}
+
 pub fn choose_column_strategy(
     field: &Field,
     query_metadata: &mut impl ResultSetMetadata,
     col_index: u16,
-    buffer_allocation_options: BufferAllocationOptions,
+    options: &StrategyOptions,
 ) -> Result<Box<dyn ReadStrategy>, ColumnFailure> {
+    let buffer_allocation_options = options.buffer_allocation_options;
+    let rounding = options.timestamp_rounding;
+    if options.unified_integers.as_ref() == Some(field.data_type()) {
+        if let Some(strategy) = choose_unified_integer_strategy(field, query_metadata, col_index)? {
+            return Ok(strategy);
+        }
+    }
     let strat: Box<dyn ReadStrategy> = match field.data_type() {
         ArrowDataType::Boolean => {
-            if field.is_nullable() {
+            let sql_type = query_metadata
+                .col_data_type(col_index)
+                .map_err(ColumnFailure::FailedToDescribeColumn)?;
+            // Some drivers report boolean columns as characters `'0'` and `'1'`.
+            if matches!(
+                sql_type,
+                OdbcDataType::Char { .. }
+                    | OdbcDataType::Varchar { .. }
+                    | OdbcDataType::LongVarchar { .. }
+                    | OdbcDataType::WChar { .. }
+                    | OdbcDataType::WVarchar { .. }
+            ) {
+                Box::new(BooleanFromText)
+            } else if field.is_nullable() {
                 Box::new(NullableBoolean)
             } else {
                 Box::new(NonNullableBoolean)
             }
         }
+        ArrowDataType::Null => Box::new(Null),
         ArrowDataType::Int8 => Int8Type::identical(field.is_nullable()),
         ArrowDataType::Int16 => Int16Type::identical(field.is_nullable()),
         ArrowDataType::Int32 => Int32Type::identical(field.is_nullable()),
         ArrowDataType::Int64 => Int64Type::identical(field.is_nullable()),
         ArrowDataType::UInt8 => UInt8Type::identical(field.is_nullable()),
+        // ODBC has no buffers for wider unsigned integers, so they are fetched into the next wider
+        // signed integer, or as text.
+        ArrowDataType::UInt16 => UInt16Type::map_with(field.is_nullable(), |&value: &i32| {
+            narrow_integer(value, ArrowDataType::UInt16)
+        }),
+        ArrowDataType::UInt32 => UInt32Type::map_with(field.is_nullable(), |&value: &i64| {
+            narrow_integer(value, ArrowDataType::UInt32)
+        }),
+        // The `rowversion` counters of Microsoft SQL Server are reported as `BINARY(8)`.
+        ArrowDataType::UInt64
+            if query_metadata
+                .col_data_type(col_index)
+                .map_err(ColumnFailure::FailedToDescribeColumn)?
+                == (OdbcDataType::Binary { length: 8 }) =>
+        {
+            Box::new(UInt64FromBigEndian)
+        }
+        ArrowDataType::UInt64 => Box::new(UInt64FromText),
+        // ODBC has no buffer for half precision floats, so they are fetched as doubles.
+        ArrowDataType::Float16 => {
+            let strict = options.strict_float16();
+            Float16Type::map_with(field.is_nullable(), move |&value: &f64| {
+                to_float16(value, strict)
+            })
+        }
         ArrowDataType::Float32 => Float32Type::identical(field.is_nullable()),
         ArrowDataType::Float64 => Float64Type::identical(field.is_nullable()),
+        ArrowDataType::Date32
+            if options.timestamps_as_dates.contains(field.name())
+                || (options.strict
+                    && options.timestamps_truncated_to_dates.contains(field.name())) =>
+        {
+            Date32Type::map_with(field.is_nullable(), days_since_epoch_of_midnight)
+        }
+        ArrowDataType::Date32 if options.timestamps_truncated_to_dates.contains(field.name()) => {
+            Date32Type::map_with(field.is_nullable(), |e| Ok(days_since_epoch_of_day(e)))
+        }
         ArrowDataType::Date32 => {
             Date32Type::map_with(field.is_nullable(), |e| Ok(days_since_epoch(e)))
         }
-        ArrowDataType::Utf8 => {
+        data_type @ (ArrowDataType::Utf8 | ArrowDataType::LargeUtf8) => {
             let sql_type = query_metadata
                 .col_data_type(col_index)
                 .map_err(ColumnFailure::FailedToDescribeColumn)?;
             let lazy_display_size = || query_metadata.col_display_size(col_index);
-            // Use the SQL type first to determine buffer length.
-            choose_text_strategy(
-                sql_type,
-                lazy_display_size,
-                buffer_allocation_options.max_text_size,
+            if data_type == &ArrowDataType::Utf8
+                && options.guid_representation == GuidRepresentation::CanonicalText
+                && is_guid(sql_type)
+            {
+                Box::new(GuidAsCanonicalText)
+            } else {
+                // Use the SQL type first to determine buffer length.
+                choose_text_strategy(
+                    sql_type,
+                    lazy_display_size,
+                    field.is_nullable(),
+                    data_type == &ArrowDataType::LargeUtf8,
+                    options,
+                )?
+            }
+        }
+        ArrowDataType::Dictionary(key_type, value_type)
+            if value_type.as_ref() == &ArrowDataType::Utf8 =>
+        {
+            let text_field = Field::new(field.name(), ArrowDataType::Utf8, field.is_nullable());
+            let text = choose_column_strategy(&text_field, query_metadata, col_index, options)?;
+            choose_dictionary_strategy(
+                key_type,
+                text,
+                options.shared_dictionaries,
+                options.max_dictionary_size,
             )?
         }
+        // Drivers report array columns as text, e.g. PostgreSQL
+        ArrowDataType::List(item) => {
+            let text_field = Field::new(field.name(), ArrowDataType::Utf8, field.is_nullable());
+            let text = choose_column_strategy(&text_field, query_metadata, col_index, options)?;
+            Box::new(ListFromText::new(text, item.clone()))
+        }
         ArrowDataType::Decimal128(precision, scale @ 0..) => {
             Box::new(Decimal::new(*precision, *scale))
         }
-        ArrowDataType::Binary => {
+        data_type @ (ArrowDataType::Binary | ArrowDataType::LargeBinary) => {
             let sql_type = query_metadata
                 .col_data_type(col_index)
                 .map_err(ColumnFailure::FailedToDescribeColumn)?;
@@ -212,23 +579,81 @@ pub fn choose_column_strategy(
                     }
                 }
             };
-            Box::new(Binary::new(length))
+            if data_type == &ArrowDataType::LargeBinary {
+                Box::new(LargeBinary::new(length))
+            } else {
+                Box::new(Binary::<i32>::new(length))
+            }
+        }
+        ArrowDataType::Timestamp(unit, Some(time_zone))
+            if is_timestamp_offset(
+                query_metadata
+                    .col_data_type(col_index)
+                    .map_err(ColumnFailure::FailedToDescribeColumn)?,
+            ) =>
+        {
+            Box::new(TimestampOffsetFromText::new(
+                unit.clone(),
+                time_zone.to_string(),
+            ))
         }
         ArrowDataType::Timestamp(TimeUnit::Second, _) => {
-            TimestampSecondType::map_with(field.is_nullable(), |e| Ok(seconds_since_epoch(e)))
+            TimestampSecondType::map_with(field.is_nullable(), move |e| {
+                Ok(seconds_since_epoch(e, rounding))
+            })
         }
         ArrowDataType::Timestamp(TimeUnit::Millisecond, _) => {
-            TimestampMillisecondType::map_with(field.is_nullable(), |e| Ok(ms_since_epoch(e)))
+            TimestampMillisecondType::map_with(field.is_nullable(), move |e| {
+                Ok(ms_since_epoch(e, rounding))
+            })
         }
         ArrowDataType::Timestamp(TimeUnit::Microsecond, _) => {
-            TimestampMicrosecondType::map_with(field.is_nullable(), |e| Ok(us_since_epoch(e)))
+            TimestampMicrosecondType::map_with(field.is_nullable(), move |e| {
+                Ok(us_since_epoch(e, rounding))
+            })
         }
         ArrowDataType::Timestamp(TimeUnit::Nanosecond, _) => {
             TimestampNanosecondType::map_with(field.is_nullable(), ns_since_epoch)
         }
+        ArrowDataType::Interval(unit) => {
+            let sql_type = query_metadata
+                .col_data_type(col_index)
+                .map_err(ColumnFailure::FailedToDescribeColumn)?;
+            let fields = interval_fields(sql_type);
+            // Day-time intervals can not be represented in months, and vice versa.
+            if let Some(inferred) = interval_unit(sql_type) {
+                let is_year_month = |unit: &IntervalUnit| *unit == IntervalUnit::YearMonth;
+                if is_year_month(unit) != is_year_month(&inferred) {
+                    return Err(ColumnFailure::UnsupportedArrowType(
+                        field.data_type().clone(),
+                    ));
+                }
+            }
+            Box::new(IntervalFromText::new(unit.clone(), fields))
+        }
+        ArrowDataType::FixedSizeBinary(16)
+            if is_guid(
+                query_metadata
+                    .col_data_type(col_index)
+                    .map_err(ColumnFailure::FailedToDescribeColumn)?,
+            ) =>
+        {
+            Box::new(GuidFromText::new(options.guid_byte_order))
+        }
         ArrowDataType::FixedSizeBinary(length) => {
             Box::new(FixedSizedBinary::new((*length).try_into().unwrap()))
         }
+        ArrowDataType::Time32(unit @ (TimeUnit::Second | TimeUnit::Millisecond))
+        | ArrowDataType::Time64(unit @ (TimeUnit::Microsecond | TimeUnit::Nanosecond)) => {
+            let sql_type = query_metadata
+                .col_data_type(col_index)
+                .map_err(ColumnFailure::FailedToDescribeColumn)?;
+            choose_time_strategy(
+                unit.clone(),
+                time_precision(sql_type),
+                options.allow_time_truncation(),
+            )?
+        }
         unsupported_arrow_type => {
             return Err(ColumnFailure::UnsupportedArrowType(
                 unsupported_arrow_type.clone(),
@@ -250,6 +675,15 @@ pub enum ColumnFailure {
         sql_type
     )]
     ZeroSizedColumn { sql_type: OdbcDataType },
+    /// The size reported for the column can not be represented on this platform.
+    #[error(
+        "ODBC reported a size for the column which is too large to be represented on this \
+        platform. Try casting the column into a type with a smaller upper bound, or limit the \
+        buffer size using the buffer allocation options. The type of the column causing this \
+        error is {:?}.",
+        sql_type
+    )]
+    ColumnSizeTooLarge { sql_type: OdbcDataType },
     /// Unable to retrieve the column display size for the column.
     #[error(
         "Unable to deduce the maximum string length for the SQL Data Type reported by the ODBC \
@@ -273,6 +707,33 @@ pub enum ColumnFailure {
         attached to the ODBC result set:\n{0}"
     )]
     FailedToDescribeColumn(#[source] odbc_api::Error),
+    /// The arrow time unit is too coarse to represent the fractional seconds of the column.
+    #[error(
+        "The column reports time of day values with {precision} fractional second digits, which \
+        can not be represented with the arrow time unit {time_unit:?} without truncation. Choose \
+        a finer unit, or explicitly allow truncation."
+    )]
+    TimeTruncation { precision: i16, time_unit: TimeUnit },
+    /// The column has been inferred to be fetched as text, because its SQL type has no dedicated
+    /// mapping, and text fallbacks have been forbidden using
+    /// [`crate::OdbcReaderBuilder::with_forbid_text_fallback`].
+    #[error(
+        "The column would be fetched as text, since its SQL type {sql_type:?} has no dedicated \
+        mapping to an arrow type, but falling back to text is forbidden. This may indicate a \
+        problem with the metadata reported by the driver. Specify the arrow type of the column \
+        explicitly or cast it in the query."
+    )]
+    UnexpectedTextFallback { sql_type: OdbcDataType },
+    /// The SQL type of the column can not be fetched as any arrow type the reader would infer or
+    /// has been asked for. Checked while building the reader, so the column can be left out using
+    /// [`crate::OdbcReaderBuilder::with_projection`], before any rows are fetched. Use
+    /// [`crate::OdbcReaderBuilder::validate`] to list all of these columns at once.
+    #[error(
+        "The SQL type {sql_type:?} of the column is not supported, since the driver can not \
+        convert it into text. Specify a binary arrow type for the column, cast it in the query, or \
+        leave it out."
+    )]
+    UnsupportedSqlType { sql_type: OdbcDataType },
     #[error(
         "Column buffer is too large to be allocated. Tried to alloacte {num_elements} elements \
         with {element_size} bytes in size each."
@@ -281,6 +742,24 @@ pub enum ColumnFailure {
         num_elements: usize,
         element_size: usize,
     },
+    /// The type of the column in the target schema of the reader can not be cast from the type it
+    /// is fetched as.
+    #[error(
+        "The column is fetched as `{source_type}`, which can not be cast into `{target_type}` \
+        requested by the target schema."
+    )]
+    UnsupportedCast {
+        source_type: ArrowDataType,
+        target_type: ArrowDataType,
+    },
+    /// The read strategy assigned to the column using
+    /// [`crate::OdbcReaderBuilder::with_column_strategy`] binds a buffer which can not hold `NULL`
+    /// values, yet the field of the column is nullable.
+    #[error(
+        "The read strategy of the column binds the buffer {buffer_desc:?}, which can not hold NULL \
+        values, but the field of the column is nullable."
+    )]
+    NonNullableBuffer { buffer_desc: BufferDesc },
 }
 
 impl ColumnFailure {
@@ -292,4 +771,13 @@ impl ColumnFailure {
             source: self,
         }
     }
+
+    /// The error reported by the ODBC driver or driver manager, which caused this failure.
+    pub fn odbc_error(&self) -> Option<&odbc_api::Error> {
+        match self {
+            ColumnFailure::UnknownStringLength { source, .. }
+            | ColumnFailure::FailedToDescribeColumn(source) => Some(source),
+            _ => None,
+        }
+    }
 }
@@ -1,31 +1,49 @@
-use std::{convert::TryInto, sync::Arc};
+use std::{convert::TryInto, marker::PhantomData, sync::Arc};
 
-use arrow::array::{ArrayRef, BinaryBuilder, FixedSizeBinaryBuilder};
-use odbc_api::buffers::{AnySlice, BufferDesc};
+use arrow::array::{
+    ArrayRef, FixedSizeBinaryBuilder, GenericBinaryBuilder, OffsetSizeTrait, UInt64Builder,
+};
+use odbc_api::buffers::{AnySlice, BinColumnView, BufferDesc};
 
-use super::{MappingError, ReadStrategy};
+use super::{MappingError, ReadStrategy, ValueErrors};
 
-pub struct Binary {
+/// Fills variadic binary arrays. `O` is the offset type of the emitted array, `i32` for `Binary`
+/// and `i64` for `LargeBinary`.
+pub struct Binary<O = i32> {
     /// Maximum length in bytes of elements
     max_len: usize,
+    offset: PhantomData<O>,
 }
 
-impl Binary {
+/// Emits `LargeBinary` arrays, whose 64 Bit offsets do not overflow for batches holding more than
+/// 2 GiB of data.
+pub type LargeBinary = Binary<i64>;
+
+impl<O> Binary<O> {
     pub fn new(max_len: usize) -> Self {
-        Self { max_len }
+        Self {
+            max_len,
+            offset: PhantomData,
+        }
     }
 }
 
-impl ReadStrategy for Binary {
+impl<O: OffsetSizeTrait> ReadStrategy for Binary<O> {
     fn buffer_desc(&self) -> BufferDesc {
         BufferDesc::Binary {
             length: self.max_len,
         }
     }
 
-    fn fill_arrow_array(&self, column_view: AnySlice) -> Result<ArrayRef, MappingError> {
+    fn fill_arrow_array(
+        &self,
+        column_view: AnySlice,
+        _errors: &ValueErrors,
+    ) -> Result<ArrayRef, MappingError> {
         let view = column_view.as_bin_view().unwrap();
-        let mut builder = BinaryBuilder::new();
+        check_binary_truncation(&view, self.max_len)?;
+        let data_capacity = view.iter().map(|value| value.map_or(0, <[u8]>::len)).sum();
+        let mut builder = GenericBinaryBuilder::<O>::with_capacity(view.len(), data_capacity);
         for value in view.iter() {
             if let Some(bytes) = value {
                 builder.append_value(bytes);
@@ -55,8 +73,13 @@ impl ReadStrategy for FixedSizedBinary {
         }
     }
 
-    fn fill_arrow_array(&self, column_view: AnySlice) -> Result<ArrayRef, MappingError> {
+    fn fill_arrow_array(
+        &self,
+        column_view: AnySlice,
+        _errors: &ValueErrors,
+    ) -> Result<ArrayRef, MappingError> {
         let view = column_view.as_bin_view().unwrap();
+        check_binary_truncation(&view, self.len as usize)?;
         let mut builder = FixedSizeBinaryBuilder::new(self.len.try_into().unwrap());
         for value in view.iter() {
             if let Some(bytes) = value {
@@ -68,3 +91,40 @@ impl ReadStrategy for FixedSizedBinary {
         Ok(Arc::new(builder.finish()))
     }
 }
+
+/// Interprets the values of a `BINARY(8)` column as big endian unsigned 64 Bit integers, e.g. the
+/// `rowversion` counters of Microsoft SQL Server. Shorter values are zero extended.
+pub struct UInt64FromBigEndian;
+
+impl ReadStrategy for UInt64FromBigEndian {
+    fn buffer_desc(&self) -> BufferDesc {
+        BufferDesc::Binary { length: 8 }
+    }
+
+    fn fill_arrow_array(
+        &self,
+        column_view: AnySlice,
+        _errors: &ValueErrors,
+    ) -> Result<ArrayRef, MappingError> {
+        let view = column_view.as_bin_view().unwrap();
+        check_binary_truncation(&view, 8)?;
+        let mut builder = UInt64Builder::with_capacity(view.len());
+        for value in view.iter() {
+            builder.append_option(value.map(|bytes| {
+                let mut big_endian = [0u8; 8];
+                big_endian[8 - bytes.len()..].copy_from_slice(bytes);
+                u64::from_be_bytes(big_endian)
+            }));
+        }
+        Ok(Arc::new(builder.finish()))
+    }
+}
+
+/// Reports values truncated by the driver, because they exceed the `buffer_len` of the column.
+fn check_binary_truncation(view: &BinColumnView, buffer_len: usize) -> Result<(), MappingError> {
+    if view.has_truncated_values() {
+        Err(MappingError::BinaryTruncated { buffer_len })
+    } else {
+        Ok(())
+    }
+}
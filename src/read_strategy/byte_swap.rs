@@ -0,0 +1,88 @@
+use std::sync::Arc;
+
+use arrow::array::{
+    Array, ArrayRef, BinaryArray, BinaryBuilder, FixedSizeBinaryArray, FixedSizeBinaryBuilder,
+};
+use odbc_api::buffers::{AnySlice, BufferDesc};
+
+use super::{MappingError, ReadStrategy, ValueErrors};
+
+/// Converts binary values holding packed big endian integers (or other multi byte elements) into
+/// native byte order, by reversing the bytes of each element. Wraps the strategy filling a
+/// `Binary` or `FixedSizeBinary` array.
+pub struct ByteSwap {
+    inner: Box<dyn ReadStrategy>,
+    /// Size of each element in bytes.
+    element_size: usize,
+}
+
+impl ByteSwap {
+    pub fn new(inner: Box<dyn ReadStrategy>, element_size: usize) -> Self {
+        Self {
+            inner,
+            element_size,
+        }
+    }
+
+    /// Copies `value` into `buf` with the bytes of each element reversed.
+    fn swap(&self, value: &[u8], buf: &mut Vec<u8>) -> Result<(), MappingError> {
+        if !value.len().is_multiple_of(self.element_size) {
+            return Err(MappingError::PackedElementSize {
+                len: value.len(),
+                element_size: self.element_size,
+            });
+        }
+        buf.clear();
+        buf.extend_from_slice(value);
+        // Big endian is already the native byte order on big endian targets.
+        if cfg!(target_endian = "little") {
+            for element in buf.chunks_exact_mut(self.element_size) {
+                element.reverse();
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ReadStrategy for ByteSwap {
+    fn buffer_desc(&self) -> BufferDesc {
+        self.inner.buffer_desc()
+    }
+
+    fn fill_arrow_array(
+        &self,
+        column_view: AnySlice,
+        errors: &ValueErrors,
+    ) -> Result<ArrayRef, MappingError> {
+        let source = self.inner.fill_arrow_array(column_view, errors)?;
+        let mut buf = Vec::new();
+        if let Some(source) = source.as_any().downcast_ref::<FixedSizeBinaryArray>() {
+            let mut builder =
+                FixedSizeBinaryBuilder::with_capacity(source.len(), source.value_length());
+            for value in source {
+                match value {
+                    Some(value) => {
+                        self.swap(value, &mut buf)?;
+                        builder.append_value(&buf).unwrap();
+                    }
+                    None => builder.append_null(),
+                }
+            }
+            Ok(Arc::new(builder.finish()))
+        } else {
+            let source = source.as_any().downcast_ref::<BinaryArray>().unwrap();
+            let data_capacity = source.value_data().len();
+            let mut builder = BinaryBuilder::with_capacity(source.len(), data_capacity);
+            for value in source {
+                match value {
+                    Some(value) => {
+                        self.swap(value, &mut buf)?;
+                        builder.append_value(&buf);
+                    }
+                    None => builder.append_null(),
+                }
+            }
+            Ok(Arc::new(builder.finish()))
+        }
+    }
+}
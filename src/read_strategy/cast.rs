@@ -0,0 +1,47 @@
+use arrow::{
+    array::ArrayRef,
+    compute::{cast_with_options, CastOptions},
+    datatypes::DataType,
+};
+use odbc_api::buffers::{AnySlice, BufferDesc};
+
+use super::{MappingError, ReadStrategy, ValueErrors};
+
+/// Casts the arrays filled by the inner strategy into the type requested in the target schema of
+/// the reader. See [`crate::OdbcReaderBuilder::with_target_schema`].
+pub struct Cast {
+    inner: Box<dyn ReadStrategy>,
+    data_type: DataType,
+}
+
+impl Cast {
+    pub fn new(inner: Box<dyn ReadStrategy>, data_type: DataType) -> Self {
+        Self { inner, data_type }
+    }
+}
+
+impl ReadStrategy for Cast {
+    fn buffer_desc(&self) -> BufferDesc {
+        self.inner.buffer_desc()
+    }
+
+    fn fill_arrow_array(
+        &self,
+        column_view: AnySlice,
+        errors: &ValueErrors,
+    ) -> Result<ArrayRef, MappingError> {
+        let source = self.inner.fill_arrow_array(column_view, errors)?;
+        // Not safe, so values which can not be cast (e.g. due to overflow) are reported as errors,
+        // rather than being replaced with NULL.
+        let options = CastOptions {
+            safe: false,
+            ..Default::default()
+        };
+        cast_with_options(&source, &self.data_type, &options).map_err(|source| {
+            MappingError::Casting {
+                data_type: self.data_type.clone(),
+                source,
+            }
+        })
+    }
+}
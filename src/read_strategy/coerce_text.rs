@@ -0,0 +1,54 @@
+use arrow::{
+    array::{ArrayRef, StringArray},
+    compute::{cast_with_options, CastOptions},
+    datatypes::DataType,
+};
+use odbc_api::buffers::{AnySlice, BufferDesc};
+
+use super::{MappingError, ReadStrategy, ValueErrors};
+
+/// Parses the text a column has been fetched as into a numeric arrow type, e.g. to recover numeric
+/// columns which fell back to text due to bad metadata reported by the driver.
+pub struct CoerceText {
+    inner: Box<dyn ReadStrategy>,
+    data_type: DataType,
+    decimal_separator: char,
+}
+
+impl CoerceText {
+    /// `inner` must emit `Utf8` arrays.
+    pub fn new(inner: Box<dyn ReadStrategy>, data_type: DataType, decimal_separator: char) -> Self {
+        Self {
+            inner,
+            data_type,
+            decimal_separator,
+        }
+    }
+}
+
+impl ReadStrategy for CoerceText {
+    fn buffer_desc(&self) -> BufferDesc {
+        self.inner.buffer_desc()
+    }
+
+    fn fill_arrow_array(
+        &self,
+        column_view: AnySlice,
+        errors: &ValueErrors,
+    ) -> Result<ArrayRef, MappingError> {
+        let text = self.inner.fill_arrow_array(column_view, errors)?;
+        let text = text.as_any().downcast_ref::<StringArray>().unwrap();
+        let normalized: StringArray = text
+            .iter()
+            .map(|value| value.map(|value| value.trim().replace(self.decimal_separator, ".")))
+            .collect();
+        // Not safe, so values which can not be parsed are reported as errors, rather than being
+        // replaced with NULL.
+        let options = CastOptions {
+            safe: false,
+            ..Default::default()
+        };
+        cast_with_options(&normalized, &self.data_type, &options)
+            .map_err(MappingError::CoercingText)
+    }
+}
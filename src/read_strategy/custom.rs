@@ -0,0 +1,116 @@
+use std::sync::Arc;
+
+use arrow::{array::ArrayRef, datatypes::DataType};
+use odbc_api::buffers::{AnySlice, BufferDesc};
+
+use super::{MappingError, ReadStrategy, ValueErrors};
+
+/// Fills the arrow array of a column with a strategy provided by the user, see
+/// [`crate::OdbcReaderBuilder::with_column_strategy`]. Checks the buffer and the emitted array
+/// against what the reader relies on, rather than trusting the user provided strategy.
+pub struct Custom {
+    inner: Arc<dyn ReadStrategy>,
+    /// Data type of the field of the column. Each emitted array must be of this type.
+    data_type: DataType,
+}
+
+impl Custom {
+    pub fn new(inner: Arc<dyn ReadStrategy>, data_type: DataType) -> Self {
+        Self { inner, data_type }
+    }
+}
+
+impl ReadStrategy for Custom {
+    fn buffer_desc(&self) -> BufferDesc {
+        self.inner.buffer_desc()
+    }
+
+    fn fill_arrow_array(
+        &self,
+        column_view: AnySlice,
+        errors: &ValueErrors,
+    ) -> Result<ArrayRef, MappingError> {
+        // Readers do not let the driver report truncation as an error, so we do it here instead of
+        // passing the beginning of the values on to the user provided strategy.
+        match &column_view {
+            AnySlice::Text(view) if view.has_truncated_values() => {
+                return Err(MappingError::TextTruncated {
+                    buffer_len: view.max_len(),
+                })
+            }
+            AnySlice::WText(view) if view.has_truncated_values() => {
+                return Err(MappingError::TextTruncated {
+                    buffer_len: view.max_len(),
+                })
+            }
+            _ => (),
+        }
+        if let (AnySlice::Binary(view), BufferDesc::Binary { length }) =
+            (&column_view, self.buffer_desc())
+        {
+            if view.has_truncated_values() {
+                return Err(MappingError::BinaryTruncated { buffer_len: length });
+            }
+        }
+        let num_rows = num_rows(&column_view);
+        let array = self.inner.fill_arrow_array(column_view, errors)?;
+        if array.data_type() != &self.data_type || array.len() != num_rows {
+            return Err(MappingError::UnexpectedArray {
+                expected_type: self.data_type.clone(),
+                expected_len: num_rows,
+                data_type: array.data_type().clone(),
+                len: array.len(),
+            });
+        }
+        Ok(array)
+    }
+}
+
+/// `false` if the buffer described by `desc` can not hold `NULL` values.
+pub fn is_nullable_buffer(desc: BufferDesc) -> bool {
+    match desc {
+        BufferDesc::Binary { .. } | BufferDesc::Text { .. } | BufferDesc::WText { .. } => true,
+        BufferDesc::F64 { nullable }
+        | BufferDesc::F32 { nullable }
+        | BufferDesc::Date { nullable }
+        | BufferDesc::Time { nullable }
+        | BufferDesc::Timestamp { nullable }
+        | BufferDesc::I8 { nullable }
+        | BufferDesc::I16 { nullable }
+        | BufferDesc::I32 { nullable }
+        | BufferDesc::I64 { nullable }
+        | BufferDesc::U8 { nullable }
+        | BufferDesc::Bit { nullable } => nullable,
+    }
+}
+
+/// Number of rows fetched into the column.
+fn num_rows(column_view: &AnySlice) -> usize {
+    match column_view {
+        AnySlice::Text(view) => view.len(),
+        AnySlice::WText(view) => view.len(),
+        AnySlice::Binary(view) => view.len(),
+        AnySlice::Date(values) => values.len(),
+        AnySlice::Time(values) => values.len(),
+        AnySlice::Timestamp(values) => values.len(),
+        AnySlice::F64(values) => values.len(),
+        AnySlice::F32(values) => values.len(),
+        AnySlice::I8(values) => values.len(),
+        AnySlice::I16(values) => values.len(),
+        AnySlice::I32(values) => values.len(),
+        AnySlice::I64(values) => values.len(),
+        AnySlice::U8(values) => values.len(),
+        AnySlice::Bit(values) => values.len(),
+        AnySlice::NullableDate(values) => values.len(),
+        AnySlice::NullableTime(values) => values.len(),
+        AnySlice::NullableTimestamp(values) => values.len(),
+        AnySlice::NullableF64(values) => values.len(),
+        AnySlice::NullableF32(values) => values.len(),
+        AnySlice::NullableI8(values) => values.len(),
+        AnySlice::NullableI16(values) => values.len(),
+        AnySlice::NullableI32(values) => values.len(),
+        AnySlice::NullableI64(values) => values.len(),
+        AnySlice::NullableU8(values) => values.len(),
+        AnySlice::NullableBit(values) => values.len(),
+    }
+}
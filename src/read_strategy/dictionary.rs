@@ -0,0 +1,189 @@
+use std::{
+    marker::PhantomData,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use arrow::{
+    array::{
+        Array, ArrayRef, DictionaryArray, PrimitiveArray, StringArray, StringDictionaryBuilder,
+    },
+    datatypes::ArrowNativeType,
+    datatypes::{
+        ArrowDictionaryKeyType, DataType, Int16Type, Int32Type, Int64Type, Int8Type, UInt16Type,
+        UInt32Type, UInt64Type, UInt8Type,
+    },
+    error::ArrowError,
+};
+use odbc_api::buffers::{AnySlice, BufferDesc};
+
+use super::{ColumnFailure, MappingError, ReadStrategy, ValueErrors};
+
+/// Chooses a strategy filling `Dictionary(key_type, Utf8)` arrays with the values fetched by
+/// `text`.
+///
+/// * `shared`: If `true` all batches share one growing dictionary, so keys are consistent across
+///   batches. Otherwise each batch gets its own dictionary.
+/// * `max_size`: Maximum number of distinct values in the dictionary. Batches exceeding it
+///   reference each value with its own key, without deduplicating them.
+pub fn choose_dictionary_strategy(
+    key_type: &DataType,
+    text: Box<dyn ReadStrategy>,
+    shared: bool,
+    max_size: Option<usize>,
+) -> Result<Box<dyn ReadStrategy>, ColumnFailure> {
+    let strategy: Box<dyn ReadStrategy> = match key_type {
+        DataType::Int8 => Box::new(DictionaryText::<Int8Type>::new(text, shared, max_size)),
+        DataType::Int16 => Box::new(DictionaryText::<Int16Type>::new(text, shared, max_size)),
+        DataType::Int32 => Box::new(DictionaryText::<Int32Type>::new(text, shared, max_size)),
+        DataType::Int64 => Box::new(DictionaryText::<Int64Type>::new(text, shared, max_size)),
+        DataType::UInt8 => Box::new(DictionaryText::<UInt8Type>::new(text, shared, max_size)),
+        DataType::UInt16 => Box::new(DictionaryText::<UInt16Type>::new(text, shared, max_size)),
+        DataType::UInt32 => Box::new(DictionaryText::<UInt32Type>::new(text, shared, max_size)),
+        DataType::UInt64 => Box::new(DictionaryText::<UInt64Type>::new(text, shared, max_size)),
+        _ => {
+            return Err(ColumnFailure::UnsupportedArrowType(DataType::Dictionary(
+                Box::new(key_type.clone()),
+                Box::new(DataType::Utf8),
+            )))
+        }
+    };
+    Ok(strategy)
+}
+
+/// Fetches text using the inner strategy and dictionary encodes it.
+struct DictionaryText<K> {
+    text: Box<dyn ReadStrategy>,
+    /// Values of the dictionary emitted with the previous batch, if the dictionary is shared
+    /// across batches. Each batch appends the values it has not seen yet, so the keys of previous
+    /// batches stay valid.
+    shared: Option<Mutex<StringArray>>,
+    /// Maximum number of distinct values in the dictionary. `None` means no limit.
+    max_size: Option<usize>,
+    /// Set once the shared dictionary exceeded `max_size`. From then on each batch gets its own
+    /// dictionary.
+    max_size_exceeded: AtomicBool,
+    _key: PhantomData<K>,
+}
+
+impl<K> DictionaryText<K> {
+    fn new(text: Box<dyn ReadStrategy>, shared: bool, max_size: Option<usize>) -> Self {
+        Self {
+            text,
+            shared: shared.then(|| Mutex::new(StringArray::from(Vec::<&str>::new()))),
+            max_size,
+            max_size_exceeded: AtomicBool::new(false),
+            _key: PhantomData,
+        }
+    }
+}
+
+impl<K> ReadStrategy for DictionaryText<K>
+where
+    K: ArrowDictionaryKeyType + Send + Sync,
+{
+    fn buffer_desc(&self) -> BufferDesc {
+        self.text.buffer_desc()
+    }
+
+    fn fill_arrow_array(
+        &self,
+        column_view: AnySlice,
+        errors: &ValueErrors,
+    ) -> Result<ArrayRef, MappingError> {
+        let text = self.text.fill_arrow_array(column_view, errors)?;
+        let text = text.as_any().downcast_ref::<StringArray>().unwrap();
+        let shared = self
+            .shared
+            .as_ref()
+            .filter(|_| !self.max_size_exceeded.load(Ordering::Relaxed));
+        let Some(shared) = shared else {
+            return self.encode_batch(text);
+        };
+        let mut dictionary = shared.lock().unwrap();
+        let mut builder =
+            StringDictionaryBuilder::<K>::new_with_dictionary(text.len(), &dictionary)
+                .map_err(key_overflow::<K>)?;
+        append_all(&mut builder, text).map_err(key_overflow::<K>)?;
+        let array = builder.finish();
+        if self.exceeds_max_size(&array) {
+            log::warn!(
+                "The shared dictionary exceeded the maximum dictionary size. From now on each batch \
+                gets its own dictionary, so keys are no longer consistent across batches."
+            );
+            self.max_size_exceeded.store(true, Ordering::Relaxed);
+            return self.encode_batch(text);
+        }
+        *dictionary = array
+            .values()
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap()
+            .clone();
+        Ok(Arc::new(array))
+    }
+}
+
+impl<K> DictionaryText<K>
+where
+    K: ArrowDictionaryKeyType,
+{
+    /// Dictionary encodes `text` using a dictionary only for this batch.
+    fn encode_batch(&self, text: &StringArray) -> Result<ArrayRef, MappingError> {
+        let mut builder = StringDictionaryBuilder::<K>::new();
+        append_all(&mut builder, text).map_err(key_overflow::<K>)?;
+        let array = builder.finish();
+        if self.exceeds_max_size(&array) {
+            log::warn!(
+                "A batch holds more distinct values than the maximum dictionary size. It is \
+                emitted without deduplicating its values."
+            );
+            if let Some(identity) = identity_dictionary::<K>(text) {
+                return Ok(Arc::new(identity));
+            }
+        }
+        Ok(Arc::new(array))
+    }
+
+    fn exceeds_max_size(&self, array: &DictionaryArray<K>) -> bool {
+        self.max_size
+            .is_some_and(|max_size| array.values().len() > max_size)
+    }
+}
+
+fn key_overflow<K: ArrowDictionaryKeyType>(_: ArrowError) -> MappingError {
+    MappingError::DictionaryKeyOverflow {
+        key_type: K::DATA_TYPE,
+    }
+}
+
+/// References each value of `text` with its own key, avoiding the cost of deduplicating them.
+/// `None` if the key type can not index all values.
+fn identity_dictionary<K: ArrowDictionaryKeyType>(
+    text: &StringArray,
+) -> Option<DictionaryArray<K>> {
+    let keys = (0..text.len())
+        .map(|index| match text.is_valid(index) {
+            true => K::Native::from_usize(index).map(Some),
+            false => Some(None),
+        })
+        .collect::<Option<PrimitiveArray<K>>>()?;
+    Some(DictionaryArray::try_new(keys, Arc::new(text.clone())).unwrap())
+}
+
+fn append_all<K: ArrowDictionaryKeyType>(
+    builder: &mut StringDictionaryBuilder<K>,
+    text: &StringArray,
+) -> Result<(), ArrowError> {
+    for value in text {
+        match value {
+            Some(value) => {
+                builder.append(value)?;
+            }
+            None => builder.append_null(),
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,130 @@
+use std::{
+    fmt::{Debug, Write},
+    panic::{catch_unwind, resume_unwind, AssertUnwindSafe},
+};
+
+use arrow::{array::ArrayRef, datatypes::Field};
+use odbc_api::buffers::{AnySlice, BufferDesc, NullableSlice};
+
+use super::{MappingError, ReadStrategy, ValueErrors};
+
+/// Logs the contents of the buffer bound to a column, together with the field it is fetched into,
+/// then the inner strategy fails to convert it, or panics. Only available with the `dump-buffers`
+/// feature, intended for diagnosing data corrupted by a driver.
+pub struct DumpOnFailure {
+    inner: Box<dyn ReadStrategy>,
+    field: Field,
+    /// Zero based column index
+    index: usize,
+}
+
+impl DumpOnFailure {
+    pub fn new(inner: Box<dyn ReadStrategy>, field: Field, index: usize) -> Self {
+        Self {
+            inner,
+            field,
+            index,
+        }
+    }
+
+    fn dump(&self, column_view: AnySlice, failure: &dyn Debug) {
+        log::error!(
+            "Failed to convert column {} fetched into {:?} from buffer {:?}: {:?}\n{}",
+            self.index,
+            self.field,
+            self.inner.buffer_desc(),
+            failure,
+            dump_slice(column_view)
+        );
+    }
+}
+
+impl ReadStrategy for DumpOnFailure {
+    fn buffer_desc(&self) -> BufferDesc {
+        self.inner.buffer_desc()
+    }
+
+    fn fill_arrow_array(
+        &self,
+        column_view: AnySlice,
+        errors: &ValueErrors,
+    ) -> Result<ArrayRef, MappingError> {
+        match catch_unwind(AssertUnwindSafe(|| {
+            self.inner.fill_arrow_array(column_view, errors)
+        })) {
+            Ok(Ok(array)) => Ok(array),
+            Ok(Err(error)) => {
+                self.dump(column_view, &error);
+                Err(error)
+            }
+            Err(panic) => {
+                self.dump(column_view, &"panic");
+                resume_unwind(panic)
+            }
+        }
+    }
+}
+
+/// One line per row. Variadic values are printed as hex, fixed size values in their debug
+/// representation.
+fn dump_slice(column_view: AnySlice) -> String {
+    match column_view {
+        AnySlice::Text(view) => dump_rows(view.iter().map(|value| value.map(hex))),
+        AnySlice::WText(view) => dump_rows((0..view.len()).map(|row| {
+            view.get(row).map(|value| {
+                hex(&value
+                    .iter()
+                    .flat_map(|c| c.to_be_bytes())
+                    .collect::<Vec<_>>())
+            })
+        })),
+        AnySlice::Binary(view) => dump_rows(view.iter().map(|value| value.map(hex))),
+        AnySlice::Date(values) => dump_values(values),
+        AnySlice::Time(values) => dump_values(values),
+        AnySlice::Timestamp(values) => dump_values(values),
+        AnySlice::F64(values) => dump_values(values),
+        AnySlice::F32(values) => dump_values(values),
+        AnySlice::I8(values) => dump_values(values),
+        AnySlice::I16(values) => dump_values(values),
+        AnySlice::I32(values) => dump_values(values),
+        AnySlice::I64(values) => dump_values(values),
+        AnySlice::U8(values) => dump_values(values),
+        AnySlice::Bit(values) => dump_values(values),
+        AnySlice::NullableDate(values) => dump_nullable(values),
+        AnySlice::NullableTime(values) => dump_nullable(values),
+        AnySlice::NullableTimestamp(values) => dump_nullable(values),
+        AnySlice::NullableF64(values) => dump_nullable(values),
+        AnySlice::NullableF32(values) => dump_nullable(values),
+        AnySlice::NullableI8(values) => dump_nullable(values),
+        AnySlice::NullableI16(values) => dump_nullable(values),
+        AnySlice::NullableI32(values) => dump_nullable(values),
+        AnySlice::NullableI64(values) => dump_nullable(values),
+        AnySlice::NullableU8(values) => dump_nullable(values),
+        AnySlice::NullableBit(values) => dump_nullable(values),
+    }
+}
+
+fn dump_values<T: Debug>(values: &[T]) -> String {
+    dump_rows(values.iter().map(|value| Some(format!("{value:?}"))))
+}
+
+fn dump_nullable<T: Debug>(values: NullableSlice<T>) -> String {
+    dump_rows(values.map(|value| value.map(|value| format!("{value:?}"))))
+}
+
+fn dump_rows(rows: impl Iterator<Item = Option<String>>) -> String {
+    let mut dump = String::new();
+    for (row, value) in rows.enumerate() {
+        let value = value.as_deref().unwrap_or("NULL");
+        writeln!(dump, "{row}: {value}").unwrap();
+    }
+    dump
+}
+
+fn hex(bytes: &[u8]) -> String {
+    let mut hex = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        write!(hex, "{byte:02x}").unwrap();
+    }
+    hex
+}
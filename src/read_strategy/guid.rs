@@ -0,0 +1,178 @@
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, FixedSizeBinaryBuilder, StringBuilder};
+use odbc_api::{
+    buffers::{AnySlice, BufferDesc},
+    sys::SqlDataType,
+    DataType as OdbcDataType,
+};
+
+use super::{check_text_truncation, MappingError, ReadStrategy, ValueErrors};
+
+/// `true` if `sql_type` describes a GUID column, e.g. `UNIQUEIDENTIFIER` in Microsoft SQL Server or
+/// `UUID` in PostgreSQL.
+pub fn is_guid(sql_type: OdbcDataType) -> bool {
+    matches!(
+        sql_type,
+        OdbcDataType::Other {
+            data_type: SqlDataType::EXT_GUID,
+            ..
+        }
+    )
+}
+
+/// Byte order of GUIDs fetched into `FixedSizeBinary(16)` arrays.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GuidByteOrder {
+    /// The bytes appear in the same order as the hex digits of the canonical text representation,
+    /// as specified by RFC 4122. E.g. `00112233-4455-6677-8899-aabbccddeeff` becomes the bytes
+    /// `00 11 22 .. ff`.
+    #[default]
+    BigEndian,
+    /// The first three groups are little endian, like the `GUID` struct on Windows and the binary
+    /// representation of `UNIQUEIDENTIFIER` in Microsoft SQL Server. E.g.
+    /// `00112233-4455-6677-8899-aabbccddeeff` becomes the bytes `33 22 11 00 55 44 77 66 88 .. ff`.
+    MixedEndian,
+}
+
+/// Representation of GUID columns in the inferred schema.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GuidRepresentation {
+    /// `Utf8` in the text representation of the driver. Depending on the driver it may be upper or
+    /// lower case and enclosed in braces.
+    #[default]
+    Text,
+    /// `Utf8` in the canonical lower case and hyphenated text representation, e.g.
+    /// `00112233-4455-6677-8899-aabbccddeeff`, regardless of the driver.
+    CanonicalText,
+    /// `FixedSizeBinary(16)` holding the bytes in the order specified by [`GuidByteOrder`].
+    Binary,
+}
+
+/// Fetches GUIDs in their text representation and parses them. The binary representation is
+/// backend specific, but the text representation is the same for all of them. So the same logical
+/// GUID always results in the same bytes, regardless of the data source.
+pub struct GuidFromText {
+    byte_order: GuidByteOrder,
+}
+
+impl GuidFromText {
+    pub fn new(byte_order: GuidByteOrder) -> Self {
+        Self { byte_order }
+    }
+}
+
+impl ReadStrategy for GuidFromText {
+    fn buffer_desc(&self) -> BufferDesc {
+        BufferDesc::Text {
+            // 32 hex digits, 4 hyphens and possibly enclosing braces
+            max_str_len: 38,
+        }
+    }
+
+    fn fill_arrow_array(
+        &self,
+        column_view: AnySlice,
+        errors: &ValueErrors,
+    ) -> Result<ArrayRef, MappingError> {
+        let view = column_view.as_text_view().unwrap();
+        check_text_truncation(&view)?;
+        let mut builder = FixedSizeBinaryBuilder::with_capacity(view.len(), 16);
+        for text in view.iter() {
+            if let Some(text) = text {
+                let parsed = parse_guid(text).ok_or_else(|| MappingError::InvalidGuid {
+                    value: String::from_utf8_lossy(text).into_owned(),
+                });
+                let Some(mut bytes) = errors.recover(parsed)? else {
+                    builder.append_null();
+                    continue;
+                };
+                if self.byte_order == GuidByteOrder::MixedEndian {
+                    bytes[0..4].reverse();
+                    bytes[4..6].reverse();
+                    bytes[6..8].reverse();
+                }
+                builder.append_value(bytes).unwrap();
+            } else {
+                builder.append_null();
+            }
+        }
+        Ok(Arc::new(builder.finish()))
+    }
+}
+
+/// Fetches GUIDs in their text representation and emits it in canonical form, i.e. lower case and
+/// without braces, so it is the same for all drivers.
+pub struct GuidAsCanonicalText;
+
+impl ReadStrategy for GuidAsCanonicalText {
+    fn buffer_desc(&self) -> BufferDesc {
+        BufferDesc::Text {
+            // 32 hex digits, 4 hyphens and possibly enclosing braces
+            max_str_len: 38,
+        }
+    }
+
+    fn fill_arrow_array(
+        &self,
+        column_view: AnySlice,
+        errors: &ValueErrors,
+    ) -> Result<ArrayRef, MappingError> {
+        let view = column_view.as_text_view().unwrap();
+        check_text_truncation(&view)?;
+        // 36 bytes for each canonical GUID
+        let mut builder = StringBuilder::with_capacity(view.len(), view.len() * 36);
+        let mut buf = String::with_capacity(36);
+        for text in view.iter() {
+            if let Some(text) = text {
+                let parsed = parse_guid(text).ok_or_else(|| MappingError::InvalidGuid {
+                    value: String::from_utf8_lossy(text).into_owned(),
+                });
+                let Some(bytes) = errors.recover(parsed)? else {
+                    builder.append_null();
+                    continue;
+                };
+                format_guid(&bytes, &mut buf);
+                builder.append_value(&buf);
+            } else {
+                builder.append_null();
+            }
+        }
+        Ok(Arc::new(builder.finish()))
+    }
+}
+
+/// Replaces the contents of `buf` with the canonical text representation of the GUID, given its
+/// bytes in big endian order.
+fn format_guid(bytes: &[u8; 16], buf: &mut String) {
+    buf.clear();
+    for (index, byte) in bytes.iter().enumerate() {
+        if [4, 6, 8, 10].contains(&index) {
+            buf.push('-');
+        }
+        buf.push_str(&format!("{byte:02x}"));
+    }
+}
+
+/// Parses the canonical text representation of a GUID, with or without enclosing braces, into its
+/// bytes in big endian order.
+fn parse_guid(text: &[u8]) -> Option<[u8; 16]> {
+    let text = match text {
+        [b'{', inner @ .., b'}'] => inner,
+        _ => text,
+    };
+    if text.len() != 36 || [8, 13, 18, 23].iter().any(|&i| text[i] != b'-') {
+        return None;
+    }
+    let mut digits = text
+        .iter()
+        .filter(|&&c| c != b'-')
+        .map(|&c| (c as char).to_digit(16));
+    let mut bytes = [0; 16];
+    for byte in &mut bytes {
+        let high = digits.next()??;
+        let low = digits.next()??;
+        *byte = (high * 16 + low) as u8;
+    }
+    Some(bytes)
+}
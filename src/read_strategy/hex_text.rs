@@ -0,0 +1,70 @@
+use std::sync::Arc;
+
+use arrow::array::{
+    Array, ArrayRef, BinaryArray, FixedSizeBinaryArray, LargeBinaryArray, StringArray,
+    StringBuilder,
+};
+use odbc_api::buffers::{AnySlice, BufferDesc};
+
+use super::{MappingError, ReadStrategy, ValueErrors};
+
+/// Emits binary values as text of lowercase hex digits without prefix, for consumers which can not
+/// handle binary arrays. Wraps the strategy filling a `Binary`, `LargeBinary` or `FixedSizeBinary`
+/// array. Empty values become empty strings, `NULL` stays `NULL`.
+pub struct HexText {
+    inner: Box<dyn ReadStrategy>,
+}
+
+impl HexText {
+    pub fn new(inner: Box<dyn ReadStrategy>) -> Self {
+        Self { inner }
+    }
+}
+
+impl ReadStrategy for HexText {
+    fn buffer_desc(&self) -> BufferDesc {
+        self.inner.buffer_desc()
+    }
+
+    fn fill_arrow_array(
+        &self,
+        column_view: AnySlice,
+        errors: &ValueErrors,
+    ) -> Result<ArrayRef, MappingError> {
+        let source = self.inner.fill_arrow_array(column_view, errors)?;
+        let any = source.as_any();
+        let hex = if let Some(source) = any.downcast_ref::<FixedSizeBinaryArray>() {
+            to_hex(source.len() * source.value_length() as usize, source.iter())
+        } else if let Some(source) = any.downcast_ref::<LargeBinaryArray>() {
+            to_hex(source.value_data().len(), source.iter())
+        } else {
+            let source = any.downcast_ref::<BinaryArray>().unwrap();
+            to_hex(source.value_data().len(), source.iter())
+        };
+        Ok(Arc::new(hex))
+    }
+}
+
+/// `num_bytes` is the total length of the binary values, used to size the buffer of the text.
+fn to_hex<'a>(
+    num_bytes: usize,
+    values: impl ExactSizeIterator<Item = Option<&'a [u8]>>,
+) -> StringArray {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+    let mut builder = StringBuilder::with_capacity(values.len(), num_bytes * 2);
+    let mut buf = String::new();
+    for value in values {
+        match value {
+            Some(bytes) => {
+                buf.clear();
+                for byte in bytes {
+                    buf.push(DIGITS[(byte >> 4) as usize] as char);
+                    buf.push(DIGITS[(byte & 0xf) as usize] as char);
+                }
+                builder.append_value(&buf);
+            }
+            None => builder.append_null(),
+        }
+    }
+    builder.finish()
+}
@@ -0,0 +1,274 @@
+use std::sync::Arc;
+
+use arrow::{
+    array::{ArrayRef, IntervalDayTimeArray, IntervalMonthDayNanoArray, IntervalYearMonthArray},
+    datatypes::{DataType, IntervalDayTimeType, IntervalMonthDayNanoType, IntervalUnit},
+};
+use odbc_api::{
+    buffers::{AnySlice, BufferDesc},
+    sys::SqlDataType,
+    DataType as OdbcDataType,
+};
+
+use super::{check_text_truncation, MappingError, ReadStrategy, ValueErrors};
+
+/// Fields of a SQL interval, from the most to the least significant one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum IntervalField {
+    Year,
+    Month,
+    Day,
+    Hour,
+    Minute,
+    Second,
+}
+
+/// Leading and trailing field of a SQL interval type, e.g. `Day` and `Second` for
+/// `INTERVAL DAY TO SECOND`. `None` if `sql_type` is not an interval.
+pub fn interval_fields(sql_type: OdbcDataType) -> Option<(IntervalField, IntervalField)> {
+    use IntervalField::*;
+    // `odbc-api` reports intervals as `Other`, with the concise type `SQL_INTERVAL_*`.
+    let OdbcDataType::Other {
+        data_type: SqlDataType(code),
+        ..
+    } = sql_type
+    else {
+        return None;
+    };
+    let fields = match code {
+        101 => (Year, Year),
+        102 => (Month, Month),
+        103 => (Day, Day),
+        104 => (Hour, Hour),
+        105 => (Minute, Minute),
+        106 => (Second, Second),
+        107 => (Year, Month),
+        108 => (Day, Hour),
+        109 => (Day, Minute),
+        110 => (Day, Second),
+        111 => (Hour, Minute),
+        112 => (Hour, Second),
+        113 => (Minute, Second),
+        _ => return None,
+    };
+    Some(fields)
+}
+
+/// Arrow interval unit inferred for a SQL interval type. Year-month intervals become
+/// `YearMonth`. Day-time intervals become `DayTime`, unless they have more than three fractional
+/// second digits, which require `MonthDayNano`.
+pub fn interval_unit(sql_type: OdbcDataType) -> Option<IntervalUnit> {
+    let (leading, trailing) = interval_fields(sql_type)?;
+    let fractional_digits = sql_type.decimal_digits();
+    let unit = if leading <= IntervalField::Month {
+        IntervalUnit::YearMonth
+    } else if trailing == IntervalField::Second && fractional_digits > 3 {
+        IntervalUnit::MonthDayNano
+    } else {
+        IntervalUnit::DayTime
+    };
+    Some(unit)
+}
+
+/// Fetches intervals as text and parses them. `odbc-api` offers no buffer for
+/// `SQL_INTERVAL_STRUCT`. The text is expected in the format of SQL interval literals without
+/// qualifier, e.g. `-1-06` for a year-month interval or `1 02:03:04.5` for a day-time interval.
+pub struct IntervalFromText {
+    unit: IntervalUnit,
+    leading: IntervalField,
+    trailing: IntervalField,
+}
+
+impl IntervalFromText {
+    /// `fields` are the leading and trailing field of the SQL interval type. `None` for
+    /// columns of other types, whose text is then expected to contain all fields of the unit,
+    /// i.e. years and months, or days to seconds.
+    pub fn new(unit: IntervalUnit, fields: Option<(IntervalField, IntervalField)>) -> Self {
+        let (leading, trailing) = fields.unwrap_or(match unit {
+            IntervalUnit::YearMonth => (IntervalField::Year, IntervalField::Month),
+            IntervalUnit::DayTime | IntervalUnit::MonthDayNano => {
+                (IntervalField::Day, IntervalField::Second)
+            }
+        });
+        Self {
+            unit,
+            leading,
+            trailing,
+        }
+    }
+}
+
+impl ReadStrategy for IntervalFromText {
+    fn buffer_desc(&self) -> BufferDesc {
+        BufferDesc::Text {
+            // Leading field with the maximum precision of 18 digits, sign, up to five more fields
+            // and nine fractional digits.
+            max_str_len: 48,
+        }
+    }
+
+    fn fill_arrow_array(
+        &self,
+        column_view: AnySlice,
+        errors: &ValueErrors,
+    ) -> Result<ArrayRef, MappingError> {
+        let view = column_view.as_text_view().unwrap();
+        check_text_truncation(&view)?;
+        let parse = |text: &[u8]| {
+            parse_interval(text, self.leading, self.trailing).ok_or_else(|| {
+                MappingError::InvalidInterval {
+                    value: String::from_utf8_lossy(text).into_owned(),
+                }
+            })
+        };
+        let out_of_range = |interval: &Interval| MappingError::IntervalOutOfRange {
+            value: interval.text.clone(),
+            data_type: DataType::Interval(self.unit.clone()),
+        };
+        let array: ArrayRef = match &self.unit {
+            IntervalUnit::YearMonth => Arc::new(
+                view.iter()
+                    .map(|text| match text {
+                        Some(text) => errors.recover(parse(text).and_then(|interval| {
+                            interval.months().ok_or_else(|| out_of_range(&interval))
+                        })),
+                        None => Ok(None),
+                    })
+                    .collect::<Result<IntervalYearMonthArray, _>>()?,
+            ),
+            IntervalUnit::DayTime => Arc::new(
+                view.iter()
+                    .map(|text| match text {
+                        Some(text) => errors.recover(parse(text).and_then(|interval| {
+                            interval.day_time().ok_or_else(|| out_of_range(&interval))
+                        })),
+                        None => Ok(None),
+                    })
+                    .collect::<Result<IntervalDayTimeArray, _>>()?,
+            ),
+            IntervalUnit::MonthDayNano => Arc::new(
+                view.iter()
+                    .map(|text| match text {
+                        Some(text) => errors.recover(parse(text).and_then(|interval| {
+                            interval
+                                .month_day_nano()
+                                .ok_or_else(|| out_of_range(&interval))
+                        })),
+                        None => Ok(None),
+                    })
+                    .collect::<Result<IntervalMonthDayNanoArray, _>>()?,
+            ),
+        };
+        Ok(array)
+    }
+}
+
+/// A parsed interval. Only either the year-month or the day-time fields are set.
+struct Interval {
+    /// Original text, to report values out of range.
+    text: String,
+    negative: bool,
+    /// Values of the fields, indexed by [`IntervalField`].
+    fields: [u64; 6],
+    /// Fractional seconds
+    nanos: u64,
+}
+
+impl Interval {
+    fn field(&self, field: IntervalField) -> u64 {
+        self.fields[field as usize]
+    }
+
+    fn sign(&self, value: i64) -> i64 {
+        if self.negative {
+            -value
+        } else {
+            value
+        }
+    }
+
+    fn months(&self) -> Option<i32> {
+        let months = self
+            .field(IntervalField::Year)
+            .checked_mul(12)?
+            .checked_add(self.field(IntervalField::Month))?;
+        self.sign(months.try_into().ok()?).try_into().ok()
+    }
+
+    /// Nanoseconds of the fields below days.
+    fn nanos_of_time(&self) -> Option<u64> {
+        let seconds = self
+            .field(IntervalField::Hour)
+            .checked_mul(60)?
+            .checked_add(self.field(IntervalField::Minute))?
+            .checked_mul(60)?
+            .checked_add(self.field(IntervalField::Second))?;
+        seconds.checked_mul(1_000_000_000)?.checked_add(self.nanos)
+    }
+
+    /// Fractional milliseconds are truncated.
+    fn day_time(&self) -> Option<i64> {
+        const MS_PER_DAY: u64 = 86_400_000;
+        let ms = self.nanos_of_time()? / 1_000_000;
+        let days = self
+            .field(IntervalField::Day)
+            .checked_add(ms / MS_PER_DAY)?;
+        let days = self.sign(days.try_into().ok()?).try_into().ok()?;
+        let ms = self.sign((ms % MS_PER_DAY) as i64) as i32;
+        Some(IntervalDayTimeType::make_value(days, ms))
+    }
+
+    fn month_day_nano(&self) -> Option<i128> {
+        let days = self.field(IntervalField::Day);
+        let days = self.sign(days.try_into().ok()?).try_into().ok()?;
+        let nanos = self.sign(self.nanos_of_time()?.try_into().ok()?);
+        Some(IntervalMonthDayNanoType::make_value(0, days, nanos))
+    }
+}
+
+/// Parses the text of an interval with the fields from `leading` to `trailing`. Fields are
+/// separated by `-` for year-month intervals, and by a space between days and hours and `:`
+/// between the other fields of day-time intervals. `None` if the text does not match.
+fn parse_interval(
+    text: &[u8],
+    leading: IntervalField,
+    trailing: IntervalField,
+) -> Option<Interval> {
+    let text = std::str::from_utf8(text).ok()?.trim();
+    let (negative, unsigned) = match text.strip_prefix('-') {
+        Some(unsigned) => (true, unsigned),
+        None => (false, text),
+    };
+    let (whole, fraction) = match unsigned.split_once('.') {
+        Some((whole, fraction)) if trailing == IntervalField::Second => (whole, Some(fraction)),
+        Some(_) => return None,
+        None => (unsigned, None),
+    };
+    let mut fields = [0; 6];
+    let mut components = whole.split([' ', ':', '-']);
+    for value in &mut fields[leading as usize..=trailing as usize] {
+        let component = components.next()?;
+        if component.is_empty() || !component.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        *value = component.parse().ok()?;
+    }
+    if components.next().is_some() {
+        return None;
+    }
+    let nanos = match fraction {
+        Some(digits) if !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()) => {
+            // Digits beyond nanoseconds are truncated.
+            let digits = &digits[..digits.len().min(9)];
+            digits.parse::<u64>().unwrap() * 10u64.pow(9 - digits.len() as u32)
+        }
+        Some(_) => return None,
+        None => 0,
+    };
+    Some(Interval {
+        text: text.to_owned(),
+        negative,
+        fields,
+        nanos,
+    })
+}
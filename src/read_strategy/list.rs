@@ -0,0 +1,183 @@
+use std::sync::Arc;
+
+use arrow::{
+    array::{Array, ArrayBuilder, ArrayRef, BooleanArray, ListArray, StringArray, StringBuilder},
+    buffer::{NullBuffer, OffsetBuffer},
+    compute::{cast_with_options, filter, CastOptions},
+    datatypes::{DataType, FieldRef},
+};
+use odbc_api::buffers::{AnySlice, BufferDesc};
+
+use super::{MappingError, ReadStrategy, ValueErrors};
+
+/// Parses array literals, as returned e.g. by the PostgreSQL driver for columns like `integer[]`,
+/// into a `List` of the element type. The text is expected as `{1,NULL,3}`. Elements may be
+/// double quoted, e.g. `{"a,b","c \"d\""}`, and backslashes escape the next character. Unquoted
+/// `NULL` is a `NULL` element, `{}` an empty list. The elements are cast from text into the type
+/// of the item field. Nested arrays are not supported.
+pub struct ListFromText {
+    inner: Box<dyn ReadStrategy>,
+    item: FieldRef,
+}
+
+impl ListFromText {
+    /// `inner` must emit `Utf8` arrays.
+    pub fn new(inner: Box<dyn ReadStrategy>, item: FieldRef) -> Self {
+        Self { inner, item }
+    }
+}
+
+impl ReadStrategy for ListFromText {
+    fn buffer_desc(&self) -> BufferDesc {
+        self.inner.buffer_desc()
+    }
+
+    fn fill_arrow_array(
+        &self,
+        column_view: AnySlice,
+        errors: &ValueErrors,
+    ) -> Result<ArrayRef, MappingError> {
+        let text = self.inner.fill_arrow_array(column_view, errors)?;
+        let text = text.as_any().downcast_ref::<StringArray>().unwrap();
+        let mut elements = StringBuilder::new();
+        // Range of the elements of each list in `elements`. `None` for `NULL`.
+        let mut lists = Vec::with_capacity(text.len());
+        for value in text.iter() {
+            let parsed = match value {
+                Some(value) => errors.recover(parse_array(value).ok_or_else(|| {
+                    MappingError::InvalidArray {
+                        value: value.to_owned(),
+                    }
+                }))?,
+                None => None,
+            };
+            lists.push(parsed.map(|parsed| {
+                let start = elements.len();
+                for element in parsed {
+                    elements.append_option(element);
+                }
+                start..elements.len()
+            }));
+        }
+        let elements = elements.finish();
+        let data_type = self.item.data_type();
+        // Safe, so elements which can not be cast become `NULL` and are reported below.
+        let options = CastOptions {
+            safe: true,
+            ..Default::default()
+        };
+        let values = cast_with_options(&elements, data_type, &options).map_err(|source| {
+            MappingError::Casting {
+                data_type: data_type.clone(),
+                source,
+            }
+        })?;
+        // Lists with elements which can not be cast are replaced with `NULL` as a whole, if the
+        // error is recovered. Their elements are removed, so they can not violate the
+        // nullability of the item field.
+        let mut keep = Vec::with_capacity(values.len());
+        let mut lengths = Vec::with_capacity(lists.len());
+        let mut validity = Vec::with_capacity(lists.len());
+        for list in lists {
+            let list = match list {
+                Some(range) => {
+                    let invalid = range
+                        .clone()
+                        .find(|&index| values.is_null(index) && elements.is_valid(index));
+                    if let Some(index) = invalid {
+                        errors.recover::<()>(Err(MappingError::InvalidArrayElement {
+                            value: elements.value(index).to_owned(),
+                            data_type: data_type.clone(),
+                        }))?;
+                        keep.extend(range.map(|_| false));
+                        None
+                    } else {
+                        keep.extend(range.clone().map(|_| true));
+                        Some(range.len())
+                    }
+                }
+                None => None,
+            };
+            lengths.push(list.unwrap_or(0));
+            validity.push(list.is_some());
+        }
+        let values = if keep.iter().all(|&keep| keep) {
+            values
+        } else {
+            filter(&values, &BooleanArray::from(keep)).unwrap()
+        };
+        let list = ListArray::try_new(
+            self.item.clone(),
+            OffsetBuffer::from_lengths(lengths),
+            values,
+            Some(NullBuffer::from(validity)),
+        )
+        .map_err(|source| MappingError::Casting {
+            data_type: DataType::List(self.item.clone()),
+            source,
+        })?;
+        Ok(Arc::new(list))
+    }
+}
+
+/// Elements of an array literal like `{1,NULL,"a b"}`. `None` if the text is not a valid array
+/// literal, or describes a nested array.
+fn parse_array(text: &str) -> Option<Vec<Option<String>>> {
+    let inner = text.trim().strip_prefix('{')?.strip_suffix('}')?;
+    let mut elements = Vec::new();
+    if inner.trim().is_empty() {
+        return Some(elements);
+    }
+    let mut chars = inner.chars().peekable();
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        let element = if chars.next_if_eq(&'"').is_some() {
+            let mut element = String::new();
+            loop {
+                match chars.next()? {
+                    '"' => break,
+                    '\\' => element.push(chars.next()?),
+                    c => element.push(c),
+                }
+            }
+            while chars.next_if(|c| c.is_whitespace()).is_some() {}
+            Some(element)
+        } else {
+            let mut element = String::new();
+            // Length of `element` without trailing whitespace, which has not been escaped.
+            let mut len = 0;
+            let mut escaped = false;
+            while let Some(c) = chars.next_if(|&c| c != ',') {
+                match c {
+                    '{' | '}' | '"' => return None,
+                    '\\' => {
+                        element.push(chars.next()?);
+                        escaped = true;
+                        len = element.len();
+                    }
+                    c => {
+                        element.push(c);
+                        if !c.is_whitespace() {
+                            len = element.len();
+                        }
+                    }
+                }
+            }
+            element.truncate(len);
+            if element.is_empty() {
+                return None;
+            }
+            if !escaped && element.eq_ignore_ascii_case("NULL") {
+                None
+            } else {
+                Some(element)
+            }
+        };
+        elements.push(element);
+        match chars.next() {
+            Some(',') => (),
+            None => return Some(elements),
+            Some(_) => return None,
+        }
+    }
+}
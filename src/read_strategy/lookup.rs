@@ -0,0 +1,101 @@
+use std::{collections::HashMap, sync::Arc};
+
+use arrow::{
+    array::{ArrayRef, StringArray, StringBuilder},
+    compute::cast,
+    datatypes::{DataType, Field},
+};
+use odbc_api::buffers::{AnySlice, BufferDesc};
+
+use super::{MappingError, ReadStrategy, ValueErrors};
+
+/// Replaces the values of a column with values from a lookup table while reading it. E.g. to map
+/// codes to human readable labels, saving a join against a small dimension table. Values are
+/// looked up by their text representation, the resulting arrow array is always `Utf8`.
+///
+/// Use [`crate::OdbcReaderBuilder::with_column_lookup`] to assign it to a column.
+#[derive(Debug, Clone)]
+pub struct ColumnLookup {
+    table: Arc<HashMap<String, String>>,
+    unmapped: UnmappedValue,
+}
+
+impl ColumnLookup {
+    /// # Parameters
+    ///
+    /// * `table`: Maps the text representation of values fetched from the database to their
+    ///   replacement.
+    /// * `unmapped`: What to do with values which are not a key in `table`.
+    pub fn new(table: HashMap<String, String>, unmapped: UnmappedValue) -> Self {
+        Self {
+            table: Arc::new(table),
+            unmapped,
+        }
+    }
+
+    /// The field emitted for the column, given the field describing the values fetched from the
+    /// database.
+    pub(crate) fn map_field(&self, field: &Field) -> Field {
+        let nullable = field.is_nullable() || self.unmapped == UnmappedValue::Null;
+        Field::new(field.name(), DataType::Utf8, nullable).with_metadata(field.metadata().clone())
+    }
+}
+
+/// Policy for values without an entry in the table of a [`ColumnLookup`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnmappedValue {
+    /// Keep the text representation of the original value.
+    Passthrough,
+    /// Replace the value with `NULL`.
+    Null,
+    /// Emit an error, then reading the batch.
+    Error,
+}
+
+/// Fills the arrow array of the column with the inner strategy and replaces its values afterwards.
+pub struct Lookup {
+    inner: Box<dyn ReadStrategy>,
+    lookup: ColumnLookup,
+}
+
+impl Lookup {
+    pub fn new(inner: Box<dyn ReadStrategy>, lookup: ColumnLookup) -> Self {
+        Self { inner, lookup }
+    }
+}
+
+impl ReadStrategy for Lookup {
+    fn buffer_desc(&self) -> BufferDesc {
+        self.inner.buffer_desc()
+    }
+
+    fn fill_arrow_array(
+        &self,
+        column_view: AnySlice,
+        errors: &ValueErrors,
+    ) -> Result<ArrayRef, MappingError> {
+        let source = self.inner.fill_arrow_array(column_view, errors)?;
+        let keys = cast(&source, &DataType::Utf8).map_err(MappingError::LookupKeyAsText)?;
+        let keys = keys.as_any().downcast_ref::<StringArray>().unwrap();
+        let mut builder = StringBuilder::new();
+        for key in keys {
+            let Some(key) = key else {
+                builder.append_null();
+                continue;
+            };
+            match (self.lookup.table.get(key), self.lookup.unmapped) {
+                (Some(value), _) => builder.append_value(value),
+                (None, UnmappedValue::Passthrough) => builder.append_value(key),
+                (None, UnmappedValue::Null) => builder.append_null(),
+                (None, UnmappedValue::Error) => {
+                    let unmapped = Err(MappingError::UnmappedValue {
+                        value: key.to_owned(),
+                    });
+                    errors.recover::<()>(unmapped)?;
+                    builder.append_null()
+                }
+            }
+        }
+        Ok(Arc::new(builder.finish()))
+    }
+}
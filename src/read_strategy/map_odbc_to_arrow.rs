@@ -2,17 +2,20 @@ use std::{marker::PhantomData, sync::Arc};
 
 use arrow::{
     array::{ArrayRef, PrimitiveBuilder},
-    datatypes::ArrowPrimitiveType,
+    datatypes::{ArrowPrimitiveType, Float16Type},
 };
 use chrono::NaiveDateTime;
 use odbc_api::buffers::{AnySlice, BufferDesc, Item};
 use thiserror::Error;
 
-use super::ReadStrategy;
+use super::{ReadStrategy, ValueErrors};
 
+/// Read strategies for arrow primitive types. Non nullable columns are bound to buffers without
+/// indicators, and their values appended without checking for `NULL`.
 pub trait MapOdbcToArrow {
     type ArrowElement;
 
+    /// Converts each value of type `U` fetched from the database with `odbc_to_arrow`.
     fn map_with<U>(
         nullable: bool,
         odbc_to_arrow: impl Fn(&U) -> Result<Self::ArrowElement, MappingError> + 'static + Send + Sync,
@@ -20,6 +23,7 @@ pub trait MapOdbcToArrow {
     where
         U: Send + Sync + Item + 'static;
 
+    /// Copies the values fetched from the database, which already have the layout of arrow.
     fn identical(nullable: bool) -> Box<dyn ReadStrategy>
     where
         Self::ArrowElement: Item;
@@ -79,7 +83,11 @@ where
         T::Native::buffer_desc(false)
     }
 
-    fn fill_arrow_array(&self, column_view: AnySlice) -> Result<ArrayRef, MappingError> {
+    fn fill_arrow_array(
+        &self,
+        column_view: AnySlice,
+        _errors: &ValueErrors,
+    ) -> Result<ArrayRef, MappingError> {
         let slice = T::Native::as_slice(column_view).unwrap();
         let mut builder = PrimitiveBuilder::<T>::with_capacity(slice.len());
         builder.append_slice(slice);
@@ -109,7 +117,11 @@ where
         T::Native::buffer_desc(true)
     }
 
-    fn fill_arrow_array(&self, column_view: AnySlice) -> Result<ArrayRef, MappingError> {
+    fn fill_arrow_array(
+        &self,
+        column_view: AnySlice,
+        _errors: &ValueErrors,
+    ) -> Result<ArrayRef, MappingError> {
         let values = T::Native::as_nullable_slice(column_view).unwrap();
         let mut builder = PrimitiveBuilder::<T>::with_capacity(values.len());
         for value in values {
@@ -145,11 +157,15 @@ where
         O::buffer_desc(false)
     }
 
-    fn fill_arrow_array(&self, column_view: AnySlice) -> Result<ArrayRef, MappingError> {
+    fn fill_arrow_array(
+        &self,
+        column_view: AnySlice,
+        errors: &ValueErrors,
+    ) -> Result<ArrayRef, MappingError> {
         let slice = column_view.as_slice::<O>().unwrap();
         let mut builder = PrimitiveBuilder::<P>::with_capacity(slice.len());
         for odbc_value in slice {
-            builder.append_value((self.odbc_to_arrow)(odbc_value)?);
+            builder.append_option(errors.recover((self.odbc_to_arrow)(odbc_value))?);
         }
         Ok(Arc::new(builder.finish()))
     }
@@ -181,11 +197,19 @@ where
         O::buffer_desc(true)
     }
 
-    fn fill_arrow_array(&self, column_view: AnySlice) -> Result<ArrayRef, MappingError> {
+    fn fill_arrow_array(
+        &self,
+        column_view: AnySlice,
+        errors: &ValueErrors,
+    ) -> Result<ArrayRef, MappingError> {
         let opts = column_view.as_nullable_slice::<O>().unwrap();
         let mut builder = PrimitiveBuilder::<P>::with_capacity(opts.len());
         for odbc_opt in opts {
-            builder.append_option(odbc_opt.map(&self.odbc_to_arrow).transpose()?);
+            let value = match odbc_opt {
+                Some(odbc_value) => errors.recover((self.odbc_to_arrow)(odbc_value))?,
+                None => None,
+            };
+            builder.append_option(value);
         }
         Ok(Arc::new(builder.finish()))
     }
@@ -202,8 +226,166 @@ pub enum MappingError {
         limits their range to values between 1677-09-21 00:12:44 and \
         2262-04-11 23:47:16.854775807. The value returned from the database is outside of this \
         range. Suggestions to fix this error either reduce the precision or fetch the values as \
-        text.\
+        text, e.g. using `OdbcReaderBuilder::with_timestamp_as_text`.\
     "
     )]
     OutOfRangeTimestampNs { value: NaiveDateTime },
+    #[error(
+        "The database returned the timestamp {value} for a column fetched as date, but its time \
+        of day is not midnight."
+    )]
+    NotMidnight { value: NaiveDateTime },
+    #[error("The database returned '{value}', which is not a valid GUID.")]
+    InvalidGuid { value: String },
+    #[error("The database returned '{value}', which is neither '0' nor '1' for a boolean.")]
+    InvalidBoolean { value: String },
+    #[error(
+        "The database returned wide text with the unpaired surrogate {code_unit:#06x} in row \
+        {index} of the batch, which is not valid UTF-16."
+    )]
+    InvalidUtf16 { code_unit: u16, index: usize },
+    #[error(
+        "The database returned narrow text in row {index} of the batch, which is not valid UTF-8. \
+        Specify a decoding fallback, or fetch the column as wide text."
+    )]
+    InvalidUtf8 { index: usize },
+    #[error(
+        "The database returned '{value}' for a column without a text type fetched as text, which \
+        is not ASCII."
+    )]
+    NonAsciiText { value: String },
+    #[error(
+        "The database returned '{value}', which is not a valid timestamp with time zone offset."
+    )]
+    InvalidTimestampOffset { value: String },
+    #[error("The database returned '{value}', which is not a valid time of day.")]
+    InvalidTime { value: String },
+    #[error(
+        "The value '{value}' has no entry in the lookup table of its column and the lookup is \
+        configured to emit an error for unmapped values."
+    )]
+    UnmappedValue { value: String },
+    #[error("Values of the column can not be represented as text to look them up:\n{0}")]
+    LookupKeyAsText(#[source] arrow::error::ArrowError),
+    #[error(
+        "The dictionary of the column holds more distinct values than can be indexed with keys of \
+        type {key_type}. Choose a wider key type for the column."
+    )]
+    DictionaryKeyOverflow {
+        key_type: arrow::datatypes::DataType,
+    },
+    #[error("Text fetched from the database could not be parsed into a number:\n{0}")]
+    CoercingText(#[source] arrow::error::ArrowError),
+    #[error(
+        "The database returned a binary value of {len} bytes, which can not be split into \
+        elements of {element_size} bytes to convert their byte order."
+    )]
+    PackedElementSize { len: usize, element_size: usize },
+    #[error(
+        "The database returned the decimal '{value}', whose unscaled value does not fit into the \
+        128 Bit integer of a Decimal128."
+    )]
+    DecimalOutOfRange { value: String },
+    #[error(
+        "The database returned text, which does not fit into the buffer of {buffer_len} elements \
+        allocated for the column, and has been truncated. Either increase the maximum text size, \
+        or explicitly allow truncation."
+    )]
+    TextTruncated { buffer_len: usize },
+    #[error(
+        "The database returned a binary value, which does not fit into the buffer of {buffer_len} \
+        bytes allocated for the column, and has been truncated. Increase the maximum binary size."
+    )]
+    BinaryTruncated { buffer_len: usize },
+    #[error("The database returned {value}, which does not fit into an arrow {target_type}.")]
+    IntegerOutOfRange {
+        value: String,
+        target_type: arrow::datatypes::DataType,
+    },
+    #[error(
+        "The database returned a value other than NULL in row {index} of the batch for a column \
+        fetched as arrow Null."
+    )]
+    NotNull { index: usize },
+    #[error(
+        "The database returned NULL in row {index} of the batch for a column whose field is not \
+        nullable."
+    )]
+    UnexpectedNull { index: usize },
+    #[error(
+        "The read strategy of the column emitted a `{data_type}` array of length {len}, yet a \
+        `{expected_type}` array of length {expected_len} is required."
+    )]
+    UnexpectedArray {
+        expected_type: arrow::datatypes::DataType,
+        expected_len: usize,
+        data_type: arrow::datatypes::DataType,
+        len: usize,
+    },
+    #[error("The database returned '{value}' for an interval, which is not a valid interval.")]
+    InvalidInterval { value: String },
+    #[error("The database returned '{value}' for a list, which is not a valid array literal.")]
+    InvalidArray { value: String },
+    #[error(
+        "The database returned the array element '{value}', which is not a valid {data_type}."
+    )]
+    InvalidArrayElement {
+        value: String,
+        data_type: arrow::datatypes::DataType,
+    },
+    #[error(
+        "The database returned {value}, which is too large for a 16 Bit float and would become \
+        infinite."
+    )]
+    Float16OutOfRange { value: f64 },
+    #[error("The database returned the interval '{value}', which does not fit into {data_type}.")]
+    IntervalOutOfRange {
+        value: String,
+        data_type: arrow::datatypes::DataType,
+    },
+    #[error("Values of the column could not be cast into `{data_type}`:\n{source}")]
+    Casting {
+        data_type: arrow::datatypes::DataType,
+        source: arrow::error::ArrowError,
+    },
+}
+
+impl MappingError {
+    /// The integer `value` does not fit into arrow integers of type `target_type`.
+    pub fn integer_out_of_range(
+        value: impl ToString,
+        target_type: arrow::datatypes::DataType,
+    ) -> Self {
+        MappingError::IntegerOutOfRange {
+            value: value.to_string(),
+            target_type,
+        }
+    }
+}
+
+/// Converts an integer fetched from the database into the native type of a narrower arrow integer
+/// type `target_type`, reporting values which do not fit.
+pub fn narrow_integer<S, T>(
+    value: S,
+    target_type: arrow::datatypes::DataType,
+) -> Result<T, MappingError>
+where
+    S: Copy + ToString,
+    T: TryFrom<S>,
+{
+    T::try_from(value).map_err(|_| MappingError::integer_out_of_range(value, target_type))
+}
+
+/// Converts a float fetched from the database into half precision. Values too large for half
+/// precision become infinite, or are reported if `strict` is `true`. Values too close to zero
+/// become zero either way.
+pub fn to_float16(
+    value: f64,
+    strict: bool,
+) -> Result<<Float16Type as ArrowPrimitiveType>::Native, MappingError> {
+    let half = <Float16Type as ArrowPrimitiveType>::Native::from_f64(value);
+    if strict && value.is_finite() && half.is_infinite() {
+        return Err(MappingError::Float16OutOfRange { value });
+    }
+    Ok(half)
 }
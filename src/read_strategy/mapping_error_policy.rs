@@ -0,0 +1,61 @@
+use std::sync::Arc;
+
+use super::MappingError;
+
+type CallbackFn = dyn Fn(usize, &MappingError) + Send + Sync;
+
+/// What to do with a value fetched from the database, which can not be converted into its arrow
+/// type, e.g. a timestamp out of range or an invalid GUID. Errors affecting the column as a whole,
+/// like truncated values or overflowing dictionary keys, always fail the batch.
+///
+/// Use [`crate::OdbcReaderBuilder::with_mapping_error_policy`] to set it for a reader.
+#[derive(Clone, Default)]
+pub enum MappingErrorPolicy {
+    /// Fail the entire batch.
+    #[default]
+    Fail,
+    /// Replace the value with `NULL` and continue.
+    Null,
+    /// Invoke the callback with the zero based index of the column and the error, then replace the
+    /// value with `NULL` and continue. E.g. to log the offending values.
+    Callback(Arc<CallbackFn>),
+}
+
+impl std::fmt::Debug for MappingErrorPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MappingErrorPolicy::Fail => f.write_str("Fail"),
+            MappingErrorPolicy::Null => f.write_str("Null"),
+            MappingErrorPolicy::Callback(_) => f.write_str("Callback"),
+        }
+    }
+}
+
+/// Applies the [`MappingErrorPolicy`] of a reader to the values of one column.
+pub struct ValueErrors<'a> {
+    policy: &'a MappingErrorPolicy,
+    /// Zero based column index
+    column_index: usize,
+}
+
+impl<'a> ValueErrors<'a> {
+    pub fn new(policy: &'a MappingErrorPolicy, column_index: usize) -> Self {
+        Self {
+            policy,
+            column_index,
+        }
+    }
+
+    /// `Ok(None)` if the value failed to convert and is to be replaced with `NULL`.
+    pub fn recover<T>(&self, result: Result<T, MappingError>) -> Result<Option<T>, MappingError> {
+        match (result, self.policy) {
+            (Ok(value), _) => Ok(Some(value)),
+            (Err(error), MappingErrorPolicy::Fail) => Err(error),
+            (Err(_), MappingErrorPolicy::Null) => Ok(None),
+            (Err(error), MappingErrorPolicy::Callback(callback)) => {
+                callback(self.column_index, &error);
+                Ok(None)
+            }
+        }
+    }
+}
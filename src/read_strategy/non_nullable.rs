@@ -0,0 +1,38 @@
+use arrow::array::{Array, ArrayRef};
+use odbc_api::buffers::{AnySlice, BufferDesc};
+
+use super::{MappingError, ReadStrategy, ValueErrors};
+
+/// Reports `NULL` values in columns of non nullable fields, rather than emitting arrays the record
+/// batch could not be constructed from. E.g. if the nullability of a column has been overridden,
+/// see [`crate::OdbcReaderBuilder::with_nullability`].
+pub struct NonNullable {
+    inner: Box<dyn ReadStrategy>,
+}
+
+impl NonNullable {
+    pub fn new(inner: Box<dyn ReadStrategy>) -> Self {
+        Self { inner }
+    }
+}
+
+impl ReadStrategy for NonNullable {
+    fn buffer_desc(&self) -> BufferDesc {
+        self.inner.buffer_desc()
+    }
+
+    fn fill_arrow_array(
+        &self,
+        column_view: AnySlice,
+        errors: &ValueErrors,
+    ) -> Result<ArrayRef, MappingError> {
+        let array = self.inner.fill_arrow_array(column_view, errors)?;
+        if array.null_count() != 0 {
+            let index = (0..array.len())
+                .find(|&index| array.is_null(index))
+                .unwrap();
+            return Err(MappingError::UnexpectedNull { index });
+        }
+        Ok(array)
+    }
+}
@@ -1,146 +1,637 @@
-use std::{char::decode_utf16, convert::TryInto, sync::Arc, cmp::min};
+use std::{
+    borrow::Cow,
+    cell::Cell,
+    char::{decode_utf16, REPLACEMENT_CHARACTER},
+    cmp::min,
+    convert::TryInto,
+    marker::PhantomData,
+    sync::Arc,
+};
 
-use arrow::array::{ArrayRef, StringBuilder};
+use arrow::array::{
+    ArrayRef, GenericStringBuilder, LargeStringArray, OffsetSizeTrait, StringArray,
+};
 use odbc_api::{
     buffers::{AnySlice, BufferDesc},
+    sys::SqlDataType,
     DataType as OdbcDataType,
 };
 
-use super::{ColumnFailure, MappingError, ReadStrategy};
+use crate::schema::is_sql_variant;
+
+use super::{
+    check_text_truncation, ColumnFailure, MappingError, ReadStrategy, StrategyOptions, ValueErrors,
+};
+
+/// Drivers report lengths like this, or larger, for text columns without an upper bound, e.g.
+/// `2^31 - 1` for `NVARCHAR(MAX)`. Allocating them for each row would exhaust the memory.
+const HUGE_TEXT_LENGTH: usize = 1 << 20;
+
+/// Buffer length for text columns with a reported length beyond [`HUGE_TEXT_LENGTH`], unless
+/// configured otherwise.
+const DEFAULT_HUGE_TEXT_BUFFER_LENGTH: usize = 8192;
+
+/// Converts narrow text, which is not valid UTF-8, into a string. E.g. by decoding it as
+/// windows-1252.
+#[derive(Clone)]
+pub struct DecodingFallback(pub Arc<DecodeFn>);
+
+type DecodeFn = dyn Fn(&[u8]) -> String + Send + Sync;
+
+impl std::fmt::Debug for DecodingFallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("DecodingFallback")
+    }
+}
+
+/// Encoding in which text columns are requested from the driver.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextEncoding {
+    /// UTF-16 on windows and UTF-8 on any other platform. On windows the system locale is usually
+    /// not UTF-8, while UTF-16 works with any driver.
+    #[default]
+    Auto,
+    /// Request narrow text and assume it to be UTF-8.
+    Utf8,
+    /// Request wide text (UTF-16).
+    Utf16,
+}
+
+impl TextEncoding {
+    /// Looks up the encoding for the current platform and data source in a user supplied table,
+    /// for deployments running on different platforms against different data sources.
+    ///
+    /// Each entry of `table` consists of a platform, as named by [`std::env::consts::OS`] (e.g.
+    /// `linux` or `windows`), the name of a database management system (see
+    /// [`odbc_api::Connection::database_management_system_name`]) and the encoding to use for
+    /// them. `*` matches any platform or database management system. The first matching entry
+    /// wins. Without a matching entry [`TextEncoding::Auto`] is returned.
+    pub fn lookup(table: &[(&str, &str, TextEncoding)], dbms_name: &str) -> TextEncoding {
+        let matches = |pattern: &str, value: &str| pattern == "*" || pattern == value;
+        table
+            .iter()
+            .find(|(os, dbms, _)| matches(os, std::env::consts::OS) && matches(dbms, dbms_name))
+            .map_or(TextEncoding::Auto, |&(_, _, encoding)| encoding)
+    }
+
+    /// `true` if text is requested as UTF-16.
+    pub(crate) fn is_wide(self) -> bool {
+        match self {
+            TextEncoding::Auto => cfg!(target_os = "windows"),
+            TextEncoding::Utf8 => false,
+            TextEncoding::Utf16 => true,
+        }
+    }
+}
+
+/// What to do with a column fetched as text, if the driver reports a size of zero for it and the
+/// maximum text size is not limited. E.g. for `VARCHAR(MAX)` or computed columns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ZeroSizedColumnPolicy {
+    /// Building the reader fails with [`ColumnFailure::ZeroSizedColumn`].
+    #[default]
+    Fail,
+    /// Allocate a buffer of this length for the column.
+    DefaultLength(usize),
+    /// Do not fetch the values of the column. It is emitted as all `NULL`, or all empty strings if
+    /// it is not nullable.
+    Null,
+}
 
 /// This function decides wether this column will be queried as narrow (assumed to be utf-8) or
 /// wide text (assumed to be utf-16). The reason we do not always use narrow is that the encoding
 /// dependends on the system locals which is usually not UTF-8 on windows systems. Furthermore we
 /// are trying to adapt the buffer size to the maximum string length the column could contain.
+///
+/// If `large` is `true` the strategy emits `LargeUtf8` arrays with 64 Bit offsets, rather than
+/// `Utf8` arrays.
 pub fn choose_text_strategy(
     sql_type: OdbcDataType,
-    lazy_display_size: impl FnMut() -> Result<isize, odbc_api::Error>,
-    max_text_size: Option<usize>,
+    mut lazy_display_size: impl FnMut() -> Result<isize, odbc_api::Error>,
+    nullable: bool,
+    large: bool,
+    options: &StrategyOptions,
 ) -> Result<Box<dyn ReadStrategy>, ColumnFailure> {
+    let max_text_size = options.buffer_allocation_options.max_text_size;
     let is_narrow = matches!(
         sql_type,
         OdbcDataType::LongVarchar { .. } | OdbcDataType::Varchar { .. } | OdbcDataType::Char { .. }
     );
     let is_wide = matches!(
         sql_type,
-        OdbcDataType::WVarchar { .. } | OdbcDataType::WChar { .. }
+        OdbcDataType::WVarchar { .. }
+            | OdbcDataType::WChar { .. }
+            | OdbcDataType::Other {
+                data_type: SqlDataType::EXT_W_LONG_VARCHAR,
+                ..
+            }
     );
-    let is_text = is_narrow || is_wide;
+    // The values of `sql_variant` columns have different types, which the driver converts into
+    // text. Like for text columns, the conversion may yield characters other than ASCII.
+    let is_text = is_narrow || is_wide || is_sql_variant(sql_type);
+    let huge_text_length = options
+        .huge_text_length
+        .unwrap_or(DEFAULT_HUGE_TEXT_BUFFER_LENGTH);
+    // `None` means the values of the column are not fetched.
     let apply_buffer_limit = |len| match (len, max_text_size) {
-        (0, None) => Err(ColumnFailure::ZeroSizedColumn { sql_type }),
-        (0, Some(limit)) => Ok(limit),
-        (len, None) => Ok(len),
-        (len, Some(limit)) => Ok(min(len, limit)),
+        (0, None) => match options.zero_sized_column_policy {
+            ZeroSizedColumnPolicy::Fail => Err(ColumnFailure::ZeroSizedColumn { sql_type }),
+            ZeroSizedColumnPolicy::DefaultLength(len) => Ok(Some(len)),
+            ZeroSizedColumnPolicy::Null => Ok(None),
+        },
+        (0, Some(limit)) => Ok(Some(limit)),
+        // The buffer needs room for a terminating zero.
+        (usize::MAX, None) => Err(ColumnFailure::ColumnSizeTooLarge { sql_type }),
+        // Values exceeding the buffer are reported as truncated, so users learn to raise it.
+        (len, None) if len > HUGE_TEXT_LENGTH => Ok(Some(min(len, huge_text_length))),
+        (len, None) => Ok(Some(len)),
+        (len, Some(limit)) => Ok(Some(min(len, limit))),
     };
+    let unfetched: Box<dyn ReadStrategy> = Box::new(UnfetchedText { nullable, large });
     let strategy = if is_text {
-        if cfg!(target_os = "windows") {
-            let hex_len = sql_type.utf16_len().unwrap();
-            let hex_len = apply_buffer_limit(hex_len)?;
-            wide_text_strategy(hex_len)
+        if options.text_encoding.is_wide() {
+            let hex_len = text_len(sql_type, 2)?;
+            let Some(hex_len) = apply_buffer_limit(hex_len)? else {
+                return Ok(unfetched);
+            };
+            wide_text_strategy(hex_len, nullable, options, large)
         } else {
-            let octet_len = sql_type.utf8_len().unwrap();
-            let octet_len = apply_buffer_limit(octet_len)?;
-            narrow_text_strategy(octet_len)
+            let octet_len = text_len(sql_type, 4)?;
+            let Some(octet_len) = apply_buffer_limit(octet_len)? else {
+                return Ok(unfetched);
+            };
+            narrow_text_strategy(octet_len, nullable, options, large, false)
         }
     } else {
-        let display_size: usize = sql_type
-            .display_size()
-            .map(|ds| Ok(ds as isize))
-            .unwrap_or_else(lazy_display_size)
-            .map_err(|source| ColumnFailure::UnknownStringLength { sql_type, source })?
-            .try_into()
-            .unwrap();
-
-        let display_size = apply_buffer_limit(display_size)?;
-
-        // We assume non text type colmuns to only consist of ASCII characters.
-        narrow_text_strategy(display_size)
+        if !is_fetchable_as_text(sql_type) {
+            return Err(ColumnFailure::UnsupportedSqlType { sql_type });
+        }
+        let display_size = match display_size(sql_type)? {
+            Some(display_size) => display_size,
+            None => match (lazy_display_size(), options.default_text_length) {
+                // Drivers may report a negative size (e.g. `SQL_NO_TOTAL`) if they do not know an
+                // upper bound. We treat it the same as a size of zero.
+                (Ok(display_size), default_text_length) => match display_size.try_into() {
+                    Ok(0) | Err(_) => default_text_length.unwrap_or(0),
+                    Ok(display_size) => display_size,
+                },
+                (Err(_), Some(default_text_length)) => default_text_length,
+                (Err(source), None) => {
+                    return Err(ColumnFailure::UnknownStringLength { sql_type, source })
+                }
+            },
+        };
+
+        let Some(display_size) = apply_buffer_limit(display_size)? else {
+            return Ok(unfetched);
+        };
+
+        // We assume non text type colmuns to only consist of ASCII characters. In strict mode the
+        // assumption is verified.
+        narrow_text_strategy(display_size, nullable, options, large, options.strict)
     };
 
     Ok(strategy)
 }
 
-fn wide_text_strategy(u16_len: usize) -> Box<dyn ReadStrategy> {
-    Box::new(WideText::new(u16_len))
+/// Maximum length of the text in a character column, in units of the encoding. `units_per_char` is
+/// `4` for UTF-8 and `2` for UTF-16. Equivalent to [`OdbcDataType::utf8_len`] and
+/// [`OdbcDataType::utf16_len`], yet reports column sizes overflowing `usize` as an error instead of
+/// panicking. This happens e.g. on 32 Bit platforms for drivers reporting very large sizes.
+fn text_len(sql_type: OdbcDataType, units_per_char: usize) -> Result<usize, ColumnFailure> {
+    let len = match sql_type {
+        OdbcDataType::LongVarchar { length } => Some(length),
+        OdbcDataType::Varchar { length }
+        | OdbcDataType::WVarchar { length }
+        | OdbcDataType::WChar { length }
+        | OdbcDataType::Char { length } => length.checked_mul(units_per_char),
+        // `odbc-api` has no variant for `SQL_WLONGVARCHAR` (e.g. `NTEXT`), but it is sized like
+        // any other wide text.
+        OdbcDataType::Other {
+            data_type: SqlDataType::EXT_W_LONG_VARCHAR,
+            column_size,
+            ..
+        } => column_size.checked_mul(units_per_char),
+        // Values of `sql_variant` columns are at most 8000 bytes, which is reported as column
+        // size. It is used as is, rather than assuming the worst case number of units for each of
+        // them, which would allocate buffers several times larger than needed for most values.
+        sql_type @ OdbcDataType::Other { column_size, .. } if is_sql_variant(sql_type) => {
+            Some(column_size)
+        }
+        _ => unreachable!("Only called for text columns"),
+    };
+    len.ok_or(ColumnFailure::ColumnSizeTooLarge { sql_type })
+}
+
+/// `false` for driver specific SQL types known to have no conversion into text. Binding a text
+/// buffer to them only fails once fetching the first batch. Other SQL types without a dedicated
+/// mapping are assumed to be convertible into text.
+fn is_fetchable_as_text(sql_type: OdbcDataType) -> bool {
+    // User defined CLR types of Microsoft SQL Server, e.g. `geometry` or `hierarchyid`, which
+    // only convert into binary.
+    const SS_UDT: SqlDataType = SqlDataType(-151);
+    // Table valued parameters of Microsoft SQL Server.
+    const SS_TABLE: SqlDataType = SqlDataType(-153);
+    !matches!(
+        sql_type,
+        OdbcDataType::Other {
+            data_type: SS_UDT | SS_TABLE,
+            ..
+        }
+    )
+}
+
+/// Equivalent to [`OdbcDataType::display_size`], yet reports sizes overflowing `usize` as an error
+/// instead of panicking.
+fn display_size(sql_type: OdbcDataType) -> Result<Option<usize>, ColumnFailure> {
+    match sql_type {
+        // Each binary byte is represented by a 2-digit hexadecimal number.
+        OdbcDataType::Varbinary { length }
+        | OdbcDataType::Binary { length }
+        | OdbcDataType::LongVarbinary { length } => length
+            .checked_mul(2)
+            .map(Some)
+            .ok_or(ColumnFailure::ColumnSizeTooLarge { sql_type }),
+        other => Ok(other.display_size()),
+    }
+}
+
+fn wide_text_strategy(
+    u16_len: usize,
+    nullable: bool,
+    options: &StrategyOptions,
+    large: bool,
+) -> Box<dyn ReadStrategy> {
+    let lossy = options.lossy_utf16;
+    let allow_truncation = options.allow_text_truncation();
+    let empty_as_null = options.empty_text_as_null;
+    if large {
+        Box::new(
+            LargeWideText::new(u16_len, nullable, lossy, allow_truncation)
+                .with_empty_as_null(empty_as_null),
+        )
+    } else {
+        Box::new(
+            WideText::<i32>::new(u16_len, nullable, lossy, allow_truncation)
+                .with_empty_as_null(empty_as_null),
+        )
+    }
+}
+
+/// `ascii_only` is `true` for columns without a text SQL type in strict mode.
+fn narrow_text_strategy(
+    octet_len: usize,
+    nullable: bool,
+    options: &StrategyOptions,
+    large: bool,
+    ascii_only: bool,
+) -> Box<dyn ReadStrategy> {
+    let decoding_fallback = options.decoding_fallback.clone();
+    let allow_truncation = options.allow_text_truncation();
+    let decoder = options.narrow_text_decoder.clone();
+    let empty_as_null = options.empty_text_as_null;
+    if large {
+        Box::new(
+            LargeNarrowText::new(octet_len, nullable, decoding_fallback, allow_truncation)
+                .with_decoder(decoder)
+                .with_empty_as_null(empty_as_null)
+                .with_ascii_only(ascii_only),
+        )
+    } else {
+        Box::new(
+            NarrowText::<i32>::new(octet_len, nullable, decoding_fallback, allow_truncation)
+                .with_decoder(decoder)
+                .with_empty_as_null(empty_as_null)
+                .with_ascii_only(ascii_only),
+        )
+    }
+}
+
+/// Emits a text column without fetching its values, see [`ZeroSizedColumnPolicy::Null`]. All values
+/// are `NULL`, or empty strings if the column is not nullable.
+pub struct UnfetchedText {
+    nullable: bool,
+    /// If `true` the emitted array is `LargeUtf8`, otherwise `Utf8`.
+    large: bool,
 }
 
-fn narrow_text_strategy(octet_len: usize) -> Box<dyn ReadStrategy> {
-    Box::new(NarrowText::new(octet_len))
+impl ReadStrategy for UnfetchedText {
+    fn buffer_desc(&self) -> BufferDesc {
+        // Smallest buffer, which can still be bound to a text column.
+        BufferDesc::Text { max_str_len: 0 }
+    }
+
+    fn fill_arrow_array(
+        &self,
+        column_view: AnySlice,
+        _errors: &ValueErrors,
+    ) -> Result<ArrayRef, MappingError> {
+        let num_rows = column_view.as_text_view().unwrap().len();
+        let value = (!self.nullable).then_some("");
+        let array: ArrayRef = if self.large {
+            Arc::new(LargeStringArray::from(vec![value; num_rows]))
+        } else {
+            Arc::new(StringArray::from(vec![value; num_rows]))
+        };
+        Ok(array)
+    }
 }
 
 /// Strategy requesting the text from the database as UTF-16 (Wide characters) and emmitting it as
 /// UTF-8. We use it, since the narrow representation in ODBC is not always guaranteed to be UTF-8,
-/// but depends on the local instead.
-pub struct WideText {
+/// but depends on the local instead. `O` is the offset type of the emitted array, `i32` for `Utf8`
+/// and `i64` for `LargeUtf8`.
+pub struct WideText<O = i32> {
     /// Maximum string length in u16, excluding terminating zero
     max_str_len: usize,
+    /// If `false` any `NULL` reported by the driver is a [`MappingError::UnexpectedNull`].
+    nullable: bool,
+    /// If `true` unpaired surrogates are replaced with `U+FFFD`, otherwise they are an error.
+    lossy: bool,
+    /// If `true` values exceeding `max_str_len` are truncated, otherwise they are an error.
+    allow_truncation: bool,
+    /// If `true` empty values become `NULL`, unless the column is not nullable.
+    empty_as_null: bool,
+    offset: PhantomData<O>,
 }
 
-impl WideText {
-    pub fn new(max_str_len: usize) -> Self {
-        Self { max_str_len }
+/// Emits `LargeUtf8` arrays, whose 64 Bit offsets do not overflow for batches holding more than
+/// 2 GiB of text.
+pub type LargeWideText = WideText<i64>;
+
+impl<O> WideText<O> {
+    pub fn new(max_str_len: usize, nullable: bool, lossy: bool, allow_truncation: bool) -> Self {
+        Self {
+            max_str_len,
+            nullable,
+            lossy,
+            allow_truncation,
+            empty_as_null: false,
+            offset: PhantomData,
+        }
+    }
+
+    /// Emit empty values as `NULL`, if the column is nullable.
+    pub fn with_empty_as_null(self, empty_as_null: bool) -> Self {
+        Self {
+            empty_as_null,
+            ..self
+        }
+    }
+
+    /// Replaces the contents of `buf_utf8` with `utf16`, the value of the row at `index`.
+    fn to_utf8(
+        &self,
+        utf16: &[u16],
+        index: usize,
+        buf_utf8: &mut String,
+    ) -> Result<(), MappingError> {
+        buf_utf8.clear();
+        // Fast path for values consisting only of ASCII, e.g. codes, which are the same in UTF-8.
+        if is_ascii(utf16) {
+            buf_utf8.extend(utf16.iter().map(|&unit| unit as u8 as char));
+            return Ok(());
+        }
+        for c in decode_utf16(utf16.iter().cloned()) {
+            let c = match c {
+                Ok(c) => c,
+                Err(_) if self.lossy => REPLACEMENT_CHARACTER,
+                Err(error) => {
+                    return Err(MappingError::InvalidUtf16 {
+                        code_unit: error.unpaired_surrogate(),
+                        index,
+                    })
+                }
+            };
+            buf_utf8.push(c);
+        }
+        Ok(())
     }
 }
 
-impl ReadStrategy for WideText {
+impl<O: OffsetSizeTrait> ReadStrategy for WideText<O> {
     fn buffer_desc(&self) -> BufferDesc {
         BufferDesc::WText {
             max_str_len: self.max_str_len,
         }
     }
 
-    fn fill_arrow_array(&self, column_view: AnySlice) -> Result<ArrayRef, MappingError> {
+    fn fill_arrow_array(
+        &self,
+        column_view: AnySlice,
+        errors: &ValueErrors,
+    ) -> Result<ArrayRef, MappingError> {
         let view = column_view.as_w_text_view().unwrap();
-        let item_capacity = view.len();
-        // Any utf-16 character could take up to 4 Bytes if represented as utf-8, but since mostly
-        // this is 1 to one, and also not every string is likeyl to use its maximum capacity, we
-        // rather accept the reallocation in these scenarios.
-        let data_capacity = self.max_str_len * item_capacity;
-        let mut builder = StringBuilder::with_capacity(item_capacity, data_capacity);
+        if !self.allow_truncation {
+            check_text_truncation(&view)?;
+        }
+        // Allocate exactly the memory needed for the values of this batch, so arrays of small or
+        // final batches do not hold on to the capacity for a full batch of maximum length values.
+        let data_capacity = view
+            .iter()
+            .map(|value| value.map_or(0, |utf16| utf8_len(utf16.as_slice())))
+            .sum();
+        let mut builder = GenericStringBuilder::<O>::with_capacity(view.len(), data_capacity);
         // Buffer used to convert individual values from utf16 to utf8.
         let mut buf_utf8 = String::new();
-        for value in view.iter() {
-            buf_utf8.clear();
-            let opt = if let Some(utf16) = value {
-                for c in decode_utf16(utf16.as_slice().iter().cloned()) {
-                    buf_utf8.push(c.unwrap());
+        if self.nullable {
+            for (index, value) in view.iter().enumerate() {
+                match value {
+                    Some(utf16) if self.empty_as_null && utf16.as_slice().is_empty() => {
+                        builder.append_null()
+                    }
+                    Some(utf16) => {
+                        let converted = self.to_utf8(utf16.as_slice(), index, &mut buf_utf8);
+                        match errors.recover(converted)? {
+                            Some(()) => builder.append_value(&buf_utf8),
+                            None => builder.append_null(),
+                        }
+                    }
+                    None => builder.append_null(),
                 }
-                Some(&buf_utf8)
-            } else {
-                None
-            };
-            builder.append_option(opt);
+            }
+        } else {
+            for index in 0..view.len() {
+                let converted = view
+                    .get(index)
+                    .ok_or(MappingError::UnexpectedNull { index })
+                    .and_then(|utf16| self.to_utf8(utf16, index, &mut buf_utf8));
+                match errors.recover(converted)? {
+                    Some(()) => builder.append_value(&buf_utf8),
+                    None => builder.append_null(),
+                }
+            }
         }
         Ok(Arc::new(builder.finish()))
     }
 }
 
-pub struct NarrowText {
+/// Length in bytes of `utf16` represented as utf-8. Unpaired surrogates count as `U+FFFD`.
+fn utf8_len(utf16: &[u16]) -> usize {
+    if is_ascii(utf16) {
+        return utf16.len();
+    }
+    decode_utf16(utf16.iter().cloned())
+        .map(|c| c.map_or(REPLACEMENT_CHARACTER.len_utf8(), char::len_utf8))
+        .sum()
+}
+
+/// `true` if all code units of `utf16` are ASCII characters.
+fn is_ascii(utf16: &[u16]) -> bool {
+    utf16.iter().all(|&unit| unit < 0x80)
+}
+
+/// Strategy requesting the text from the database as narrow text, assumed to be UTF-8. `O` is the
+/// offset type of the emitted array, `i32` for `Utf8` and `i64` for `LargeUtf8`.
+pub struct NarrowText<O = i32> {
     /// Maximum string length in u8, excluding terminating zero
     max_str_len: usize,
+    /// If `false` any `NULL` reported by the driver is a [`MappingError::UnexpectedNull`].
+    nullable: bool,
+    /// Applied to values which are not valid UTF-8. `None` means invalid values are reported as
+    /// [`MappingError::InvalidUtf8`].
+    decoding_fallback: Option<DecodingFallback>,
+    /// Decodes all values in place of UTF-8, e.g. for drivers returning text in the encoding of
+    /// the client locale. Takes precedence over `decoding_fallback`.
+    decoder: Option<DecodingFallback>,
+    /// If `true` values exceeding `max_str_len` are truncated, otherwise they are an error.
+    allow_truncation: bool,
+    /// If `true` empty values become `NULL`, unless the column is not nullable.
+    empty_as_null: bool,
+    /// If `true` values containing bytes other than ASCII are an error.
+    ascii_only: bool,
+    offset: PhantomData<O>,
 }
 
-impl NarrowText {
-    pub fn new(max_str_len: usize) -> Self {
-        Self { max_str_len }
+/// Emits `LargeUtf8` arrays, whose 64 Bit offsets do not overflow for batches holding more than
+/// 2 GiB of text.
+pub type LargeNarrowText = NarrowText<i64>;
+
+impl<O> NarrowText<O> {
+    pub fn new(
+        max_str_len: usize,
+        nullable: bool,
+        decoding_fallback: Option<DecodingFallback>,
+        allow_truncation: bool,
+    ) -> Self {
+        Self {
+            max_str_len,
+            nullable,
+            decoding_fallback,
+            decoder: None,
+            allow_truncation,
+            empty_as_null: false,
+            ascii_only: false,
+            offset: PhantomData,
+        }
+    }
+
+    /// Decode all values with `decoder`, rather than assuming them to be UTF-8.
+    pub fn with_decoder(self, decoder: Option<DecodingFallback>) -> Self {
+        Self { decoder, ..self }
+    }
+
+    /// Emit empty values as `NULL`, if the column is nullable.
+    pub fn with_empty_as_null(self, empty_as_null: bool) -> Self {
+        Self {
+            empty_as_null,
+            ..self
+        }
+    }
+
+    /// Report values which are not ASCII as errors, e.g. for columns without a text SQL type,
+    /// which are fetched as text assuming they only consist of ASCII characters.
+    pub fn with_ascii_only(self, ascii_only: bool) -> Self {
+        Self { ascii_only, ..self }
+    }
+
+    fn check_ascii<'a>(&self, bytes: &'a [u8]) -> Result<&'a [u8], MappingError> {
+        if self.ascii_only && !bytes.is_ascii() {
+            return Err(MappingError::NonAsciiText {
+                value: String::from_utf8_lossy(bytes).into_owned(),
+            });
+        }
+        Ok(bytes)
     }
 }
 
-impl ReadStrategy for NarrowText {
+impl<O: OffsetSizeTrait> ReadStrategy for NarrowText<O> {
     fn buffer_desc(&self) -> BufferDesc {
         BufferDesc::Text {
             max_str_len: self.max_str_len,
         }
     }
 
-    fn fill_arrow_array(&self, column_view: AnySlice) -> Result<ArrayRef, MappingError> {
+    fn fill_arrow_array(
+        &self,
+        column_view: AnySlice,
+        errors: &ValueErrors,
+    ) -> Result<ArrayRef, MappingError> {
         let view = column_view.as_text_view().unwrap();
-        let mut builder = StringBuilder::with_capacity(view.len(), self.max_str_len * view.len());
-        for value in view.iter() {
-            builder.append_option(value.map(|bytes| {
-                std::str::from_utf8(bytes)
-                    .expect("ODBC column had been expected to return valid utf8, but did not.")
-            }));
+        if !self.allow_truncation {
+            check_text_truncation(&view)?;
+        }
+        let data_capacity = view.iter().map(|value| value.map_or(0, <[u8]>::len)).sum();
+        let mut builder = GenericStringBuilder::<O>::with_capacity(view.len(), data_capacity);
+        let num_decoded_with_fallback = Cell::new(0);
+        // Decodes the value of the row at `index`.
+        let to_str = |index, bytes| -> Result<Cow<str>, MappingError> {
+            let bytes = self.check_ascii(bytes)?;
+            if let Some(DecodingFallback(decoder)) = &self.decoder {
+                return Ok(Cow::Owned(decoder(bytes)));
+            }
+            match (std::str::from_utf8(bytes), &self.decoding_fallback) {
+                (Ok(text), _) => Ok(Cow::Borrowed(text)),
+                (Err(_), Some(DecodingFallback(fallback))) => {
+                    num_decoded_with_fallback.set(num_decoded_with_fallback.get() + 1);
+                    Ok(Cow::Owned(fallback(bytes)))
+                }
+                (Err(_), None) => Err(MappingError::InvalidUtf8 { index }),
+            }
+        };
+        if self.nullable {
+            for (index, value) in view.iter().enumerate() {
+                let value = match value.filter(|bytes| !(self.empty_as_null && bytes.is_empty())) {
+                    Some(bytes) => errors.recover(to_str(index, bytes))?,
+                    None => None,
+                };
+                builder.append_option(value);
+            }
+        } else if !self.ascii_only && self.decoder.is_none() {
+            // Valid UTF-8 is appended right away. Only `NULL`s and invalid values need to go
+            // through the error handling.
+            for index in 0..view.len() {
+                let bytes = view.get(index);
+                if let Some(Ok(text)) = bytes.map(std::str::from_utf8) {
+                    builder.append_value(text);
+                    continue;
+                }
+                let text = bytes
+                    .ok_or(MappingError::UnexpectedNull { index })
+                    .and_then(|bytes| to_str(index, bytes));
+                match errors.recover(text)? {
+                    Some(text) => builder.append_value(text),
+                    None => builder.append_null(),
+                }
+            }
+        } else {
+            for index in 0..view.len() {
+                let text = view
+                    .get(index)
+                    .ok_or(MappingError::UnexpectedNull { index })
+                    .and_then(|bytes| to_str(index, bytes));
+                match errors.recover(text)? {
+                    Some(text) => builder.append_value(text),
+                    None => builder.append_null(),
+                }
+            }
+        }
+        if num_decoded_with_fallback.get() != 0 {
+            log::warn!(
+                "{} values of the batch are not valid UTF-8 and have been decoded using the \
+                fallback.",
+                num_decoded_with_fallback.get()
+            );
         }
         Ok(Arc::new(builder.finish()))
     }
@@ -0,0 +1,121 @@
+use std::sync::Arc;
+
+use arrow::{
+    array::{ArrayRef, PrimitiveArray},
+    datatypes::{
+        ArrowPrimitiveType, Time32MillisecondType, Time32SecondType, Time64MicrosecondType,
+        Time64NanosecondType, TimeUnit,
+    },
+};
+use odbc_api::{
+    buffers::{AnySlice, BufferDesc},
+    sys::SqlDataType,
+    DataType as OdbcDataType,
+};
+
+use crate::date_time::ns_since_midnight;
+
+use super::{check_text_truncation, ColumnFailure, MappingError, ReadStrategy, ValueErrors};
+
+/// Microsoft SQL Server reports its `TIME` type as a driver specific type.
+const SS_TIME2: SqlDataType = SqlDataType(-154);
+
+/// Number of fractional second digits of the time of day column, if `sql_type` describes one.
+pub fn time_precision(sql_type: OdbcDataType) -> Option<i16> {
+    match sql_type {
+        OdbcDataType::Time { precision } => Some(precision),
+        OdbcDataType::Other {
+            data_type: SS_TIME2,
+            column_size: _,
+            decimal_digits,
+        } => Some(decimal_digits),
+        _ => None,
+    }
+}
+
+/// Number of fractional second digits which can be represented with `unit`.
+fn precision_of(unit: &TimeUnit) -> i16 {
+    match unit {
+        TimeUnit::Second => 0,
+        TimeUnit::Millisecond => 3,
+        TimeUnit::Microsecond => 6,
+        TimeUnit::Nanosecond => 9,
+    }
+}
+
+/// Chooses a strategy filling an arrow time array with the given unit. `precision` is the number of
+/// fractional second digits reported for the column, if known.
+pub fn choose_time_strategy(
+    unit: TimeUnit,
+    precision: Option<i16>,
+    allow_truncation: bool,
+) -> Result<Box<dyn ReadStrategy>, ColumnFailure> {
+    if let Some(precision) = precision {
+        if !allow_truncation && precision > precision_of(&unit) {
+            return Err(ColumnFailure::TimeTruncation {
+                precision,
+                time_unit: unit,
+            });
+        }
+    }
+    let strategy: Box<dyn ReadStrategy> = match unit {
+        TimeUnit::Second => Box::new(TimeFromText::<Time32SecondType>::new(1_000_000_000)),
+        TimeUnit::Millisecond => Box::new(TimeFromText::<Time32MillisecondType>::new(1_000_000)),
+        TimeUnit::Microsecond => Box::new(TimeFromText::<Time64MicrosecondType>::new(1_000)),
+        TimeUnit::Nanosecond => Box::new(TimeFromText::<Time64NanosecondType>::new(1)),
+    };
+    Ok(strategy)
+}
+
+/// Fetches time of day values as text and parses them. `odbc-api` offers a buffer for `SQL_TIME`,
+/// but `SQL_TIME_STRUCT` has no fractional seconds.
+struct TimeFromText<P> {
+    /// Nanoseconds per unit of `P`. Fractions of a unit are truncated.
+    ns_per_unit: i64,
+    _phantom: std::marker::PhantomData<P>,
+}
+
+impl<P> TimeFromText<P> {
+    fn new(ns_per_unit: i64) -> Self {
+        Self {
+            ns_per_unit,
+            _phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<P> ReadStrategy for TimeFromText<P>
+where
+    P: ArrowPrimitiveType + Send + Sync,
+    P::Native: TryFrom<i64>,
+{
+    fn buffer_desc(&self) -> BufferDesc {
+        BufferDesc::Text {
+            // hh:mm:ss.fffffffff
+            max_str_len: 18,
+        }
+    }
+
+    fn fill_arrow_array(
+        &self,
+        column_view: AnySlice,
+        errors: &ValueErrors,
+    ) -> Result<ArrayRef, MappingError> {
+        let view = column_view.as_text_view().unwrap();
+        check_text_truncation(&view)?;
+        let values = view
+            .iter()
+            .map(|text| {
+                let Some(text) = text else { return Ok(None) };
+                // A time of day always fits into the native type of the arrow time arrays.
+                let time = ns_since_midnight(text)
+                    .and_then(|ns| (ns / self.ns_per_unit).try_into().ok())
+                    .ok_or_else(|| MappingError::InvalidTime {
+                        value: String::from_utf8_lossy(text).into_owned(),
+                    });
+                errors.recover(time)
+            })
+            .collect::<Result<Vec<Option<P::Native>>, _>>()?;
+        Ok(Arc::new(PrimitiveArray::<P>::from_iter(values)))
+    }
+}
@@ -0,0 +1,87 @@
+use std::sync::Arc;
+
+use arrow::{
+    array::{ArrayRef, PrimitiveArray},
+    datatypes::{
+        ArrowTimestampType, TimeUnit, TimestampMicrosecondType, TimestampMillisecondType,
+        TimestampNanosecondType, TimestampSecondType,
+    },
+};
+use odbc_api::{
+    buffers::{AnySlice, BufferDesc, TextColumnView},
+    sys::SqlDataType,
+    DataType as OdbcDataType,
+};
+
+use crate::date_time::since_epoch_of_offset_text;
+
+use super::{check_text_truncation, MappingError, ReadStrategy, ValueErrors};
+
+/// Microsoft SQL Server reports its `DATETIMEOFFSET` type as a driver specific type.
+const SS_TIMESTAMPOFFSET: SqlDataType = SqlDataType(-155);
+
+/// `true` if `sql_type` describes a timestamp with a time zone offset.
+pub fn is_timestamp_offset(sql_type: OdbcDataType) -> bool {
+    matches!(
+        sql_type,
+        OdbcDataType::Other {
+            data_type: SS_TIMESTAMPOFFSET,
+            ..
+        }
+    )
+}
+
+/// Fetches timestamps with a time zone offset as text and normalizes them to UTC. `odbc-api` offers
+/// no buffer for `SQL_SS_TIMESTAMPOFFSET_STRUCT`.
+pub struct TimestampOffsetFromText {
+    /// Unit of the emitted array.
+    unit: TimeUnit,
+    /// Time zone of the emitted array. The instants are normalized to UTC regardless, it only
+    /// affects how they are displayed.
+    time_zone: String,
+}
+
+impl TimestampOffsetFromText {
+    pub fn new(unit: TimeUnit, time_zone: String) -> Self {
+        Self { unit, time_zone }
+    }
+
+    fn timestamps<T: ArrowTimestampType>(
+        &self,
+        view: &TextColumnView<u8>,
+        errors: &ValueErrors,
+    ) -> Result<ArrayRef, MappingError> {
+        let values = view
+            .iter()
+            .map(|text| match text {
+                Some(text) => errors.recover(since_epoch_of_offset_text(text, &self.unit)),
+                None => Ok(None),
+            })
+            .collect::<Result<PrimitiveArray<T>, _>>()?;
+        Ok(Arc::new(values.with_timezone(self.time_zone.clone())))
+    }
+}
+
+impl ReadStrategy for TimestampOffsetFromText {
+    fn buffer_desc(&self) -> BufferDesc {
+        BufferDesc::Text {
+            // yyyy-mm-dd hh:mm:ss.fffffff +hh:mm
+            max_str_len: 34,
+        }
+    }
+
+    fn fill_arrow_array(
+        &self,
+        column_view: AnySlice,
+        errors: &ValueErrors,
+    ) -> Result<ArrayRef, MappingError> {
+        let view = column_view.as_text_view().unwrap();
+        check_text_truncation(&view)?;
+        match self.unit {
+            TimeUnit::Second => self.timestamps::<TimestampSecondType>(&view, errors),
+            TimeUnit::Millisecond => self.timestamps::<TimestampMillisecondType>(&view, errors),
+            TimeUnit::Microsecond => self.timestamps::<TimestampMicrosecondType>(&view, errors),
+            TimeUnit::Nanosecond => self.timestamps::<TimestampNanosecondType>(&view, errors),
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, StringArray};
+use odbc_api::buffers::{AnySlice, BufferDesc};
+
+use super::{MappingError, ReadStrategy, ValueErrors};
+
+/// Removes the trailing spaces fixed size `CHAR` columns are padded with, after the inner strategy
+/// filled the array.
+pub struct TrimTrailingSpaces {
+    inner: Box<dyn ReadStrategy>,
+}
+
+impl TrimTrailingSpaces {
+    /// `inner` must emit `Utf8` arrays.
+    pub fn new(inner: Box<dyn ReadStrategy>) -> Self {
+        Self { inner }
+    }
+}
+
+impl ReadStrategy for TrimTrailingSpaces {
+    fn buffer_desc(&self) -> BufferDesc {
+        self.inner.buffer_desc()
+    }
+
+    fn fill_arrow_array(
+        &self,
+        column_view: AnySlice,
+        errors: &ValueErrors,
+    ) -> Result<ArrayRef, MappingError> {
+        let padded = self.inner.fill_arrow_array(column_view, errors)?;
+        let padded = padded.as_any().downcast_ref::<StringArray>().unwrap();
+        let trimmed: StringArray = padded
+            .iter()
+            .map(|value| value.map(|value| value.trim_end_matches(' ')))
+            .collect();
+        Ok(Arc::new(trimmed))
+    }
+}
@@ -0,0 +1,149 @@
+use std::{marker::PhantomData, sync::Arc};
+
+use arrow::{
+    array::{ArrayRef, PrimitiveBuilder},
+    datatypes::{
+        ArrowPrimitiveType, DataType as ArrowDataType, Field, Int16Type, Int32Type, Int64Type,
+        Int8Type, UInt16Type, UInt32Type, UInt64Type, UInt8Type,
+    },
+};
+use atoi::FromRadix10Checked;
+use odbc_api::{
+    buffers::{AnySlice, BufferDesc},
+    DataType as OdbcDataType, ResultSetMetadata,
+};
+
+use super::{
+    check_text_truncation,
+    map_odbc_to_arrow::{narrow_integer, MapOdbcToArrow},
+    ColumnFailure, MappingError, ReadStrategy, ValueErrors,
+};
+
+/// Chooses the strategy for an integer column, whose field has the type all integers are unified
+/// to (see [`crate::OdbcReaderBuilder::with_unified_integers`]). The column is bound to a buffer of
+/// its own width, rather than one of the field type, and its values are converted. Values which do
+/// not fit the field type are reported as [`MappingError::IntegerOutOfRange`], rather than failing
+/// the fetch. `None` if the column does not have an integer SQL type.
+pub fn choose_unified_integer_strategy(
+    field: &Field,
+    query_metadata: &mut impl ResultSetMetadata,
+    col_index: u16,
+) -> Result<Option<Box<dyn ReadStrategy>>, ColumnFailure> {
+    let sql_type = query_metadata
+        .col_data_type(col_index)
+        .map_err(ColumnFailure::FailedToDescribeColumn)?;
+    if !matches!(
+        sql_type,
+        OdbcDataType::TinyInt
+            | OdbcDataType::SmallInt
+            | OdbcDataType::Integer
+            | OdbcDataType::BigInt
+    ) {
+        return Ok(None);
+    }
+    let unsigned = query_metadata
+        .column_is_unsigned(col_index)
+        .unwrap_or(false);
+    let nullable = field.is_nullable();
+    let strategy = match field.data_type() {
+        ArrowDataType::Int8 => from_integer_column::<Int8Type>(sql_type, unsigned, nullable),
+        ArrowDataType::Int16 => from_integer_column::<Int16Type>(sql_type, unsigned, nullable),
+        ArrowDataType::Int32 => from_integer_column::<Int32Type>(sql_type, unsigned, nullable),
+        ArrowDataType::Int64 => from_integer_column::<Int64Type>(sql_type, unsigned, nullable),
+        ArrowDataType::UInt8 => from_integer_column::<UInt8Type>(sql_type, unsigned, nullable),
+        ArrowDataType::UInt16 => from_integer_column::<UInt16Type>(sql_type, unsigned, nullable),
+        ArrowDataType::UInt32 => from_integer_column::<UInt32Type>(sql_type, unsigned, nullable),
+        ArrowDataType::UInt64 => from_integer_column::<UInt64Type>(sql_type, unsigned, nullable),
+        _ => return Ok(None),
+    };
+    Ok(Some(strategy))
+}
+
+/// Binds a buffer matching the width of `sql_type`. ODBC has no buffers for unsigned integers wider
+/// than 8 Bits, so these are fetched into the next wider signed integer, or as text.
+fn from_integer_column<T>(
+    sql_type: OdbcDataType,
+    unsigned: bool,
+    nullable: bool,
+) -> Box<dyn ReadStrategy>
+where
+    T: ArrowPrimitiveType + Send + Sync,
+    T::Native:
+        TryFrom<i8> + TryFrom<i16> + TryFrom<i32> + TryFrom<i64> + TryFrom<u8> + TryFrom<u64>,
+{
+    match (sql_type, unsigned) {
+        (OdbcDataType::TinyInt, false) => {
+            T::map_with(nullable, |&value: &i8| narrow_integer(value, T::DATA_TYPE))
+        }
+        (OdbcDataType::TinyInt, true) => {
+            T::map_with(nullable, |&value: &u8| narrow_integer(value, T::DATA_TYPE))
+        }
+        (OdbcDataType::SmallInt, false) => {
+            T::map_with(nullable, |&value: &i16| narrow_integer(value, T::DATA_TYPE))
+        }
+        (OdbcDataType::SmallInt, true) | (OdbcDataType::Integer, false) => {
+            T::map_with(nullable, |&value: &i32| narrow_integer(value, T::DATA_TYPE))
+        }
+        (OdbcDataType::Integer, true) | (OdbcDataType::BigInt, false) => {
+            T::map_with(nullable, |&value: &i64| narrow_integer(value, T::DATA_TYPE))
+        }
+        _ => Box::new(UnsignedBigIntFromText::<T>::new()),
+    }
+}
+
+/// Fetches unsigned 64 Bit integers in their text representation, like
+/// [`super::UInt64FromText`], and converts them into `T`.
+struct UnsignedBigIntFromText<T> {
+    _integer_type: PhantomData<T>,
+}
+
+impl<T> UnsignedBigIntFromText<T> {
+    fn new() -> Self {
+        Self {
+            _integer_type: PhantomData,
+        }
+    }
+}
+
+impl<T> ReadStrategy for UnsignedBigIntFromText<T>
+where
+    T: ArrowPrimitiveType + Send + Sync,
+    T::Native: TryFrom<u64>,
+{
+    fn buffer_desc(&self) -> BufferDesc {
+        BufferDesc::Text {
+            // 20 digits of u64::MAX and a sign, so negative values are reported, rather than being
+            // truncated.
+            max_str_len: 21,
+        }
+    }
+
+    fn fill_arrow_array(
+        &self,
+        column_view: AnySlice,
+        errors: &ValueErrors,
+    ) -> Result<ArrayRef, MappingError> {
+        let view = column_view.as_text_view().unwrap();
+        check_text_truncation(&view)?;
+        let mut builder = PrimitiveBuilder::<T>::with_capacity(view.len());
+        for text in view.iter() {
+            let value = match text.map(<[u8]>::trim_ascii) {
+                Some(text) => {
+                    let value = match u64::from_radix_10_checked(text) {
+                        (Some(value), consumed) if consumed == text.len() => {
+                            narrow_integer(value, T::DATA_TYPE)
+                        }
+                        _ => Err(MappingError::integer_out_of_range(
+                            String::from_utf8_lossy(text),
+                            T::DATA_TYPE,
+                        )),
+                    };
+                    errors.recover(value)?
+                }
+                None => None,
+            };
+            builder.append_option(value);
+        }
+        Ok(Arc::new(builder.finish()))
+    }
+}
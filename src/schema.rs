@@ -1,14 +1,51 @@
-use std::convert::TryInto;
+use std::{collections::HashMap, convert::TryInto, ptr::null_mut};
 
 use arrow::datatypes::{DataType as ArrowDataType, Field, Schema, TimeUnit};
-use odbc_api::{ColumnDescription, DataType as OdbcDataType, ResultSetMetadata};
+use odbc_api::{
+    handles::{SqlResult, Statement},
+    sys::{Desc, Pointer, SQLColAttributeW, SqlDataType, SqlReturn},
+    ColumnDescription, DataType as OdbcDataType, ResultSetMetadata,
+};
 
-use crate::{ColumnFailure, Error};
+use crate::{
+    read_strategy::{interval_unit, is_timestamp_offset, time_precision},
+    ColumnFailure, Error,
+};
 
 /// Query the metadata to create an arrow schema. This method is invoked automatically for you by
 /// [`crate::OdbcReader::new`]. You may want to call this method in situtation ther you want to
 /// create an arrow schema without creating the reader yet.
 ///
+/// Columns of the legacy `datetime` type of Microsoft SQL Server are represented as
+/// `Timestamp(Millisecond)`, even if the driver reports a higher precision for them.
+///
+/// Values of `Decimal128` fields are normalized to the scale of the field, padding fractional
+/// digits the data source omitted with zeroes. This accounts for Oracle `NUMBER` values, whose
+/// scale varies from value to value. For `NUMBER` columns without a declared scale, use
+/// [`crate::OdbcReaderBuilder::with_type_rule`] to configure one. Fractional digits beyond the
+/// scale are rounded half away from zero, like arrow does casting text to decimals. The
+/// normalization happens while parsing the text of each value, so it requires no extra pass over
+/// the batch.
+///
+/// Time of day columns are represented with the coarsest arrow time unit holding all of their
+/// fractional seconds. E.g. `TIME(0)` as `Time32(Second)`, `TIME(3)` as `Time32(Millisecond)`,
+/// `TIME(6)` as `Time64(Microsecond)` and `TIME(7)` as `Time64(Nanosecond)`.
+///
+/// Columns of type `DATETIMEOFFSET` (Microsoft SQL Server) are represented as timestamps with the
+/// time zone `UTC`. Their values are normalized to UTC using the offset of each value. Like for
+/// timestamps without time zone, the time unit is the coarsest one holding all fractional seconds
+/// of the column, e.g. `Millisecond` for `DATETIMEOFFSET(3)` and `Nanosecond` for
+/// `DATETIMEOFFSET(7)`.
+///
+/// Columns of type `sql_variant` (Microsoft SQL Server) are fetched as text. Their fields carry the
+/// metadata entry `arrow_odbc.sql_type` with the value `sql_variant`, so consumers know that the
+/// values originally had different types.
+///
+/// Fields of columns the driver reports as auto incrementing (`SQL_DESC_AUTO_UNIQUE_VALUE`), e.g.
+/// identity columns, carry the metadata entry `arrow_odbc.auto_increment` with the value `true`.
+/// Not all drivers report this attribute, so its absence does not imply the column is not auto
+/// incrementing.
+///
 /// # Example
 ///
 /// ```
@@ -68,6 +105,11 @@ pub fn arrow_schema_from(resut_set_metadata: &mut impl ResultSetMetadata) -> Res
                     ArrowDataType::Float64
                 }
                 OdbcDataType::Date => ArrowDataType::Date32,
+                OdbcDataType::Timestamp { .. }
+                    if is_legacy_datetime(resut_set_metadata, index + 1) =>
+                {
+                    ArrowDataType::Timestamp(TimeUnit::Millisecond, None)
+                }
                 OdbcDataType::Timestamp { precision: 0 } => {
                     ArrowDataType::Timestamp(TimeUnit::Second, None)
                 }
@@ -88,8 +130,28 @@ pub fn arrow_schema_from(resut_set_metadata: &mut impl ResultSetMetadata) -> Res
                 }
                 OdbcDataType::LongVarbinary { length: _ }
                 | OdbcDataType::Varbinary { length: _ } => ArrowDataType::Binary,
-                OdbcDataType::Unknown
-                | OdbcDataType::Time { precision: _ }
+                sql_type if time_precision(sql_type).is_some() => {
+                    match time_precision(sql_type).unwrap() {
+                        i16::MIN..=0 => ArrowDataType::Time32(TimeUnit::Second),
+                        1..=3 => ArrowDataType::Time32(TimeUnit::Millisecond),
+                        4..=6 => ArrowDataType::Time64(TimeUnit::Microsecond),
+                        _ => ArrowDataType::Time64(TimeUnit::Nanosecond),
+                    }
+                }
+                sql_type if interval_unit(sql_type).is_some() => {
+                    ArrowDataType::Interval(interval_unit(sql_type).unwrap())
+                }
+                sql_type if is_timestamp_offset(sql_type) => {
+                    let unit = match sql_type.decimal_digits() {
+                        i16::MIN..=0 => TimeUnit::Second,
+                        1..=3 => TimeUnit::Millisecond,
+                        4..=6 => TimeUnit::Microsecond,
+                        _ => TimeUnit::Nanosecond,
+                    };
+                    ArrowDataType::Timestamp(unit, Some("UTC".into()))
+                }
+                OdbcDataType::Unknown => ArrowDataType::Null,
+                OdbcDataType::Time { precision: _ }
                 | OdbcDataType::Numeric { .. }
                 | OdbcDataType::Decimal { .. }
                 | OdbcDataType::Other {
@@ -103,10 +165,137 @@ pub fn arrow_schema_from(resut_set_metadata: &mut impl ResultSetMetadata) -> Res
                 | OdbcDataType::LongVarchar { length: _ }
                 | OdbcDataType::Varchar { length: _ } => ArrowDataType::Utf8,
             },
-            column_description.could_be_nullable(),
+            // An arrow `Null` column consists of nothing but nulls.
+            column_description.could_be_nullable()
+                || column_description.data_type == OdbcDataType::Unknown,
         );
+        let mut metadata = HashMap::new();
+        if is_sql_variant(column_description.data_type) {
+            metadata.insert("arrow_odbc.sql_type".to_owned(), "sql_variant".to_owned());
+        }
+        if is_auto_increment(resut_set_metadata, index + 1) {
+            metadata.insert("arrow_odbc.auto_increment".to_owned(), "true".to_owned());
+        }
+        let field = if metadata.is_empty() {
+            field
+        } else {
+            field.with_metadata(metadata)
+        };
 
         fields.push(field)
     }
     Ok(Schema::new(fields))
 }
+
+/// `true` if the column has the legacy `datetime` type of Microsoft SQL Server, as opposed to
+/// `datetime2`. Its resolution of 3.33 milliseconds and its range from 1753 to 9999 is always
+/// representable in milliseconds, regardless of the precision reported by the driver.
+fn is_legacy_datetime(resut_set_metadata: &mut impl ResultSetMetadata, column_number: u16) -> bool {
+    type_name(resut_set_metadata, column_number).as_deref() == Some("datetime")
+}
+
+/// `true` if the column has the `rowversion` type of Microsoft SQL Server, which the driver names
+/// by its deprecated synonym `timestamp`. Despite the name its values are not points in time, but
+/// counters, reported as `BINARY(8)`.
+pub(crate) fn is_rowversion(
+    resut_set_metadata: &mut impl ResultSetMetadata,
+    column_number: u16,
+    sql_type: OdbcDataType,
+) -> bool {
+    sql_type == OdbcDataType::Binary { length: 8 }
+        && type_name(resut_set_metadata, column_number).as_deref() == Some("timestamp")
+}
+
+/// Data source specific name of the column type (`SQL_DESC_TYPE_NAME`), e.g. `datetime2`. `None` if
+/// the driver fails to report it.
+pub(crate) fn type_name(
+    resut_set_metadata: &mut impl ResultSetMetadata,
+    column_number: u16,
+) -> Option<String> {
+    let stmt = resut_set_metadata.as_stmt_ref();
+    let mut buf = [0u16; 128];
+    let mut len_in_bytes: i16 = 0;
+    // Safety: The buffer is valid for the duration of the call and its length is passed in bytes.
+    let ret = unsafe {
+        SQLColAttributeW(
+            stmt.as_sys(),
+            column_number,
+            Desc::TypeName,
+            buf.as_mut_ptr() as Pointer,
+            (buf.len() * 2).try_into().unwrap(),
+            &mut len_in_bytes,
+            null_mut(),
+        )
+    };
+    if ret != SqlReturn::SUCCESS {
+        return None;
+    }
+    let len = (len_in_bytes.max(0) as usize / 2).min(buf.len());
+    String::from_utf16(&buf[..len]).ok()
+}
+
+/// `true` if the driver reports the column as auto incrementing (`SQL_DESC_AUTO_UNIQUE_VALUE`).
+/// Drivers which do not support the attribute, or fail to report it, yield `false`.
+fn is_auto_increment(resut_set_metadata: &mut impl ResultSetMetadata, column_number: u16) -> bool {
+    let stmt = resut_set_metadata.as_stmt_ref();
+    // Safety: `AutoUniqueValue` is a numeric attribute.
+    match unsafe { stmt.numeric_col_attribute(Desc::AutoUniqueValue, column_number) } {
+        SqlResult::Success(value) | SqlResult::SuccessWithInfo(value) => value != 0,
+        _ => false,
+    }
+}
+
+/// `true` if the driver reports the column as unsigned. Only meaningful for numeric columns.
+pub(crate) fn is_unsigned(
+    resut_set_metadata: &mut impl ResultSetMetadata,
+    column_number: u16,
+) -> bool {
+    resut_set_metadata
+        .column_is_unsigned(column_number)
+        .unwrap_or(false)
+}
+
+/// Microsoft SQL Server reports `sql_variant` columns with a driver specific type.
+pub(crate) fn is_sql_variant(data_type: OdbcDataType) -> bool {
+    const SS_VARIANT: SqlDataType = SqlDataType(-150);
+    matches!(
+        data_type,
+        OdbcDataType::Other {
+            data_type: SS_VARIANT,
+            ..
+        }
+    )
+}
+
+/// Metadata key used by the parquet crate (and Apache Iceberg) to identify fields independent of
+/// their name or position.
+const PARQUET_FIELD_ID: &str = "PARQUET:field_id";
+
+/// How to assign parquet field ids to the fields of the schema emitted by the reader. Downstream
+/// parquet writers preserve the ids, which makes schema evolution in e.g. Apache Iceberg tables
+/// track columns across renames and reorderings.
+///
+/// Use [`crate::OdbcReaderBuilder::with_field_ids`] to assign them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldIds {
+    /// The id of each field is its one based column position in the result set.
+    Positional,
+    /// Ids are looked up by column name. Fields of columns not in the map do not get an id.
+    ByName(HashMap<String, i32>),
+}
+
+impl FieldIds {
+    /// Adds the `PARQUET:field_id` metadata entry to the field at `index`.
+    pub(crate) fn assign(&self, field: Field, index: usize) -> Field {
+        let id = match self {
+            FieldIds::Positional => Some(index as i32 + 1),
+            FieldIds::ByName(ids) => ids.get(field.name()).copied(),
+        };
+        let Some(id) = id else {
+            return field;
+        };
+        let mut metadata = field.metadata().clone();
+        metadata.insert(PARQUET_FIELD_ID.to_owned(), id.to_string());
+        field.with_metadata(metadata)
+    }
+}